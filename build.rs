@@ -0,0 +1,29 @@
+//! Generates the man pages of connectctl in `$OUT_DIR/man`, or in `$CONNECTCTL_MAN_DIR` when set
+
+#[cfg(feature = "cli")]
+#[allow(dead_code)]
+mod cli {
+    include!("src/bin/connectctl/cli.rs");
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/bin/connectctl/cli.rs");
+    println!("cargo:rerun-if-env-changed=CONNECTCTL_MAN_DIR");
+    #[cfg(feature = "cli")]
+    if let Err(e) = man_pages() {
+        println!("cargo:warning=Could not generate the man pages: {}", e);
+    }
+}
+
+#[cfg(feature = "cli")]
+fn man_pages() -> std::io::Result<()> {
+    use clap::CommandFactory;
+    use std::path::PathBuf;
+
+    let dir = match std::env::var_os("CONNECTCTL_MAN_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("OUT_DIR").unwrap_or_default()).join("man"),
+    };
+    std::fs::create_dir_all(&dir)?;
+    clap_mangen::generate_to(cli::Cli::command(), dir)
+}
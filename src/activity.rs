@@ -0,0 +1,253 @@
+//! Recent HTTP interactions of a client, to dump when a workflow fails
+//! A client built with an [`ActivityRecorder`] keeps its last requests, every attempt and
+//! redirect included, in a ring buffer read with [`Connect::recent_activity`]. Bodies are kept up
+//! to a limit, and the values of the keys the installed redactor considers secrets are masked when
+//! reading them. Headers are not kept, as they carry the credentials. Response bodies are captured
+//! as the caller reads them, so a body not read yet shows as empty
+use crate::redact::{is_secret_key, MASK};
+use crate::Connect;
+use async_trait::async_trait;
+use futures::StreamExt;
+use http::Extensions;
+use regex::{Captures, Regex};
+use reqwest::{Request, Response, ResponseBuilderExt};
+use reqwest_middleware::{Middleware, Next};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A string value in JSON, possibly cut by the truncation of the body
+static STRING_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""((?:[^"\\]|\\.)*)"(\s*:\s*)"((?:[^"\\]|\\.)*)("|$)"#).unwrap());
+
+/// Masks the string values of the secret keys, in bodies that may be truncated
+fn redact_body(body: &[u8]) -> String {
+    let body = String::from_utf8_lossy(body);
+    STRING_FIELD
+        .replace_all(&body, |captures: &Captures| {
+            if is_secret_key(&captures[1]) {
+                format!(
+                    "\"{}\"{}\"{}{}",
+                    &captures[1], &captures[2], MASK, &captures[4]
+                )
+            } else {
+                captures[0].to_string()
+            }
+        })
+        .into_owned()
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_millis())
+}
+
+/// A request and what came back, bodies having their secrets masked
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Interaction {
+    /// Milliseconds since the epoch when the request was sent
+    pub started_at: u64,
+    pub method: String,
+    pub url: String,
+    /// Unset when the request failed without a response
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    /// Time until the headers of the response
+    #[serde(rename = "latency_ms", serialize_with = "serialize_millis")]
+    pub latency: Duration,
+    pub request_body: Option<String>,
+    pub response_body: Option<String>,
+    /// Whether a body was cut at the limit of the recorder
+    pub truncated: bool,
+}
+
+impl fmt::Display for Interaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+        match (&self.status, &self.error) {
+            (Some(status), _) => write!(f, " -> {}", status)?,
+            (None, Some(error)) => write!(f, " -> {}", error)?,
+            (None, None) => {}
+        }
+        write!(f, " in {}ms", self.latency.as_millis())
+    }
+}
+
+/// An interaction as recorded, the response body growing as it is read
+#[derive(Debug)]
+struct Recorded {
+    started_at: u64,
+    method: String,
+    url: String,
+    status: Option<u16>,
+    error: Option<String>,
+    latency: Duration,
+    request_body: Option<Vec<u8>>,
+    response_body: Option<Vec<u8>>,
+    truncated: bool,
+}
+
+impl Recorded {
+    fn interaction(&self) -> Interaction {
+        Interaction {
+            started_at: self.started_at,
+            method: self.method.clone(),
+            url: self.url.clone(),
+            status: self.status,
+            error: self.error.clone(),
+            latency: self.latency,
+            request_body: self.request_body.as_deref().map(redact_body),
+            response_body: self.response_body.as_deref().map(redact_body),
+            truncated: self.truncated,
+        }
+    }
+}
+
+/// Ring buffer of the last interactions of a client. Clones share the same buffer
+#[derive(Clone, Debug)]
+pub struct ActivityRecorder {
+    capacity: usize,
+    max_body: usize,
+    recorded: Arc<Mutex<VecDeque<Arc<Mutex<Recorded>>>>>,
+}
+
+impl ActivityRecorder {
+    /// Keeps the last `capacity` interactions, with up to 4 KiB of each body
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            max_body: 4096,
+            recorded: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// Bytes of each body kept
+    pub fn max_body(mut self, max_body: usize) -> Self {
+        self.max_body = max_body;
+        self
+    }
+
+    /// Interactions recorded, the oldest first
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.recorded
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|recorded| recorded.lock().unwrap().interaction())
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.recorded.lock().unwrap().clear();
+    }
+
+    fn push(&self, recorded: Recorded) -> Arc<Mutex<Recorded>> {
+        let recorded = Arc::new(Mutex::new(recorded));
+        let mut buffer = self.recorded.lock().unwrap();
+        if self.capacity == 0 {
+            return recorded;
+        }
+        if buffer.len() == self.capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(recorded.clone());
+        recorded
+    }
+}
+
+/// Keeps up to `max` bytes of the chunk, returning whether some were left out
+fn capture(body: &mut Vec<u8>, chunk: &[u8], max: usize) -> bool {
+    let kept = chunk.len().min(max.saturating_sub(body.len()));
+    body.extend_from_slice(&chunk[..kept]);
+    kept < chunk.len()
+}
+
+/// Same response, its body being captured as it is read
+fn recording_body(
+    response: Response,
+    recorded: Arc<Mutex<Recorded>>,
+    max: usize,
+) -> reqwest_middleware::Result<Response> {
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version())
+        .url(response.url().clone());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+    recorded.lock().unwrap().response_body = Some(Vec::new());
+    let stream = response.bytes_stream().inspect(move |chunk| {
+        if let Ok(chunk) = chunk {
+            let mut recorded = recorded.lock().unwrap();
+            let Recorded {
+                response_body,
+                truncated,
+                ..
+            } = &mut *recorded;
+            if let Some(body) = response_body {
+                *truncated |= capture(body, chunk, max);
+            }
+        }
+    });
+    let response = builder
+        .body(reqwest::Body::wrap_stream(stream))
+        .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+    Ok(Response::from(response))
+}
+
+/// Records every attempt, so it runs after the retries and the redirects
+pub(crate) struct Recording(pub(crate) ActivityRecorder);
+
+#[async_trait]
+impl Middleware for Recording {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let mut truncated = false;
+        let request_body = req.body().and_then(|body| body.as_bytes()).map(|bytes| {
+            let mut body = Vec::new();
+            truncated = capture(&mut body, bytes, self.0.max_body);
+            body
+        });
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        let method = req.method().to_string();
+        let url = req.url().to_string();
+        let started = Instant::now();
+        let result = next.run(req, extensions).await;
+        let recorded = self.0.push(Recorded {
+            started_at,
+            method,
+            url,
+            status: result
+                .as_ref()
+                .ok()
+                .map(|response| response.status().as_u16()),
+            error: result.as_ref().err().map(ToString::to_string),
+            latency: started.elapsed(),
+            request_body,
+            response_body: None,
+            truncated,
+        });
+        recording_body(result?, recorded, self.0.max_body)
+    }
+}
+
+impl Connect {
+    /// Last interactions of the client, empty unless built with an [`ActivityRecorder`]
+    pub fn recent_activity(&self) -> Vec<Interaction> {
+        self.activity
+            .as_ref()
+            .map(ActivityRecorder::interactions)
+            .unwrap_or_default()
+    }
+}
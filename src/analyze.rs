@@ -0,0 +1,372 @@
+//! Review of the practices across the connectors of a cluster
+//! [`Connect::analyze_cluster`] reads every connector once and reports:
+//! - connectors running a single task while reading or writing busy topics
+//! - connectors of the same class pointing at the same topic or table
+//! - converters differing between the source writing a topic and the sinks reading it
+//! - connectors failed for longer than a threshold
+//!
+//! The REST API does not tell how busy a topic is nor since when a connector failed. Busy topics
+//! come from the options, from broker metrics for instance. Failures are timed by the
+//! [`FailureLog`], which remembers when each failure was first seen, so the rule only fires
+//! across runs sharing the log, such as a periodic job persisting it
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::models::{Connector, Status};
+use crate::Connect;
+use anyhow::Result;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CONCURRENCY: usize = 8;
+
+/// Keys naming the tables a source captures, paired with the keys of the database they live in
+const TABLE_KEYS: &[&str] = &[
+    "table.whitelist",
+    "table.include.list",
+    "collection.include.list",
+];
+const DATABASE_KEYS: &[&str] = &[
+    "connection.url",
+    "database.hostname",
+    "mongodb.connection.string",
+];
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// When the failures of the connectors were first seen, in seconds since the epoch. Clones share
+/// the same log, which serializes to be kept between runs
+#[derive(Clone, Debug, Default)]
+pub struct FailureLog {
+    failed_since: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl Serialize for FailureLog {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.failed_since.lock().unwrap().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FailureLog {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        Ok(Self {
+            failed_since: Arc::new(Mutex::new(BTreeMap::deserialize(deserializer)?)),
+        })
+    }
+}
+
+impl FailureLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// When the failure of the connector was first seen, `None` when it was not failed last run
+    pub fn failed_since(&self, connector: &str) -> Option<u64> {
+        self.failed_since.lock().unwrap().get(connector).copied()
+    }
+
+    /// Records the connectors failed now, forgetting the others. Returns for how long each failed
+    fn update(&self, failed: &BTreeSet<&str>, now: u64) -> BTreeMap<String, Duration> {
+        let mut failed_since = self.failed_since.lock().unwrap();
+        failed_since.retain(|connector, _| failed.contains(connector.as_str()));
+        failed
+            .iter()
+            .map(|connector| {
+                let since = *failed_since.entry(connector.to_string()).or_insert(now);
+                (
+                    connector.to_string(),
+                    Duration::from_secs(now.saturating_sub(since)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnalyzeOptions {
+    /// Topics with enough traffic to need more than a task
+    pub busy_topics: BTreeSet<String>,
+    /// Time after which a failed connector is reported, one day by default
+    pub failed_for: Duration,
+    pub failures: FailureLog,
+}
+
+impl Default for AnalyzeOptions {
+    fn default() -> Self {
+        Self {
+            busy_topics: BTreeSet::new(),
+            failed_for: Duration::from_secs(24 * 60 * 60),
+            failures: FailureLog::new(),
+        }
+    }
+}
+
+impl AnalyzeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn busy_topics<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.busy_topics = topics.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn failed_for(mut self, failed_for: Duration) -> Self {
+        self.failed_for = failed_for;
+        self
+    }
+
+    pub fn failures(mut self, failures: FailureLog) -> Self {
+        self.failures = failures;
+        self
+    }
+}
+
+fn serialize_secs<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Finding {
+    SingleTaskBusyTopics {
+        connector: String,
+        topics: Vec<String>,
+    },
+    /// Sinks of the same class reading the same topic, or sources capturing the same table
+    Duplicated {
+        class: String,
+        target: String,
+        connectors: Vec<String>,
+    },
+    /// A converter of the source writing the topic differs from that of a sink reading it, values
+    /// being `None` when left to the default of the worker
+    ConverterMismatch {
+        topic: String,
+        source: String,
+        sink: String,
+        key: String,
+        source_converter: Option<String>,
+        sink_converter: Option<String>,
+    },
+    FailedFor {
+        connector: String,
+        #[serde(rename = "for_secs", serialize_with = "serialize_secs")]
+        duration: Duration,
+    },
+}
+
+/// Findings of the analysis, in the order of the rules listed above
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AnalysisReport {
+    pub connectors: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl AnalysisReport {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// What the analysis knows of a connector
+struct Analyzed {
+    name: String,
+    kind: String,
+    config: ConnectorConfig,
+    tasks: usize,
+    /// Topics used, as tracked by the workers, along with those a sink is configured to read
+    topics: BTreeSet<String>,
+    failed: bool,
+}
+
+impl Analyzed {
+    fn class(&self) -> &str {
+        self.config
+            .get_str(config_keys::CONNECTOR_CLASS)
+            .unwrap_or_default()
+    }
+
+    fn tables(&self) -> Vec<String> {
+        let database = DATABASE_KEYS
+            .iter()
+            .find_map(|key| self.config.get_str(key))
+            .unwrap_or_default();
+        TABLE_KEYS
+            .iter()
+            .filter_map(|key| self.config.get_list(key))
+            .flatten()
+            .map(|table| format!("{}/{}", database, table))
+            .collect()
+    }
+}
+
+fn single_task_busy_topics(
+    connectors: &[Analyzed],
+    options: &AnalyzeOptions,
+    findings: &mut Vec<Finding>,
+) {
+    for connector in connectors.iter().filter(|connector| connector.tasks == 1) {
+        let topics: Vec<String> = connector
+            .topics
+            .intersection(&options.busy_topics)
+            .cloned()
+            .collect();
+        if !topics.is_empty() {
+            findings.push(Finding::SingleTaskBusyTopics {
+                connector: connector.name.clone(),
+                topics,
+            });
+        }
+    }
+}
+
+fn duplicated(connectors: &[Analyzed], findings: &mut Vec<Finding>) {
+    let mut targets: BTreeMap<(&str, String), Vec<String>> = BTreeMap::new();
+    for connector in connectors {
+        let mut own: BTreeSet<String> = connector.tables().into_iter().collect();
+        // sources of a class commonly share topics, such as the heartbeats of debezium
+        if connector.kind == "sink" {
+            own.extend(connector.topics.iter().cloned());
+        }
+        for target in own {
+            targets
+                .entry((connector.class(), target))
+                .or_default()
+                .push(connector.name.clone());
+        }
+    }
+    for ((class, target), names) in targets {
+        if names.len() > 1 {
+            findings.push(Finding::Duplicated {
+                class: class.to_string(),
+                target,
+                connectors: names,
+            });
+        }
+    }
+}
+
+fn converter_mismatches(connectors: &[Analyzed], findings: &mut Vec<Finding>) {
+    let (sources, sinks): (Vec<&Analyzed>, Vec<&Analyzed>) = connectors
+        .iter()
+        .filter(|connector| connector.kind == "source" || connector.kind == "sink")
+        .partition(|connector| connector.kind == "source");
+    for source in &sources {
+        for topic in &source.topics {
+            for sink in sinks.iter().filter(|sink| sink.topics.contains(topic)) {
+                for key in [config_keys::KEY_CONVERTER, config_keys::VALUE_CONVERTER] {
+                    let source_converter = source.config.get_str(key);
+                    let sink_converter = sink.config.get_str(key);
+                    if source_converter != sink_converter {
+                        findings.push(Finding::ConverterMismatch {
+                            topic: topic.clone(),
+                            source: source.name.clone(),
+                            sink: sink.name.clone(),
+                            key: key.to_string(),
+                            source_converter: source_converter.map(str::to_string),
+                            sink_converter: sink_converter.map(str::to_string),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn failed_for(connectors: &[Analyzed], options: &AnalyzeOptions, findings: &mut Vec<Finding>) {
+    let failed: BTreeSet<&str> = connectors
+        .iter()
+        .filter(|connector| connector.failed)
+        .map(|connector| connector.name.as_str())
+        .collect();
+    for (connector, duration) in options.failures.update(&failed, now()) {
+        if duration >= options.failed_for {
+            findings.push(Finding::FailedFor {
+                connector,
+                duration,
+            });
+        }
+    }
+}
+
+impl Connect {
+    async fn analyzed(&self, name: String, connector: Connector) -> Result<Option<Analyzed>> {
+        let (Some(info), Some(status)) = (connector.info, connector.status) else {
+            return Ok(None);
+        };
+        let mut topics: BTreeSet<String> = match self.connector_active_topics(&name).await {
+            Ok(active) => active.unwrap_or_default().into_iter().collect(),
+            // deleted since the listing, or workers older than the endpoint; the listing is
+            // analyzed as it was read
+            Err(err)
+                if matches!(
+                    err.downcast_ref(),
+                    Some(crate::error::ConnectError::ConnectorNotFound(_))
+                ) =>
+            {
+                BTreeSet::new()
+            }
+            Err(err) => return Err(err),
+        };
+        if let Some(configured) = info.config.get_list(config_keys::TOPICS) {
+            topics.extend(configured);
+        }
+        let failed = status.connector.state == Status::Failed
+            || status.tasks.iter().any(|task| task.state == Status::Failed);
+        Ok(Some(Analyzed {
+            name,
+            kind: info.kind,
+            config: info.config,
+            tasks: status.tasks.len(),
+            topics,
+            failed,
+        }))
+    }
+
+    /// Runs the practices review across every connector of the cluster
+    pub async fn analyze_cluster(&self, options: &AnalyzeOptions) -> Result<AnalysisReport> {
+        let listing = self.connectors(true, true).await?;
+        let results: Vec<Result<Option<Analyzed>>> = stream::iter(
+            listing
+                .into_iter()
+                .map(|(name, connector)| self.analyzed(name, connector)),
+        )
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+        let mut connectors = Vec::with_capacity(results.len());
+        for result in results {
+            connectors.extend(result?);
+        }
+        connectors.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut findings = Vec::new();
+        single_task_busy_topics(&connectors, options, &mut findings);
+        duplicated(&connectors, &mut findings);
+        converter_mismatches(&connectors, &mut findings);
+        failed_for(&connectors, options, &mut findings);
+        Ok(AnalysisReport {
+            connectors: connectors.len(),
+            findings,
+        })
+    }
+}
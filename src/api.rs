@@ -0,0 +1,204 @@
+//! Object safe interface of the client, so that code depending on a cluster can be tested against a mock
+use crate::config::ConnectorConfig;
+use crate::models::*;
+use crate::offsets::ConnectorOffsets;
+use crate::Connect;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Operations of the kafka-connect REST API, implemented by [`Connect`].
+/// Offsets are exchanged as json values, generic variants are only available on [`Connect`]
+#[async_trait]
+pub trait ConnectApi: Send + Sync {
+    async fn info(&self) -> Result<ClusterInfo>;
+    async fn connector_names(&self) -> Result<Vec<String>>;
+    async fn connectors(
+        &self,
+        expand_status: bool,
+        expand_info: bool,
+    ) -> Result<HashMap<String, Connector>>;
+    async fn connector_status(&self, connector: &str) -> Result<ConnectorStatus>;
+    async fn connector_active_topics(&self, connector: &str) -> Result<Option<Vec<String>>>;
+    async fn create_connector(&self, name: &str, config: &ConnectorConfig)
+        -> Result<ConnectorInfo>;
+    async fn create_connector_with_state(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+        initial_state: Status,
+    ) -> Result<ConnectorInfo>;
+    async fn update_connector_config(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo>;
+    async fn patch_connector_config(
+        &self,
+        name: &str,
+        patch: &HashMap<String, Option<String>>,
+    ) -> Result<ConnectorInfo>;
+    async fn restart_connector(
+        &self,
+        name: &str,
+        include_tasks: bool,
+        only_failed: bool,
+    ) -> Result<Option<ConnectorStatus>>;
+    async fn restart_task(&self, connector: &str, task: u64) -> Result<()>;
+    async fn pause_connector(&self, connector: &str) -> Result<()>;
+    async fn resume_connector(&self, connector: &str) -> Result<()>;
+    async fn stop_connector(&self, connector: &str) -> Result<()>;
+    async fn delete_connector(&self, connector: &str) -> Result<()>;
+    async fn connector_config(&self, connector: &str) -> Result<ConnectorConfig>;
+    async fn connector_offsets(&self, connector: &str) -> Result<ConnectorOffsets>;
+    async fn alter_connector_offsets(
+        &self,
+        connector: &str,
+        offsets: &ConnectorOffsets,
+    ) -> Result<String>;
+    async fn reset_connector_offsets(&self, connector: &str) -> Result<String>;
+    async fn validate_connector_config(&self, config: &ConnectorConfig) -> Result<ConfigInfos>;
+    async fn connector_plugins(&self) -> Result<Vec<PluginInfo>>;
+    async fn loggers(&self) -> Result<HashMap<String, LoggerLevel>>;
+    async fn logger(&self, logger: &str) -> Result<LoggerLevel>;
+    async fn set_logger_level(
+        &self,
+        logger: &str,
+        level: &str,
+        scope: LoggerScope,
+    ) -> Result<Vec<String>>;
+}
+
+#[async_trait]
+impl ConnectApi for Connect {
+    async fn info(&self) -> Result<ClusterInfo> {
+        Connect::info(self).await
+    }
+
+    async fn connector_names(&self) -> Result<Vec<String>> {
+        Connect::connector_names(self).await
+    }
+
+    async fn connectors(
+        &self,
+        expand_status: bool,
+        expand_info: bool,
+    ) -> Result<HashMap<String, Connector>> {
+        Connect::connectors(self, expand_status, expand_info).await
+    }
+
+    async fn connector_status(&self, connector: &str) -> Result<ConnectorStatus> {
+        Connect::connector_status(self, connector).await
+    }
+
+    async fn connector_active_topics(&self, connector: &str) -> Result<Option<Vec<String>>> {
+        Connect::connector_active_topics(self, connector).await
+    }
+
+    async fn create_connector(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        Connect::create_connector(self, name, config).await
+    }
+
+    async fn create_connector_with_state(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+        initial_state: Status,
+    ) -> Result<ConnectorInfo> {
+        Connect::create_connector_with_state(self, name, config, initial_state).await
+    }
+
+    async fn update_connector_config(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        Connect::update_connector_config(self, name, config).await
+    }
+
+    async fn patch_connector_config(
+        &self,
+        name: &str,
+        patch: &HashMap<String, Option<String>>,
+    ) -> Result<ConnectorInfo> {
+        Connect::patch_connector_config(self, name, patch).await
+    }
+
+    async fn restart_connector(
+        &self,
+        name: &str,
+        include_tasks: bool,
+        only_failed: bool,
+    ) -> Result<Option<ConnectorStatus>> {
+        Connect::restart_connector(self, name, include_tasks, only_failed).await
+    }
+
+    async fn restart_task(&self, connector: &str, task: u64) -> Result<()> {
+        Connect::restart_task(self, connector, task).await
+    }
+
+    async fn pause_connector(&self, connector: &str) -> Result<()> {
+        Connect::pause_connector(self, connector).await
+    }
+
+    async fn resume_connector(&self, connector: &str) -> Result<()> {
+        Connect::resume_connector(self, connector).await
+    }
+
+    async fn stop_connector(&self, connector: &str) -> Result<()> {
+        Connect::stop_connector(self, connector).await
+    }
+
+    async fn delete_connector(&self, connector: &str) -> Result<()> {
+        Connect::delete_connector(self, connector).await
+    }
+
+    async fn connector_config(&self, connector: &str) -> Result<ConnectorConfig> {
+        Connect::connector_config(self, connector).await
+    }
+
+    async fn connector_offsets(&self, connector: &str) -> Result<ConnectorOffsets> {
+        Connect::connector_offsets(self, connector).await
+    }
+
+    async fn alter_connector_offsets(
+        &self,
+        connector: &str,
+        offsets: &ConnectorOffsets,
+    ) -> Result<String> {
+        Connect::alter_connector_offsets(self, connector, offsets).await
+    }
+
+    async fn reset_connector_offsets(&self, connector: &str) -> Result<String> {
+        Connect::reset_connector_offsets(self, connector).await
+    }
+
+    async fn validate_connector_config(&self, config: &ConnectorConfig) -> Result<ConfigInfos> {
+        Connect::validate_connector_config(self, config).await
+    }
+
+    async fn connector_plugins(&self) -> Result<Vec<PluginInfo>> {
+        Connect::connector_plugins(self).await
+    }
+
+    async fn loggers(&self) -> Result<HashMap<String, LoggerLevel>> {
+        Connect::loggers(self).await
+    }
+
+    async fn logger(&self, logger: &str) -> Result<LoggerLevel> {
+        Connect::logger(self, logger).await
+    }
+
+    async fn set_logger_level(
+        &self,
+        logger: &str,
+        level: &str,
+        scope: LoggerScope,
+    ) -> Result<Vec<String>> {
+        Connect::set_logger_level(self, logger, level, scope).await
+    }
+}
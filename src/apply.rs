@@ -0,0 +1,101 @@
+//! Declarative management of connectors
+//! A spec describes the desired state of a connector, and applying it creates or updates the
+//! connector only when needed
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::normalize::Normalizer;
+use crate::Connect;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Desired state of a connector, as declared in a manifest
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ConnectorSpec {
+    pub name: String,
+    pub config: ConnectorConfig,
+}
+
+impl ConnectorSpec {
+    pub fn new(name: impl Into<String>, config: impl Into<ConnectorConfig>) -> Self {
+        Self {
+            name: name.into(),
+            config: config.into(),
+        }
+    }
+
+    /// Sets a key of the configuration
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    /// The configuration as returned by kafka-connect, which always includes the name
+    pub fn effective_config(&self) -> ConnectorConfig {
+        let mut config = self.config.clone();
+        config
+            .entry(config_keys::NAME.to_string())
+            .or_insert_with(|| self.name.clone());
+        config
+    }
+}
+
+/// What applying a spec resulted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ApplyOutcome {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+impl fmt::Display for ApplyOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Unchanged => "unchanged",
+        };
+        f.write_str(outcome)
+    }
+}
+
+impl Connect {
+    /// Brings a connector to the state described by the spec. The live configuration is left as
+    /// it is when it only differs cosmetically, see [`Normalizer`]
+    pub async fn apply(&self, spec: &ConnectorSpec) -> Result<ApplyOutcome> {
+        self.apply_normalized(spec, &Normalizer::default()).await
+    }
+
+    /// Same as [`Connect::apply`], comparing the configurations once normalized by the normalizer
+    pub async fn apply_normalized(
+        &self,
+        spec: &ConnectorSpec,
+        normalizer: &Normalizer,
+    ) -> Result<ApplyOutcome> {
+        let names = self.connector_names().await?;
+        self.apply_among(spec, normalizer, &names).await
+    }
+
+    /// Same as [`Connect::apply_normalized`], given the names of the connectors of the cluster
+    pub(crate) async fn apply_among(
+        &self,
+        spec: &ConnectorSpec,
+        normalizer: &Normalizer,
+        names: &[String],
+    ) -> Result<ApplyOutcome> {
+        let desired = spec.effective_config();
+        if !names.contains(&spec.name) {
+            self.create_connector(&spec.name, &desired).await?;
+            return Ok(ApplyOutcome::Created);
+        }
+        let live = self.connector_config(&spec.name).await?;
+        if normalizer.equivalent(&live, &desired) {
+            return Ok(ApplyOutcome::Unchanged);
+        }
+        self.update_connector_config(&spec.name, &desired).await?;
+        Ok(ApplyOutcome::Updated)
+    }
+}
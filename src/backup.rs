@@ -0,0 +1,248 @@
+//! Scheduled backups of a cluster, in a directory or an object store
+//! Only available with the `backup` feature
+//!
+//! A daemon takes snapshots on a fixed interval or on a cron schedule, writes them as
+//! `snapshot-<taken_at>.json`, and prunes the old ones according to the retention. The time of
+//! the last successful backup is kept for monitoring, to alert when backups stop
+use crate::snapshot::{self, ClusterSnapshot};
+use crate::Connect;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+
+#[derive(Clone, Debug)]
+pub enum BackupSchedule {
+    Every(Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+impl BackupSchedule {
+    /// Schedule of a cron expression with seconds, such as `0 0 * * * *` for every hour
+    pub fn cron(expression: &str) -> Result<Self> {
+        let schedule = cron::Schedule::from_str(expression)
+            .with_context(|| format!("Invalid cron expression {}", expression))?;
+        Ok(Self::Cron(Box::new(schedule)))
+    }
+
+    /// Time until the next backup is due
+    fn next_delay(&self) -> Duration {
+        match self {
+            Self::Every(interval) => *interval,
+            Self::Cron(schedule) => schedule
+                .upcoming(chrono::Utc)
+                .next()
+                .and_then(|next| (next - chrono::Utc::now()).to_std().ok())
+                .unwrap_or(Duration::MAX),
+        }
+    }
+}
+
+/// Where the snapshots are written
+#[derive(Clone, Debug)]
+pub enum BackupSink {
+    Directory(PathBuf),
+    /// Prefix in an object store, see [`crate::snapshot_store`]
+    #[cfg(feature = "object_store")]
+    ObjectStore(String),
+}
+
+impl BackupSink {
+    async fn write(&self, snapshot: &ClusterSnapshot) -> Result<String> {
+        match self {
+            Self::Directory(directory) => {
+                tokio::fs::create_dir_all(directory).await?;
+                let path = directory.join(snapshot::file_name(snapshot.taken_at));
+                tokio::fs::write(&path, serde_json::to_vec_pretty(snapshot)?)
+                    .await
+                    .with_context(|| format!("Could not write {}", path.display()))?;
+                Ok(path.display().to_string())
+            }
+            #[cfg(feature = "object_store")]
+            Self::ObjectStore(url) => crate::snapshot_store::write_snapshot(url, snapshot).await,
+        }
+    }
+
+    /// Times of the snapshots in the sink, most recent first
+    async fn list(&self) -> Result<Vec<u64>> {
+        match self {
+            Self::Directory(directory) => {
+                let mut taken_at = Vec::new();
+                let mut entries = tokio::fs::read_dir(directory).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    taken_at.extend(entry.file_name().to_str().and_then(snapshot::taken_at_of));
+                }
+                taken_at.sort_unstable_by(|a, b| b.cmp(a));
+                Ok(taken_at)
+            }
+            #[cfg(feature = "object_store")]
+            Self::ObjectStore(url) => crate::snapshot_store::list_snapshots(url).await,
+        }
+    }
+
+    async fn delete(&self, taken_at: u64) -> Result<()> {
+        match self {
+            Self::Directory(directory) => {
+                Ok(tokio::fs::remove_file(directory.join(snapshot::file_name(taken_at))).await?)
+            }
+            #[cfg(feature = "object_store")]
+            Self::ObjectStore(url) => crate::snapshot_store::delete_snapshot(url, taken_at).await,
+        }
+    }
+}
+
+/// Which snapshots to keep. A snapshot is pruned when either limit rules it out, the most recent
+/// one is always kept
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Retention {
+    pub keep_last: Option<usize>,
+    pub max_age: Option<Duration>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            keep_last: Some(30),
+            max_age: None,
+        }
+    }
+}
+
+impl Retention {
+    /// Snapshots to prune among the times given, most recent first
+    pub fn expired(&self, taken_at: &[u64], now: u64) -> Vec<u64> {
+        taken_at
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(rank, taken_at)| {
+                self.keep_last.is_some_and(|keep| *rank >= keep)
+                    || self
+                        .max_age
+                        .is_some_and(|age| now.saturating_sub(**taken_at) > age.as_secs())
+            })
+            .map(|(_, taken_at)| *taken_at)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BackupOptions {
+    pub schedule: BackupSchedule,
+    pub sink: BackupSink,
+    pub retention: Retention,
+    /// Masks the secrets, see [`ClusterSnapshot::masked`]. Masked snapshots cannot be restored
+    /// as they are, so encrypted backups should opt out
+    pub masked: bool,
+}
+
+impl BackupOptions {
+    /// Hourly masked backups to the sink, keeping the last 30
+    pub fn new(sink: BackupSink) -> Self {
+        Self {
+            schedule: BackupSchedule::Every(Duration::from_secs(60 * 60)),
+            sink,
+            retention: Retention::default(),
+            masked: true,
+        }
+    }
+
+    /// Keeps the secrets in the snapshots, so that they can be restored. The sink must be
+    /// encrypted
+    pub fn unmasked(mut self) -> Self {
+        self.masked = false;
+        self
+    }
+}
+
+/// State of the backups, for monitoring
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BackupStatus {
+    /// Unix timestamp in seconds of the last successful backup
+    pub last_success: Option<u64>,
+    /// Where the last successful backup was written
+    pub last_location: Option<String>,
+    /// Error of the last backup, if it failed
+    pub last_error: Option<String>,
+    /// Snapshots pruned since the daemon started
+    pub pruned: usize,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+impl Connect {
+    /// Takes a snapshot, writes it to the sink and prunes the expired ones. Returns where the
+    /// snapshot was written and how many were pruned
+    pub async fn backup(&self, options: &BackupOptions) -> Result<(String, usize)> {
+        let mut snapshot = self.snapshot().await?;
+        if options.masked {
+            snapshot = snapshot.masked();
+        }
+        let location = options.sink.write(&snapshot).await?;
+        let expired = options
+            .retention
+            .expired(&options.sink.list().await?, now());
+        for taken_at in &expired {
+            options.sink.delete(*taken_at).await?;
+        }
+        Ok((location, expired.len()))
+    }
+}
+
+/// Takes backups on schedule until dropped
+pub struct BackupDaemon {
+    status: Arc<RwLock<BackupStatus>>,
+    task: JoinHandle<()>,
+}
+
+impl BackupDaemon {
+    pub fn spawn(client: Connect, options: BackupOptions) -> Self {
+        let status = Arc::new(RwLock::new(BackupStatus::default()));
+        let shared = status.clone();
+        let task = tokio::spawn(async move {
+            // interval backups start right away, cron ones wait for their time
+            let mut first = matches!(options.schedule, BackupSchedule::Every(_));
+            loop {
+                if !first {
+                    tokio::time::sleep(options.schedule.next_delay()).await;
+                }
+                first = false;
+                let result = client.backup(&options).await;
+                let mut status = shared.write().unwrap();
+                match result {
+                    Ok((location, pruned)) => {
+                        status.last_success = Some(now());
+                        status.last_location = Some(location);
+                        status.last_error = None;
+                        status.pruned += pruned;
+                    }
+                    Err(err) => status.last_error = Some(format!("{:#}", err)),
+                }
+            }
+        });
+        Self { status, task }
+    }
+
+    pub fn status(&self) -> BackupStatus {
+        self.status.read().unwrap().clone()
+    }
+
+    /// Unix timestamp in seconds of the last successful backup
+    pub fn last_success(&self) -> Option<u64> {
+        self.status.read().unwrap().last_success
+    }
+}
+
+impl Drop for BackupDaemon {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
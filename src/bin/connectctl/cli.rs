@@ -0,0 +1,261 @@
+// Command line definition of connectctl
+// Also included by build.rs to generate the man pages, which rules out inner attributes
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+/// Manage kafka-connect clusters from the command line
+#[derive(Debug, Parser)]
+#[command(name = "connectctl", version)]
+pub struct Cli {
+    #[command(flatten)]
+    pub connection: ConnectionArgs,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    pub output: OutputFormat,
+    /// Print the changes instead of making them, after checking what can be checked
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Yaml,
+}
+
+/// Connection settings. When given, they take precedence over those of the context
+#[derive(Debug, Args)]
+pub struct ConnectionArgs {
+    /// Context of the configuration file to use instead of the current one
+    #[arg(long, env = "CONNECT_CONTEXT", global = true)]
+    pub context: Option<String>,
+    /// Address of the kafka-connect REST API, http://localhost:8083 by default
+    #[arg(long, env = "CONNECT_URL", global = true)]
+    pub url: Option<String>,
+    /// Username used for basic authentication
+    #[arg(long, env = "CONNECT_USER", global = true)]
+    pub user: Option<String>,
+    /// Password used for basic authentication
+    #[arg(long, env = "CONNECT_PASSWORD", hide_env_values = true, global = true)]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List the connectors of the cluster
+    List,
+    /// Show the status of a connector and of its tasks
+    Status { name: String },
+    /// Restart a connector
+    Restart {
+        name: String,
+        /// Restart the tasks as well
+        #[arg(long)]
+        include_tasks: bool,
+        /// Only restart the failed connector and tasks
+        #[arg(long)]
+        only_failed: bool,
+    },
+    /// Pause a connector and its tasks
+    Pause { name: String },
+    /// Resume a paused or stopped connector
+    Resume { name: String },
+    /// Stop a connector, keeping its configuration
+    Stop { name: String },
+    /// Delete a connector, or every connector declared in the given manifests
+    Delete {
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        name: Option<String>,
+        /// Manifest file, or directory of manifests
+        #[arg(short = 'f', long)]
+        file: Option<PathBuf>,
+    },
+    /// Display resources of the cluster
+    Get {
+        #[command(subcommand)]
+        resource: Resource,
+    },
+    /// Inspect or modify the offsets of a connector
+    Offsets {
+        #[command(subcommand)]
+        command: OffsetsCommand,
+    },
+    /// Validate the connectors declared in the given manifests, exiting with 1 when invalid
+    Validate {
+        /// Manifest file, or directory of manifests
+        #[arg(short = 'f', long)]
+        file: PathBuf,
+    },
+    /// Lint the connectors declared in the given manifests, exiting with 1 on errors
+    Lint {
+        /// Manifest file, or directory of manifests
+        #[arg(short = 'f', long)]
+        file: PathBuf,
+        /// Also exit with 1 on warnings
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Write the manifest of every connector to a directory
+    Export {
+        /// Destination directory, created if missing
+        #[arg(long)]
+        out: PathBuf,
+        /// Replace passwords and other secrets with a mask
+        #[arg(long)]
+        mask_secrets: bool,
+    },
+    /// Restore the connectors exported to a directory
+    Import { dir: PathBuf },
+    /// Restart every failed connector and task of the cluster
+    RestartFailed {
+        /// Only restart the connectors whose name matches the regex
+        #[arg(long)]
+        connector_regex: Option<String>,
+        /// Maximum number of restarts running at the same time
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+    },
+    /// Inspect or change the log levels of the workers
+    Loggers {
+        #[command(subcommand)]
+        command: LoggersCommand,
+    },
+    /// Create or update the connectors declared in the given manifests
+    Apply {
+        /// Manifest file, or directory of manifests
+        #[arg(short = 'f', long)]
+        file: PathBuf,
+    },
+    /// Dashboard of the connectors, to restart, pause and resume them
+    #[cfg(feature = "tui")]
+    Top {
+        /// Seconds between two refreshes
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
+    /// Print the completion script of a shell
+    Completions { shell: Shell },
+    /// Print the JSON schema of a file format, for editors to validate the files against
+    #[cfg(feature = "schemars")]
+    Schema {
+        #[arg(value_enum)]
+        format: SchemaFormat,
+    },
+    /// Create a connector
+    Create {
+        name: String,
+        /// Class of the connector plugin
+        #[arg(long)]
+        class: String,
+        /// Configuration entry, as key=value
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        settings: Vec<String>,
+        /// Prompt for every missing or invalid key until the configuration validates
+        #[arg(short, long)]
+        interactive: bool,
+    },
+    /// Manage the contexts of the configuration file
+    Ctx {
+        #[command(subcommand)]
+        command: CtxCommand,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SchemaFormat {
+    /// Connector manifest, as read by `apply`
+    Manifest,
+    /// Cluster snapshot, as written by the backups
+    Snapshot,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CtxCommand {
+    /// List the contexts, marking the current one
+    List,
+    /// Show the name of the current context
+    Current,
+    /// Make a context the current one
+    Use { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Resource {
+    /// Status table of every connector
+    Connectors {
+        /// Keep refreshing the table, highlighting the connectors that changed
+        #[arg(short, long)]
+        watch: bool,
+        /// Seconds between two refreshes
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Slow down to this many seconds between refreshes while the cluster is stable
+        #[arg(long)]
+        max_interval: Option<u64>,
+        /// Only show the connectors whose name matches the regex
+        #[arg(long)]
+        name: Option<String>,
+        /// Only show the connectors in this state, repeat to accept several
+        #[arg(long)]
+        state: Vec<String>,
+        /// Only show the connectors of this type, source or sink
+        #[arg(long = "type")]
+        kind: Option<String>,
+        /// Only show the connectors running on this worker
+        #[arg(long)]
+        worker: Option<String>,
+        /// Only show the connectors whose labels match, e.g. team=payments,env!=prod
+        #[arg(short = 'l', long)]
+        selector: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OffsetsCommand {
+    /// Print the offsets of a connector
+    Get { connector: String },
+    /// Alter the offsets of a stopped connector
+    Alter {
+        connector: String,
+        /// File containing the offsets to write, in json or yaml
+        #[arg(short = 'f', long)]
+        file: PathBuf,
+        /// Stop the connector first, and bring it back to its state once the offsets are altered
+        #[arg(long)]
+        safe: bool,
+    },
+    /// Reset the offsets of a stopped connector
+    Reset {
+        connector: String,
+        /// Stop the connector first, and bring it back to its state once the offsets are reset
+        #[arg(long)]
+        safe: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LoggersCommand {
+    /// List the level of every logger
+    List,
+    /// Show the level of a logger
+    Get { logger: String },
+    /// Set the level of a logger and of its children
+    Set {
+        logger: String,
+        level: String,
+        #[arg(long, value_enum, default_value_t = Scope::Worker)]
+        scope: Scope,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Scope {
+    /// Only the worker receiving the request
+    Worker,
+    /// Every worker of the cluster
+    Cluster,
+}
@@ -0,0 +1,125 @@
+//! Named cluster contexts, stored in `~/.config/connectctl/config.yaml`
+//!
+//! ```yaml
+//! current-context: production
+//! contexts:
+//!   production:
+//!     url: https://connect.example.com:8083
+//!     user: admin
+//!     password: secret
+//!     tls:
+//!       ca-certificate: /etc/ssl/connect-ca.pem
+//! ```
+use crate::cli::ConnectionArgs;
+use anyhow::{Context as _, Result};
+use connect_rs::Connect;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_URL: &str = "http://localhost:8083";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_context: Option<String>,
+    #[serde(default)]
+    pub contexts: BTreeMap<String, Context>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Context {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<Tls>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Tls {
+    /// PEM file of an additional certificate authority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_certificate: Option<PathBuf>,
+    /// Skips the verification of the server certificate
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+impl Config {
+    /// Location of the file, which `CONNECTCTL_CONFIG` overrides
+    pub fn path() -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os("CONNECTCTL_CONFIG") {
+            return Ok(PathBuf::from(path));
+        }
+        let home = std::env::var_os("HOME").context("HOME is not set")?;
+        Ok(PathBuf::from(home).join(".config/connectctl/config.yaml"))
+    }
+
+    /// Loads the file, which may not exist
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_yaml::to_string(self)?)
+            .with_context(|| format!("Could not write {}", path.display()))
+    }
+
+    /// The context named on the command line, otherwise the current one
+    pub fn select(&self, name: Option<&str>) -> Result<Option<&Context>> {
+        match name.or(self.current_context.as_deref()) {
+            Some(name) => self
+                .contexts
+                .get(name)
+                .map(Some)
+                .with_context(|| format!("Context {} does not exist", name)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Creates the client, the connection arguments overriding the settings of the context
+pub fn connect(args: &ConnectionArgs) -> Result<Connect> {
+    let config = Config::load()?;
+    let context = config
+        .select(args.context.as_deref())?
+        .cloned()
+        .unwrap_or_default();
+    let url = args
+        .url
+        .as_deref()
+        .or(Some(context.url.as_str()).filter(|url| !url.is_empty()))
+        .unwrap_or(DEFAULT_URL);
+    let user = args
+        .user
+        .as_deref()
+        .or(context.user.as_deref())
+        .unwrap_or("");
+    let password = args.password.as_deref().or(context.password.as_deref());
+    let mut builder = Connect::builder(url).basic_auth(user, password);
+    if let Some(tls) = &context.tls {
+        if let Some(path) = &tls.ca_certificate {
+            let pem =
+                fs::read(path).with_context(|| format!("Could not read {}", path.display()))?;
+            builder = builder.ca_certificate(pem);
+        }
+        builder = builder.accept_invalid_certificates(tls.insecure);
+    }
+    builder.build()
+}
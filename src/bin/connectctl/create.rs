@@ -0,0 +1,85 @@
+//! Creation of connectors, optionally prompting for their configuration
+use anyhow::{Context, Result};
+use connect_rs::config::ConnectorConfig;
+use connect_rs::config_keys;
+use connect_rs::models::ConnectorInfo;
+use connect_rs::wizard::{ConfigWizard, Prompt};
+use connect_rs::Connect;
+use std::io::{self, BufRead, Write};
+
+/// Parses `key=value` pairs into a configuration
+pub fn parse_settings(settings: &[String]) -> Result<ConnectorConfig> {
+    settings
+        .iter()
+        .map(|setting| {
+            setting
+                .split_once('=')
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .with_context(|| format!("Expected key=value, got {}", setting))
+        })
+        .collect()
+}
+
+pub async fn run(
+    client: &Connect,
+    name: &str,
+    class: &str,
+    mut config: ConnectorConfig,
+    interactive: bool,
+) -> Result<ConnectorInfo> {
+    config.set(config_keys::NAME, name);
+    config.set(config_keys::CONNECTOR_CLASS, class);
+    if interactive {
+        config = prompt_until_valid(client, config).await?;
+    }
+    client.create_connector(name, &config).await
+}
+
+async fn prompt_until_valid(client: &Connect, config: ConnectorConfig) -> Result<ConnectorConfig> {
+    let mut wizard = ConfigWizard::with_config(client, config);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    while let Some(prompt) = wizard.next().await? {
+        ask(&prompt)?;
+        let line = lines.next().context("No more input")??;
+        let answer = line.trim();
+        let value = match answer.parse::<usize>() {
+            // numbers pick one of the recommended values, when there are some
+            Ok(choice) if (1..=prompt.recommended_values.len()).contains(&choice) => {
+                prompt.recommended_values[choice - 1].clone()
+            }
+            _ if answer.is_empty() => match &prompt.default_value {
+                Some(default) => default.clone(),
+                None => continue,
+            },
+            _ => answer.to_string(),
+        };
+        wizard.answer(&prompt.key, &value);
+    }
+    Ok(wizard.into_config())
+}
+
+/// Prompts are written to stderr, so that the output of the command stays parseable
+fn ask(prompt: &Prompt) -> io::Result<()> {
+    let mut stderr = io::stderr();
+    writeln!(stderr)?;
+    writeln!(
+        stderr,
+        "{} ({}, {})",
+        prompt.display_name, prompt.key, prompt.kind
+    )?;
+    if let Some(documentation) = &prompt.documentation {
+        writeln!(stderr, "  {}", documentation)?;
+    }
+    for error in &prompt.errors {
+        writeln!(stderr, "  error: {}", error)?;
+    }
+    for (index, value) in prompt.recommended_values.iter().enumerate() {
+        writeln!(stderr, "  {}) {}", index + 1, value)?;
+    }
+    match &prompt.default_value {
+        Some(default) => write!(stderr, "> [{}] ", default)?,
+        None => write!(stderr, "> ")?,
+    }
+    stderr.flush()
+}
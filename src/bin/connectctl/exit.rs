@@ -0,0 +1,30 @@
+//! Exit codes, so that scripts and health checks can tell outcomes apart
+use connect_rs::error::ConnectError;
+
+pub const SUCCESS: i32 = 0;
+/// Any error, including invalid configurations
+pub const FAILURE: i32 = 1;
+/// The command succeeded, but some connectors or tasks are not running
+pub const DEGRADED: i32 = 2;
+pub const NOT_FOUND: i32 = 3;
+/// The cluster is rebalancing, retrying later may succeed
+pub const REBALANCING: i32 = 4;
+
+pub fn for_error(error: &anyhow::Error) -> i32 {
+    match error.downcast_ref::<ConnectError>() {
+        Some(
+            ConnectError::ConnectorNotFound(_)
+            | ConnectError::PluginNotFound(_)
+            | ConnectError::PluginMissing { .. }
+            | ConnectError::LoggerNotFound(_),
+        ) => NOT_FOUND,
+        Some(ConnectError::Rebalancing) => REBALANCING,
+        Some(
+            ConnectError::ResponseTooLarge { .. }
+            | ConnectError::UnsupportedByCluster(_)
+            | ConnectError::ConfigConflict { .. }
+            | ConnectError::InvalidConnectorName { .. },
+        )
+        | None => FAILURE,
+    }
+}
@@ -0,0 +1,570 @@
+mod cli;
+mod context;
+mod create;
+mod exit;
+mod manifest;
+mod offsets;
+mod output;
+#[cfg(feature = "tui")]
+mod top;
+
+use anyhow::{Context, Result};
+use clap::{CommandFactory, Parser};
+use cli::{Cli, Command, CtxCommand, LoggersCommand, Resource, Scope};
+use connect_rs::bulk::RestartFailedOptions;
+use connect_rs::display::{self, FmtTable};
+use connect_rs::dry_run::DryRun;
+use connect_rs::filter::ConnectorFilter;
+use connect_rs::health::ClusterHealth;
+use connect_rs::lint::{max_severity, Finding, Severity};
+use connect_rs::models::{ConnectorStatus, LoggerScope};
+use connect_rs::snapshot::ClusterSnapshot;
+use connect_rs::watch::{AdaptiveInterval, WatchEvent, Watcher};
+use connect_rs::Connect;
+use output::{outcome_lines, Outcome, Output};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::IsTerminal;
+use std::path::Path;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let code = match run(cli).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            exit::for_error(&e)
+        }
+    };
+    std::process::exit(code);
+}
+
+/// Runs the command, returning the exit code
+async fn run(cli: Cli) -> Result<i32> {
+    let output = Output::new(cli.output);
+    match cli.command {
+        Command::Ctx { command } => return ctx(command, &output),
+        #[cfg(feature = "schemars")]
+        Command::Schema { format } => {
+            let schema = match format {
+                cli::SchemaFormat::Manifest => connect_rs::schema::manifest_schema(),
+                cli::SchemaFormat::Snapshot => connect_rs::schema::snapshot_schema(),
+            };
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            return Ok(exit::SUCCESS);
+        }
+        Command::Lint { file, strict } => return lint(&file, strict, &output),
+        Command::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "connectctl",
+                &mut std::io::stdout(),
+            );
+            return Ok(exit::SUCCESS);
+        }
+        _ => {}
+    }
+    let mut client = context::connect(&cli.connection)?;
+    let dry_run = cli.dry_run.then(DryRun::new);
+    if let Some(dry_run) = &dry_run {
+        client = client.with_dry_run(dry_run.clone());
+    }
+    let code = execute(cli.command, client, &output).await;
+    if let Some(dry_run) = dry_run {
+        for mutation in dry_run.take() {
+            eprintln!("{}", mutation);
+        }
+    }
+    code
+}
+
+/// Runs a command against the cluster
+async fn execute(command: Command, client: Connect, output: &Output) -> Result<i32> {
+    match command {
+        Command::List => {
+            let mut names = client.connector_names().await?;
+            names.sort();
+            output.emit(&names, |names| names.to_vec())?;
+        }
+        Command::Status { name } => {
+            let status = client.connector_status(&name).await?;
+            output.emit(&status, status_lines)?;
+            if !status.is_healthy() {
+                return Ok(exit::DEGRADED);
+            }
+        }
+        Command::Restart {
+            name,
+            include_tasks,
+            only_failed,
+        } => match client
+            .restart_connector(&name, include_tasks, only_failed)
+            .await?
+        {
+            Some(status) => output.emit(&status, status_lines)?,
+            None => output.emit(&Outcome::new(name, "restarted"), Outcome::lines)?,
+        },
+        Command::Pause { name } => {
+            client.pause_connector(&name).await?;
+            output.emit(&Outcome::new(name, "pausing"), Outcome::lines)?;
+        }
+        Command::Resume { name } => {
+            client.resume_connector(&name).await?;
+            output.emit(&Outcome::new(name, "resuming"), Outcome::lines)?;
+        }
+        Command::Stop { name } => {
+            client.stop_connector(&name).await?;
+            output.emit(&Outcome::new(name, "stopped"), Outcome::lines)?;
+        }
+        Command::Delete {
+            name: Some(name), ..
+        } => {
+            client.delete_connector(&name).await?;
+            output.emit(&Outcome::new(name, "deleted"), Outcome::lines)?;
+        }
+        Command::Delete {
+            file: Some(file), ..
+        } => {
+            let specs = manifest::load(&file)?;
+            let names = client.connector_names().await?;
+            let mut outcomes = Vec::with_capacity(specs.len());
+            for spec in specs {
+                if !names.contains(&spec.name) {
+                    outcomes.push(Outcome::new(spec.name, "not found"));
+                    continue;
+                }
+                client.delete_connector(&spec.name).await?;
+                outcomes.push(Outcome::new(spec.name, "deleted"));
+            }
+            output.emit(&outcomes, |outcomes| outcome_lines(outcomes))?;
+        }
+        Command::Delete { .. } => unreachable!("clap requires either a name or a file"),
+        Command::Get {
+            resource:
+                Resource::Connectors {
+                    watch,
+                    interval,
+                    max_interval,
+                    name,
+                    state,
+                    kind,
+                    worker,
+                    selector,
+                },
+        } => {
+            let mut filter = ConnectorFilter::new();
+            if let Some(name) = name {
+                filter = filter.name_regex(&name)?;
+            }
+            for state in state {
+                filter = filter.state(state.parse()?);
+            }
+            if let Some(kind) = kind {
+                filter = filter.kind(&kind);
+            }
+            if let Some(worker) = worker {
+                filter = filter.worker(&worker);
+            }
+            if let Some(selector) = selector {
+                filter = filter.label_selector(&selector)?;
+            }
+            let watcher = match max_interval {
+                Some(max_interval) => Watcher::adaptive(
+                    client,
+                    AdaptiveInterval {
+                        fast: Duration::from_secs(interval),
+                        slow: Duration::from_secs(max_interval),
+                        settle: Duration::from_secs(max_interval),
+                    },
+                ),
+                None => Watcher::new(client, Duration::from_secs(interval)),
+            };
+            let mut watcher = watcher.filter(filter);
+            let mut update = watcher.next().await?;
+            if !watch {
+                output.emit(&update.statuses, |statuses| {
+                    status_table(statuses, &HashSet::new())
+                })?;
+                let health: ClusterHealth = update.statuses.values().collect();
+                return Ok(if health.is_healthy() {
+                    exit::SUCCESS
+                } else {
+                    exit::DEGRADED
+                });
+            }
+            if !output.is_table() {
+                // structured output streams the statuses once, then every event as it happens
+                output.emit_line(&update.statuses, |_| Vec::new())?;
+                loop {
+                    update = watcher.next().await?;
+                    for event in &update.events {
+                        output.emit_line(event, |_| Vec::new())?;
+                    }
+                }
+            }
+            loop {
+                let changed: HashSet<&str> = update
+                    .events
+                    .iter()
+                    .filter(|event| !matches!(event, WatchEvent::Added { .. }))
+                    .map(WatchEvent::connector)
+                    .collect();
+                // clear the screen and move the cursor back to the top
+                print!("\x1b[2J\x1b[H");
+                for line in status_table(&update.statuses, &changed) {
+                    println!("{}", line);
+                }
+                for event in &update.events {
+                    if let Some(transition) = describe(event) {
+                        println!("{}", transition);
+                    }
+                }
+                update = watcher.next().await?;
+            }
+        }
+        Command::Offsets { command } => offsets::run(&client, command, output).await?,
+        Command::Validate { file } => {
+            let mut validations = Vec::new();
+            for spec in manifest::load(&file)? {
+                let validation = client
+                    .validate_connector_config(&spec.effective_config())
+                    .await?;
+                let errors = validation
+                    .errors()
+                    .into_iter()
+                    .map(|(key, errors)| (key.to_string(), errors.to_vec()))
+                    .collect();
+                validations.push(Validation {
+                    connector: spec.name,
+                    valid: validation.is_valid(),
+                    errors,
+                });
+            }
+            output.emit(&validations, |validations| {
+                validations.iter().flat_map(validation_lines).collect()
+            })?;
+            if validations.iter().any(|validation| !validation.valid) {
+                return Ok(exit::FAILURE);
+            }
+        }
+        Command::Export { out, mask_secrets } => {
+            let mut snapshot = client.snapshot().await?;
+            if mask_secrets {
+                snapshot = snapshot.masked();
+            }
+            std::fs::create_dir_all(&out)?;
+            let mut outcomes = Vec::with_capacity(snapshot.connectors.len());
+            for spec in &snapshot.connectors {
+                let path = manifest::save(&out, spec)?;
+                outcomes.push(Outcome::new(spec.name.clone(), path.display()));
+            }
+            output.emit(&outcomes, |outcomes| outcome_lines(outcomes))?;
+        }
+        Command::Import { dir } => {
+            let info = client.info().await?;
+            let snapshot = ClusterSnapshot {
+                version: info.version,
+                taken_at: 0,
+                connectors: manifest::load(&dir)?,
+            };
+            let outcomes: Vec<Outcome> = client
+                .restore(&snapshot)
+                .await?
+                .into_iter()
+                .map(|(name, outcome)| Outcome::new(name, outcome))
+                .collect();
+            output.emit(&outcomes, |outcomes| outcome_lines(outcomes))?;
+        }
+        Command::RestartFailed {
+            connector_regex,
+            concurrency,
+        } => {
+            let mut filter = ConnectorFilter::new();
+            if let Some(regex) = &connector_regex {
+                filter = filter.name_regex(regex)?;
+            }
+            // the global --dry-run only reports the failed connectors
+            let dry_run = client.dry_run().is_some();
+            let options = RestartFailedOptions {
+                filter,
+                dry_run,
+                concurrency,
+                ..Default::default()
+            };
+            let summary = client.restart_failed(&options).await?;
+            output.emit(&summary, |summary| {
+                if dry_run {
+                    let mut lines: Vec<String> = summary
+                        .failed
+                        .iter()
+                        .map(|name| format!("{}: would restart", name))
+                        .collect();
+                    lines.push(format!("{} failed connectors", summary.failed.len()));
+                    return lines;
+                }
+                let mut lines: Vec<String> = summary
+                    .restarted
+                    .iter()
+                    .map(|name| format!("{}: restarted", name))
+                    .chain(
+                        summary
+                            .errors
+                            .iter()
+                            .map(|(name, error)| format!("{}: {}", name, error)),
+                    )
+                    .collect();
+                lines.push(format!(
+                    "{} failed connectors, {} restarted, {} errors",
+                    summary.failed.len(),
+                    summary.restarted.len(),
+                    summary.errors.len()
+                ));
+                lines
+            })?;
+            if !summary.errors.is_empty() {
+                return Ok(exit::FAILURE);
+            }
+        }
+        Command::Loggers { command } => match command {
+            LoggersCommand::List => {
+                let loggers: BTreeMap<_, _> = client.loggers().await?.into_iter().collect();
+                output.emit(&loggers, |loggers| {
+                    let rows: Vec<Vec<String>> = loggers
+                        .iter()
+                        .map(|(name, logger)| vec![name.clone(), logger.level.clone()])
+                        .collect();
+                    display::render(&["LOGGER", "LEVEL"], &rows)
+                })?;
+            }
+            LoggersCommand::Get { logger } => {
+                let level = client.logger(&logger).await?;
+                output.emit(&level, |level| vec![format!("{}: {}", logger, level.level)])?;
+            }
+            LoggersCommand::Set {
+                logger,
+                level,
+                scope,
+            } => {
+                let scope = match scope {
+                    Scope::Worker => LoggerScope::Worker,
+                    Scope::Cluster => LoggerScope::Cluster,
+                };
+                let affected = client.set_logger_level(&logger, &level, scope).await?;
+                output.emit(&affected, |affected| match scope {
+                    LoggerScope::Cluster => {
+                        vec![format!("{} set to {} on every worker", logger, level)]
+                    }
+                    LoggerScope::Worker => affected
+                        .iter()
+                        .map(|name| format!("{}: {}", name, level))
+                        .collect(),
+                })?;
+            }
+        },
+        Command::Apply { file } => {
+            let specs = manifest::load(&file)?;
+            let mut failed = false;
+            let mut outcomes = Vec::with_capacity(specs.len());
+            for spec in specs {
+                match client.apply(&spec).await {
+                    Ok(outcome) => outcomes.push(Outcome::new(spec.name, outcome)),
+                    Err(e) => {
+                        failed = true;
+                        outcomes.push(Outcome::new(spec.name, format!("{:#}", e)));
+                    }
+                }
+            }
+            output.emit(&outcomes, |outcomes| outcome_lines(outcomes))?;
+            if failed {
+                return Ok(exit::FAILURE);
+            }
+        }
+        Command::Create {
+            name,
+            class,
+            settings,
+            interactive,
+        } => {
+            let config = create::parse_settings(&settings)?;
+            let info = create::run(&client, &name, &class, config, interactive).await?;
+            output.emit(&info, |info| {
+                Outcome::new(info.name.clone(), "created").lines()
+            })?;
+        }
+        #[cfg(feature = "tui")]
+        Command::Top { interval } => top::run(client, Duration::from_secs(interval)).await?,
+        Command::Ctx { .. } | Command::Lint { .. } | Command::Completions { .. } => {
+            unreachable!("handled before connecting")
+        }
+        #[cfg(feature = "schemars")]
+        Command::Schema { .. } => unreachable!("handled before connecting"),
+    }
+    Ok(exit::SUCCESS)
+}
+
+fn ctx(command: CtxCommand, output: &Output) -> Result<i32> {
+    let mut config = context::Config::load()?;
+    match command {
+        CtxCommand::List => {
+            let current = config.current_context.as_deref();
+            let rows: Vec<Vec<String>> = config
+                .contexts
+                .iter()
+                .map(|(name, context)| {
+                    let marker = if current == Some(name.as_str()) {
+                        "*"
+                    } else {
+                        ""
+                    };
+                    vec![marker.to_string(), name.clone(), context.url.clone()]
+                })
+                .collect();
+            let names: Vec<&String> = config.contexts.keys().collect();
+            output.emit(&names, |_| {
+                display::render(&["CURRENT", "NAME", "URL"], &rows)
+            })?;
+        }
+        CtxCommand::Current => {
+            let current = config
+                .current_context
+                .context("No context is currently selected")?;
+            output.emit(&current, |current| vec![current.clone()])?;
+        }
+        CtxCommand::Use { name } => {
+            if !config.contexts.contains_key(&name) {
+                anyhow::bail!("Context {} does not exist", name);
+            }
+            config.current_context = Some(name.clone());
+            config.save()?;
+            output.emit(&name, |name| vec![format!("Switched to context {}", name)])?;
+        }
+    }
+    Ok(exit::SUCCESS)
+}
+
+/// Findings of the linting of a manifest
+#[derive(Serialize)]
+struct Linting {
+    connector: String,
+    findings: Vec<Finding>,
+}
+
+fn lint(file: &Path, strict: bool, output: &Output) -> Result<i32> {
+    let lintings: Vec<Linting> = manifest::load(file)?
+        .into_iter()
+        .map(|spec| Linting {
+            findings: connect_rs::lint::lint(&spec.effective_config()),
+            connector: spec.name,
+        })
+        .collect();
+    output.emit(&lintings, |lintings| {
+        lintings
+            .iter()
+            .flat_map(|linting| {
+                let mut lines = vec![format!(
+                    "{}: {} findings",
+                    linting.connector,
+                    linting.findings.len()
+                )];
+                lines.extend(
+                    linting
+                        .findings
+                        .iter()
+                        .map(|finding| format!("  {}", finding)),
+                );
+                lines
+            })
+            .collect()
+    })?;
+    let threshold = if strict {
+        Severity::Warning
+    } else {
+        Severity::Error
+    };
+    let failed = lintings
+        .iter()
+        .any(|linting| max_severity(&linting.findings).is_some_and(|max| max >= threshold));
+    Ok(if failed { exit::FAILURE } else { exit::SUCCESS })
+}
+
+/// Outcome of the validation of a manifest
+#[derive(Serialize)]
+struct Validation {
+    connector: String,
+    valid: bool,
+    errors: BTreeMap<String, Vec<String>>,
+}
+
+fn validation_lines(validation: &Validation) -> Vec<String> {
+    if validation.valid {
+        return vec![format!("{}: valid", validation.connector)];
+    }
+    let count: usize = validation.errors.values().map(Vec::len).sum();
+    let mut lines = vec![format!("{}: {} errors", validation.connector, count)];
+    for (key, errors) in &validation.errors {
+        for error in errors {
+            lines.push(format!("  {}: {}", key, error));
+        }
+    }
+    lines
+}
+
+fn status_lines(status: &ConnectorStatus) -> Vec<String> {
+    status
+        .fmt_table(std::io::stdout().is_terminal())
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+fn status_table(
+    statuses: &BTreeMap<String, ConnectorStatus>,
+    highlighted: &HashSet<&str>,
+) -> Vec<String> {
+    let color = std::io::stdout().is_terminal();
+    let rows: Vec<Vec<String>> = statuses
+        .values()
+        .map(|status| {
+            // the colors of the states would end the highlight early
+            display::listing_row(status, color && !highlighted.contains(status.name.as_str()))
+        })
+        .collect();
+    let mut lines = display::render(&display::LISTING_HEADERS, &rows).into_iter();
+    // the header is never highlighted
+    let header = lines.next();
+    header
+        .into_iter()
+        .chain(lines.zip(statuses.keys()).map(|(line, name)| {
+            if highlighted.contains(name.as_str()) {
+                format!("\x1b[1;33m{}\x1b[0m", line)
+            } else {
+                line
+            }
+        }))
+        .collect()
+}
+
+fn describe(event: &WatchEvent) -> Option<String> {
+    match event {
+        WatchEvent::Added { .. } => None,
+        WatchEvent::Removed { name } => Some(format!("{}: removed", name)),
+        WatchEvent::StateChanged { name, from, to } => {
+            Some(format!("{}: {} -> {}", name, from, to))
+        }
+        WatchEvent::TaskStateChanged {
+            name,
+            task,
+            from,
+            to,
+        } => Some(format!(
+            "{} task {}: {} -> {}",
+            name,
+            task,
+            from.map_or("NEW".to_string(), |from| from.to_string()),
+            to
+        )),
+    }
+}
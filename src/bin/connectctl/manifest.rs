@@ -0,0 +1,39 @@
+//! Loading of connector manifests from files and directories
+use anyhow::{Context, Result};
+use connect_rs::apply::ConnectorSpec;
+use connect_rs::formats::{self, Format};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Loads the manifests of a file, or of every file of a directory sorted by file name
+pub fn load(path: &Path) -> Result<Vec<ConnectorSpec>> {
+    if !path.is_dir() {
+        return formats::load_manifests(path);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(path)
+        .with_context(|| format!("Could not read directory {}", path.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| Format::from_path(file).is_ok())
+        .collect();
+    files.sort();
+    let mut specs = Vec::new();
+    for file in &files {
+        specs.extend(formats::load_manifests(file)?);
+    }
+    Ok(specs)
+}
+
+/// Writes a manifest to the directory, in a file named after the connector
+pub fn save(dir: &Path, spec: &ConnectorSpec) -> Result<PathBuf> {
+    let file_name: String = spec
+        .name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' => '_',
+            c => c,
+        })
+        .collect();
+    let path = dir.join(format!("{}.yaml", file_name));
+    formats::save_manifests(&path, std::slice::from_ref(spec))?;
+    Ok(path)
+}
@@ -0,0 +1,84 @@
+//! Offsets subcommands
+use crate::cli::OffsetsCommand;
+use crate::output::{Outcome, Output};
+use anyhow::{Context, Result};
+use connect_rs::models::Status;
+use connect_rs::offsets::ConnectorOffsets;
+use connect_rs::Connect;
+use std::time::Duration;
+
+const STOP_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub async fn run(client: &Connect, command: OffsetsCommand, output: &Output) -> Result<()> {
+    match command {
+        OffsetsCommand::Get { connector } => {
+            let offsets = client.connector_offsets(&connector).await?;
+            // offsets have no tabular form, tables fall back to json
+            output.emit(&offsets, |offsets| {
+                serde_json::to_string_pretty(offsets)
+                    .map(|json| json.lines().map(String::from).collect())
+                    .unwrap_or_default()
+            })?;
+        }
+        OffsetsCommand::Alter {
+            connector,
+            file,
+            safe,
+        } => {
+            let content = std::fs::read_to_string(&file)
+                .with_context(|| format!("Could not read {}", file.display()))?;
+            let offsets: ConnectorOffsets = serde_yaml::from_str(&content)
+                .with_context(|| format!("Invalid offsets in {}", file.display()))?;
+            let previous = prepare(client, &connector, safe).await?;
+            let result = client.alter_connector_offsets(&connector, &offsets).await;
+            let message = finish(client, &connector, previous, result).await?;
+            output.emit(&Outcome::new(connector, message), Outcome::lines)?;
+        }
+        OffsetsCommand::Reset { connector, safe } => {
+            let previous = prepare(client, &connector, safe).await?;
+            let result = client.reset_connector_offsets(&connector).await;
+            let message = finish(client, &connector, previous, result).await?;
+            output.emit(&Outcome::new(connector, message), Outcome::lines)?;
+        }
+    }
+    Ok(())
+}
+
+/// Stops the connector in safe mode, returning the state to bring it back to
+async fn prepare(client: &Connect, connector: &str, safe: bool) -> Result<Option<Status>> {
+    if !safe {
+        return Ok(None);
+    }
+    let previous = client.connector_status(connector).await?.connector.state;
+    client.stop_and_wait(connector, STOP_TIMEOUT).await?;
+    Ok(Some(previous))
+}
+
+/// Brings the connector back to its previous state, even on failure, and returns the outcome
+/// along with a failure to do so. The connector is resumed unless it was paused or stopped, as
+/// failed connectors are meant to run as well
+async fn finish(
+    client: &Connect,
+    connector: &str,
+    previous: Option<Status>,
+    result: Result<String>,
+) -> Result<String> {
+    let restored = match previous {
+        None | Some(Status::Stopped) => return result,
+        Some(Status::Paused) => client.pause_connector(connector).await,
+        Some(_) => client.resume_connector(connector).await,
+    };
+    let Err(err) = restored else {
+        return result;
+    };
+    match result {
+        Ok(message) => Err(err.context(format!(
+            "{}, but connector {} could not be brought back to its previous state",
+            message, connector
+        ))),
+        Err(failure) => Err(failure.context(format!(
+            "Connector {} could not be brought back to its previous state either: {}",
+            connector, err
+        ))),
+    }
+}
@@ -0,0 +1,75 @@
+//! Rendering of the results of every command
+use crate::cli::OutputFormat;
+use anyhow::Result;
+use serde::Serialize;
+
+pub struct Output {
+    format: OutputFormat,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn is_table(&self) -> bool {
+        matches!(self.format, OutputFormat::Table)
+    }
+
+    /// Prints the value in the selected format, using `table` to render the human readable form
+    pub fn emit<T, F>(&self, value: &T, table: F) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+        F: FnOnce(&T) -> Vec<String>,
+    {
+        match self.format {
+            OutputFormat::Table => {
+                for line in table(value) {
+                    println!("{}", line);
+                }
+            }
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+            OutputFormat::Yaml => print!("{}", serde_yaml::to_string(value)?),
+        }
+        Ok(())
+    }
+
+    /// Prints the value on a single line, for streams of values such as watch events
+    pub fn emit_line<T, F>(&self, value: &T, table: F) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+        F: FnOnce(&T) -> Vec<String>,
+    {
+        match self.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string(value)?),
+            OutputFormat::Yaml => print!("---\n{}", serde_yaml::to_string(value)?),
+            OutputFormat::Table => self.emit(value, table)?,
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of an action on a single connector
+#[derive(Serialize)]
+pub struct Outcome {
+    pub connector: String,
+    pub outcome: String,
+}
+
+impl Outcome {
+    pub fn new(connector: impl Into<String>, outcome: impl ToString) -> Self {
+        Self {
+            connector: connector.into(),
+            outcome: outcome.to_string(),
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        vec![format!("{}: {}", self.connector, self.outcome)]
+    }
+}
+
+/// Renders outcomes as `connector: outcome` lines
+pub fn outcome_lines(outcomes: &[Outcome]) -> Vec<String> {
+    outcomes.iter().flat_map(Outcome::lines).collect()
+}
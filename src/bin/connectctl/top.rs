@@ -0,0 +1,180 @@
+//! Terminal dashboard of the connectors of a cluster
+use anyhow::Result;
+use connect_rs::models::{ConnectorStatus, Status};
+use connect_rs::Connect;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+const KEY_POLL: Duration = Duration::from_millis(100);
+const HELP: &str = "q quit  ↑/↓ select  r restart  p pause  u resume";
+
+struct Dashboard {
+    statuses: Vec<ConnectorStatus>,
+    table: TableState,
+    message: String,
+}
+
+impl Dashboard {
+    fn selected(&self) -> Option<&str> {
+        self.table
+            .selected()
+            .and_then(|index| self.statuses.get(index))
+            .map(|status| status.name.as_str())
+    }
+
+    fn update(&mut self, statuses: BTreeMap<String, ConnectorStatus>) {
+        // keep the same connector selected when others appear or disappear
+        let selected = self.selected().map(String::from);
+        self.statuses = statuses.into_values().collect();
+        let index = selected
+            .and_then(|name| self.statuses.iter().position(|status| status.name == name))
+            .or((!self.statuses.is_empty()).then_some(0));
+        self.table.select(index);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [connectors, workers, footer] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(7),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+
+        let rows = self.statuses.iter().map(|status| {
+            let style = match status.connector.state {
+                Status::Running if status.failed_tasks().is_empty() => Style::default(),
+                Status::Paused | Status::Stopped => Style::default().fg(Color::Yellow),
+                _ => Style::default().fg(Color::Red),
+            };
+            Row::new(vec![
+                status.name.clone(),
+                status.kind.clone(),
+                status.connector.state.to_string(),
+                format!("{}/{}", status.running_task_count(), status.tasks.len()),
+                status.failed_tasks().len().to_string(),
+                status.workers().into_iter().collect::<Vec<_>>().join(","),
+            ])
+            .style(style)
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(30),
+                Constraint::Length(8),
+                Constraint::Length(12),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(
+            Row::new(["CONNECTOR", "TYPE", "STATE", "TASKS", "FAILED", "WORKERS"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::bordered().title(" connectors "));
+        frame.render_stateful_widget(table, connectors, &mut self.table);
+
+        let mut distribution: BTreeMap<&str, usize> = BTreeMap::new();
+        for task in self.statuses.iter().flat_map(|status| &status.tasks) {
+            *distribution.entry(task.worker_id.as_str()).or_default() += 1;
+        }
+        let lines: Vec<Line> = distribution
+            .iter()
+            .map(|(worker, tasks)| Line::from(format!("{:<40} {} tasks", worker, tasks)))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" workers ")),
+            workers,
+        );
+        frame.render_widget(
+            Paragraph::new(vec![Line::from(self.message.as_str()), Line::from(HELP)]),
+            footer,
+        );
+    }
+}
+
+pub async fn run(client: Connect, interval: Duration) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = dashboard(&mut terminal, client, interval).await;
+    ratatui::restore();
+    result
+}
+
+async fn dashboard(
+    terminal: &mut DefaultTerminal,
+    client: Connect,
+    interval: Duration,
+) -> Result<()> {
+    let mut dashboard = Dashboard {
+        statuses: Vec::new(),
+        table: TableState::default(),
+        message: String::new(),
+    };
+    dashboard.update(statuses(&client).await?);
+    let mut refreshed = Instant::now();
+    let mut stale = false;
+    loop {
+        terminal.draw(|frame| dashboard.draw(frame))?;
+        if stale || refreshed.elapsed() >= interval {
+            match statuses(&client).await {
+                Ok(statuses) => dashboard.update(statuses),
+                Err(e) => dashboard.message = format!("Refresh failed: {:#}", e),
+            }
+            refreshed = Instant::now();
+            stale = false;
+        }
+        if !event::poll(KEY_POLL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Down | KeyCode::Char('j') => dashboard.table.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => dashboard.table.select_previous(),
+            KeyCode::Char(action @ ('r' | 'p' | 'u')) => {
+                let Some(name) = dashboard.selected().map(String::from) else {
+                    continue;
+                };
+                let (result, done) = match action {
+                    'r' => (
+                        client
+                            .restart_connector(&name, true, false)
+                            .await
+                            .map(|_| ()),
+                        "restarting",
+                    ),
+                    'p' => (client.pause_connector(&name).await, "pausing"),
+                    _ => (client.resume_connector(&name).await, "resuming"),
+                };
+                dashboard.message = match result {
+                    Ok(()) => format!("{}: {}", name, done),
+                    Err(e) => format!("{}: {:#}", name, e),
+                };
+                // show the effect of the action without waiting for the next refresh
+                stale = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn statuses(client: &Connect) -> Result<BTreeMap<String, ConnectorStatus>> {
+    Ok(client
+        .connectors(true, false)
+        .await?
+        .into_iter()
+        .filter_map(|(name, connector)| connector.status.map(|status| (name, status)))
+        .collect())
+}
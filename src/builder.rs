@@ -0,0 +1,319 @@
+//! Configuration of the client beyond an address and credentials
+use crate::activity::{ActivityRecorder, Recording};
+use crate::credentials::{Credentials, CredentialsMiddleware, CredentialsProvider};
+use crate::dry_run::DryRun;
+use crate::hedging::Hedging;
+use crate::naming::NamingPolicy;
+use crate::plugins::PluginPreflight;
+use crate::recycle::{BuildClient, Recycling};
+use crate::redirect::{RedirectPolicy, Redirects};
+use crate::retry::RetryClassification;
+use crate::Connect;
+use anyhow::{Context, Result};
+use reqwest::{redirect, Certificate, Client};
+use reqwest_middleware::{ClientBuilder, Middleware};
+use reqwest_retry::RetryTransientMiddleware;
+use retry_policies::policies::ExponentialBackoff;
+use retry_policies::Jitter;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone, Default)]
+pub struct ConnectBuilder {
+    address: String,
+    credentials: Option<(String, Option<String>)>,
+    credentials_provider: Option<Arc<dyn CredentialsProvider>>,
+    ca_certificates: Vec<Vec<u8>>,
+    accept_invalid_certificates: bool,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    hedging: Option<(Vec<String>, Duration)>,
+    max_response_size: Option<usize>,
+    http2_prior_knowledge: bool,
+    http2_adaptive_window: bool,
+    http2_keep_alive_interval: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    dry_run: Option<DryRun>,
+    naming_policy: Option<Arc<dyn NamingPolicy>>,
+    plugin_preflight: Option<PluginPreflight>,
+    retry_classification: RetryClassification,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    connection_max_lifetime: Option<Duration>,
+    retry_stale_connections: bool,
+    redirect_policy: RedirectPolicy,
+    activity: Option<ActivityRecorder>,
+}
+
+impl fmt::Debug for ConnectBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectBuilder")
+            .field("address", &self.address)
+            .field("credentials_provider", &self.credentials_provider.is_some())
+            .field("ca_certificates", &self.ca_certificates.len())
+            .field(
+                "accept_invalid_certificates",
+                &self.accept_invalid_certificates,
+            )
+            .field("middlewares", &self.middlewares.len())
+            .field("hedging", &self.hedging)
+            .field("max_response_size", &self.max_response_size)
+            .field("http2_prior_knowledge", &self.http2_prior_knowledge)
+            .field("http2_adaptive_window", &self.http2_adaptive_window)
+            .field("http2_keep_alive_interval", &self.http2_keep_alive_interval)
+            .field("tcp_keepalive", &self.tcp_keepalive)
+            .field("dry_run", &self.dry_run.is_some())
+            .field("naming_policy", &self.naming_policy)
+            .field("plugin_preflight", &self.plugin_preflight)
+            .field("retry_classification", &self.retry_classification)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("connection_max_lifetime", &self.connection_max_lifetime)
+            .field("retry_stale_connections", &self.retry_stale_connections)
+            .field("redirect_policy", &self.redirect_policy)
+            .field("activity", &self.activity.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConnectBuilder {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Authenticates every request with basic authentication
+    pub fn basic_auth(mut self, username: &str, password: Option<&str>) -> Self {
+        self.credentials = Some((username.to_string(), password.map(String::from)));
+        self
+    }
+
+    /// Asks the provider for the credentials of every request, instead of fixed ones. Takes
+    /// precedence over [`ConnectBuilder::basic_auth`]
+    pub fn credentials_provider<P: CredentialsProvider>(mut self, provider: P) -> Self {
+        self.credentials_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Trusts an additional certificate authority, PEM encoded
+    pub fn ca_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.ca_certificates.push(pem.into());
+        self
+    }
+
+    /// Disables the verification of the server certificate. Only meant for development clusters
+    pub fn accept_invalid_certificates(mut self, accept: bool) -> Self {
+        self.accept_invalid_certificates = accept;
+        self
+    }
+
+    /// Adds a middleware, run on every attempt after the retry middleware and in the order added
+    pub fn middleware<M: Middleware>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Sends reads that take longer than `threshold` again to one of the other workers, in turn,
+    /// and keeps the first successful response. Only GET requests are hedged
+    pub fn hedged_reads(mut self, workers: &[&str], threshold: Duration) -> Self {
+        let workers = workers.iter().map(|worker| worker.to_string()).collect();
+        self.hedging = Some((workers, threshold));
+        self
+    }
+
+    /// Fails reading bodies larger than `bytes` with [`ConnectError::ResponseTooLarge`],
+    /// instead of buffering whatever the server sends
+    ///
+    /// [`ConnectError::ResponseTooLarge`]: crate::error::ConnectError::ResponseTooLarge
+    pub fn max_response_size(mut self, bytes: usize) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    /// Talks HTTP/2 without negotiating it first, for workers behind an HTTP/2 only proxy
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.http2_prior_knowledge = true;
+        self
+    }
+
+    /// Sizes the HTTP/2 flow control window from the measured bandwidth, for large listings
+    pub fn http2_adaptive_window(mut self, enabled: bool) -> Self {
+        self.http2_adaptive_window = enabled;
+        self
+    }
+
+    /// Sends HTTP/2 pings on idle connections at this interval
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.http2_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Enables TCP keepalive probes at this interval, so that NAT gateways and load balancers do
+    /// not silently drop the idle connections of long running watchers
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Previews the mutations in the dry run instead of making them, see [`crate::dry_run`]
+    pub fn dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = Some(dry_run);
+        self
+    }
+
+    /// Rejects the names of new connectors that do not follow the policy
+    pub fn naming_policy<P: NamingPolicy + 'static>(mut self, policy: P) -> Self {
+        self.naming_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Checks that the class of new connectors is installed before creating them, see
+    /// [`crate::plugins`]
+    pub fn plugin_preflight(mut self, preflight: PluginPreflight) -> Self {
+        self.plugin_preflight = Some(preflight);
+        self
+    }
+
+    /// Changes the statuses and errors retried, see [`crate::retry`]
+    pub fn retry_classification(mut self, classification: RetryClassification) -> Self {
+        self.retry_classification = classification;
+        self
+    }
+
+    /// Closes the idle connections of the pool after this time
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Keeps at most this many idle connections per host, none disabling reuse
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Opens new connections, resolving the name of the address again, once the connections are
+    /// this old, so that a long lived client follows a service whose IP changed
+    pub fn connection_max_lifetime(mut self, lifetime: Duration) -> Self {
+        self.connection_max_lifetime = Some(lifetime);
+        self
+    }
+
+    /// Sends a request again at once on a new connection when the server or a proxy closed the
+    /// idle connection it was sent on
+    pub fn retry_stale_connections(mut self, retry: bool) -> Self {
+        self.retry_stale_connections = retry;
+        self
+    }
+
+    /// Changes how redirects are followed, see [`crate::redirect`]
+    pub fn redirect_policy(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect_policy = policy;
+        self
+    }
+
+    /// Keeps the last interactions in the recorder, see [`crate::activity`]
+    pub fn activity_recorder(mut self, recorder: ActivityRecorder) -> Self {
+        self.activity = Some(recorder);
+        self
+    }
+
+    fn http_client(&self) -> Result<Client> {
+        let mut client = Client::builder()
+            // followed by the redirect middleware, which controls the headers sent again
+            .redirect(redirect::Policy::none())
+            .danger_accept_invalid_certs(self.accept_invalid_certificates)
+            .http2_adaptive_window(self.http2_adaptive_window)
+            .tcp_keepalive(self.tcp_keepalive)
+            .pool_idle_timeout(self.pool_idle_timeout);
+        if let Some(max) = self.pool_max_idle_per_host {
+            client = client.pool_max_idle_per_host(max);
+        }
+        if self.http2_prior_knowledge {
+            client = client.http2_prior_knowledge();
+        }
+        if let Some(interval) = self.http2_keep_alive_interval {
+            client = client
+                .http2_keep_alive_interval(interval)
+                .http2_keep_alive_while_idle(true);
+        }
+        for pem in &self.ca_certificates {
+            client = client.add_root_certificate(
+                Certificate::from_pem(pem).context("Invalid CA certificate")?,
+            );
+        }
+        Ok(client.build()?)
+    }
+
+    pub fn build(self) -> Result<Connect> {
+        let http = self.http_client()?;
+        let recycling = match (self.connection_max_lifetime, self.retry_stale_connections) {
+            (None, false) => None,
+            (lifetime, retry_stale) => {
+                let builder = self.clone();
+                let build: BuildClient = Arc::new(move || builder.http_client());
+                Some(Arc::new(Recycling::new(build, lifetime, retry_stale)?))
+            }
+        };
+        // setup backoff
+        let policy = ExponentialBackoff::builder()
+            .retry_bounds(Duration::from_secs(1), Duration::from_secs(60))
+            .jitter(Jitter::Bounded)
+            .base(2)
+            .build_with_total_retry_duration(Duration::from_secs(600));
+        let retry_transient_middleware = RetryTransientMiddleware::new_with_policy_and_strategy(
+            policy,
+            self.retry_classification,
+        );
+        let mut client = ClientBuilder::new(http.clone()).with(retry_transient_middleware);
+        // probes share the connection pool, without retries nor hedging
+        let mut probe = ClientBuilder::new(http);
+        let basic = self.credentials.map(|(username, password)| {
+            Arc::new(Credentials::Basic { username, password }) as Arc<dyn CredentialsProvider>
+        });
+        if let Some(provider) = self.credentials_provider.or(basic) {
+            let credentials = Arc::new(CredentialsMiddleware(provider));
+            client = client.with_arc(credentials.clone());
+            probe = probe.with_arc(credentials);
+        }
+        let redirects = Arc::new(Redirects(self.redirect_policy));
+        client = client.with_arc(redirects.clone());
+        probe = probe.with_arc(redirects);
+        if let Some((workers, threshold)) = self.hedging {
+            client = client.with(Hedging::new(&self.address, workers, threshold));
+        }
+        let mut client = self
+            .middlewares
+            .iter()
+            .cloned()
+            .fold(client, ClientBuilder::with_arc);
+        let mut probe = self
+            .middlewares
+            .into_iter()
+            .fold(probe, ClientBuilder::with_arc);
+        if let Some(recorder) = &self.activity {
+            let recording = Arc::new(Recording(recorder.clone()));
+            client = client.with_arc(recording.clone());
+            probe = probe.with_arc(recording);
+        }
+        // sends the requests, after every other middleware
+        if let Some(recycling) = recycling {
+            client = client.with_arc(recycling.clone());
+            probe = probe.with_arc(recycling);
+        }
+        let (client, probe) = (client.build(), probe.build());
+        Ok(Connect {
+            client,
+            probe,
+            address: self.address,
+            max_response_size: self.max_response_size,
+            capabilities: Default::default(),
+            dry_run: self.dry_run,
+            naming_policy: self.naming_policy,
+            plugin_preflight: self.plugin_preflight,
+            activity: self.activity,
+        })
+    }
+}
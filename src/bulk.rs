@@ -0,0 +1,136 @@
+//! Operations spanning many connectors at once
+use crate::filter::ConnectorFilter;
+use crate::models::{ConnectorStatus, Status};
+use crate::restart_budget::{Admission, RestartBudget};
+use crate::Connect;
+use anyhow::Result;
+use futures::{stream, Stream, StreamExt};
+use serde::Serialize;
+
+/// Options of a restart sweep
+#[derive(Clone, Debug)]
+pub struct RestartFailedOptions {
+    /// Only connectors selected by the filter are restarted
+    pub filter: ConnectorFilter,
+    /// Finds the failed connectors without restarting them
+    pub dry_run: bool,
+    /// Maximum number of restarts running at the same time
+    pub concurrency: usize,
+    /// Restarts allowed, shared across sweeps. Unlimited when unset
+    pub budget: Option<RestartBudget>,
+}
+
+impl Default for RestartFailedOptions {
+    fn default() -> Self {
+        Self {
+            filter: ConnectorFilter::new(),
+            dry_run: false,
+            concurrency: 4,
+            budget: None,
+        }
+    }
+}
+
+/// Outcome of a restart sweep
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RestartSummary {
+    /// Connectors that are failed or have failed tasks
+    pub failed: Vec<String>,
+    pub restarted: Vec<String>,
+    /// Connectors whose restart request failed, along with the error
+    pub errors: Vec<(String, String)>,
+    /// Connectors that used up their restart budget during this sweep
+    pub given_up: Vec<String>,
+    /// Connectors left failed because of the budget, cooling down, backing off or over the
+    /// global budget
+    pub deferred: Vec<String>,
+}
+
+impl Connect {
+    /// Fetches the status of each connector with individual requests, at most `concurrency` at a time.
+    /// Statuses are yielded as they complete, which on large clusters is often faster than an expanded listing
+    pub fn status_stream<'a, I>(
+        &'a self,
+        names: I,
+        concurrency: usize,
+    ) -> impl Stream<Item = (String, Result<ConnectorStatus>)> + 'a
+    where
+        I: IntoIterator<Item = String>,
+        I::IntoIter: 'a,
+    {
+        stream::iter(names)
+            .map(move |name| async move {
+                let status = self.connector_status(&name).await;
+                (name, status)
+            })
+            .buffer_unordered(concurrency.max(1))
+    }
+
+    /// Collects [`Connect::status_stream`], in completion order
+    pub async fn statuses_for(
+        &self,
+        names: &[String],
+        concurrency: usize,
+    ) -> Vec<(String, Result<ConnectorStatus>)> {
+        self.status_stream(names.iter().cloned(), concurrency)
+            .collect()
+            .await
+    }
+
+    /// Restarts every failed connector and failed task of the cluster
+    pub async fn restart_failed(&self, options: &RestartFailedOptions) -> Result<RestartSummary> {
+        let (_, expand_info) = options.filter.expand();
+        let (failed, healthy): (Vec<_>, Vec<_>) = self
+            .connectors(true, expand_info)
+            .await?
+            .into_iter()
+            .filter(|(name, connector)| options.filter.matches(name, connector))
+            .filter_map(|(name, connector)| connector.status.map(|status| (name, status)))
+            .partition(|(_, status)| {
+                status.connector.state == Status::Failed || !status.failed_tasks().is_empty()
+            });
+        if let Some(budget) = &options.budget {
+            for (name, _) in &healthy {
+                budget.observe_healthy(name);
+            }
+        }
+        let mut failed: Vec<String> = failed.into_iter().map(|(name, _)| name).collect();
+        failed.sort();
+        let mut summary = RestartSummary {
+            failed,
+            ..Default::default()
+        };
+        if options.dry_run {
+            return Ok(summary);
+        }
+        let mut admitted = Vec::new();
+        for name in &summary.failed {
+            match options.budget.as_ref().map(|budget| budget.admit(name)) {
+                None | Some(Admission::Allowed) => admitted.push(name.clone()),
+                Some(Admission::GivenUp) => summary.given_up.push(name.clone()),
+                Some(
+                    Admission::CoolingDown(_)
+                    | Admission::BackingOff(_)
+                    | Admission::GlobalExhausted,
+                ) => summary.deferred.push(name.clone()),
+            }
+        }
+        let results: Vec<(String, Result<()>)> = stream::iter(admitted)
+            .map(|name| async move {
+                let result = self.restart_connector(&name, true, true).await.map(|_| ());
+                (name, result)
+            })
+            .buffer_unordered(options.concurrency.max(1))
+            .collect()
+            .await;
+        for (name, result) in results {
+            match result {
+                Ok(()) => summary.restarted.push(name),
+                Err(e) => summary.errors.push((name, e.to_string())),
+            }
+        }
+        summary.restarted.sort();
+        summary.errors.sort();
+        Ok(summary)
+    }
+}
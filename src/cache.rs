@@ -0,0 +1,120 @@
+//! Cache of the state of a cluster, refreshed in the background
+//! Many readers can share one cache instead of each polling the REST API
+use crate::models::{Connector, ConnectorStatus};
+use crate::watch::{self, WatchEvent};
+use crate::Connect;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch as channel;
+use tokio::task::JoinHandle;
+
+/// Expanded connectors of the cluster at the last refresh that changed anything
+#[derive(Clone, Debug, Default)]
+pub struct ClusterState {
+    /// Unix timestamp in seconds of the refresh, None before the first one completes
+    pub refreshed_at: Option<u64>,
+    pub connectors: HashMap<String, Connector>,
+    /// Changes of the statuses since the previous state
+    pub events: Vec<WatchEvent>,
+}
+
+/// Refreshes the expanded connector map on an interval until dropped
+pub struct StateCache {
+    state: channel::Receiver<Arc<ClusterState>>,
+    error: Arc<RwLock<Option<String>>>,
+    refresher: JoinHandle<()>,
+}
+
+impl StateCache {
+    /// Starts refreshing in the background. The state is empty until the first refresh completes
+    pub fn start(client: Connect, interval: Duration) -> Self {
+        let (sender, state) = channel::channel(Arc::new(ClusterState::default()));
+        let error = Arc::new(RwLock::new(None));
+        let refresher = tokio::spawn(refresh(client, interval, sender, error.clone()));
+        Self {
+            state,
+            error,
+            refresher,
+        }
+    }
+
+    /// Latest state, without waiting for a refresh
+    pub fn snapshot(&self) -> Arc<ClusterState> {
+        self.state.borrow().clone()
+    }
+
+    /// Waits for the first refresh to complete
+    pub async fn initialized(&self) -> Arc<ClusterState> {
+        let mut receiver = self.state.clone();
+        let initialized = receiver
+            .wait_for(|state| state.refreshed_at.is_some())
+            .await
+            .map(|state| state.clone());
+        // the refresher only stops when the cache is dropped
+        initialized.unwrap_or_else(|_| self.snapshot())
+    }
+
+    pub fn connector(&self, name: &str) -> Option<Connector> {
+        self.state.borrow().connectors.get(name).cloned()
+    }
+
+    /// Error of the last refresh, if it failed. The state is left as it was meanwhile
+    pub fn last_error(&self) -> Option<String> {
+        self.error.read().unwrap().clone()
+    }
+
+    /// Receiver notified whenever a refresh changes the state
+    pub fn subscribe(&self) -> channel::Receiver<Arc<ClusterState>> {
+        let mut receiver = self.state.clone();
+        receiver.mark_unchanged();
+        receiver
+    }
+}
+
+impl Drop for StateCache {
+    fn drop(&mut self) {
+        self.refresher.abort();
+    }
+}
+
+async fn refresh(
+    client: Connect,
+    interval: Duration,
+    sender: channel::Sender<Arc<ClusterState>>,
+    error: Arc<RwLock<Option<String>>>,
+) {
+    loop {
+        match client.connectors(true, true).await {
+            Ok(connectors) => {
+                *error.write().unwrap() = None;
+                sender.send_if_modified(|state| {
+                    if state.refreshed_at.is_some() && state.connectors == connectors {
+                        return false;
+                    }
+                    let events =
+                        watch::diff(&statuses_of(&state.connectors), &statuses_of(&connectors));
+                    let refreshed_at = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|elapsed| elapsed.as_secs())
+                        .ok();
+                    *state = Arc::new(ClusterState {
+                        refreshed_at,
+                        connectors,
+                        events,
+                    });
+                    true
+                });
+            }
+            Err(e) => *error.write().unwrap() = Some(e.to_string()),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+fn statuses_of(connectors: &HashMap<String, Connector>) -> BTreeMap<String, ConnectorStatus> {
+    connectors
+        .iter()
+        .filter_map(|(name, connector)| Some((name.clone(), connector.status.clone()?)))
+        .collect()
+}
@@ -0,0 +1,183 @@
+//! Endpoints available on a cluster, derived from its version
+//! Older workers answer endpoints they do not know with a 404, which reads like a missing
+//! connector, or silently ignore parameters such as the scope of loggers. Methods relying on such
+//! endpoints fail with [`ConnectError::UnsupportedByCluster`] instead
+use crate::error::ConnectError;
+use crate::models::ClusterInfo;
+use crate::Connect;
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use semver::Version;
+use serde::Serialize;
+use std::fmt;
+
+/// An endpoint or parameter introduced after the first versions of kafka-connect
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// `includeTasks` and `onlyFailed` of restarts, from 3.0
+    RestartTasks,
+    /// Stopping connectors, from 3.5
+    Stop,
+    /// Reading offsets, from 3.5
+    ReadOffsets,
+    /// Altering and resetting offsets, from 3.6
+    AlterOffsets,
+    /// Patching configurations, from 3.5
+    PatchConfig,
+    /// Setting logger levels on every worker, from 3.7
+    ClusterLoggers,
+    /// Creating connectors paused or stopped, from 3.7
+    InitialState,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 7] = [
+        Self::RestartTasks,
+        Self::Stop,
+        Self::ReadOffsets,
+        Self::AlterOffsets,
+        Self::PatchConfig,
+        Self::ClusterLoggers,
+        Self::InitialState,
+    ];
+
+    /// First Apache Kafka version offering the feature, as major and minor
+    pub fn since(&self) -> (u64, u64) {
+        match self {
+            Self::RestartTasks => (3, 0),
+            Self::Stop | Self::ReadOffsets | Self::PatchConfig => (3, 5),
+            Self::AlterOffsets => (3, 6),
+            Self::ClusterLoggers | Self::InitialState => (3, 7),
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let feature = match self {
+            Self::RestartTasks => "restarting tasks along with connectors",
+            Self::Stop => "stopping connectors",
+            Self::ReadOffsets => "reading offsets",
+            Self::AlterOffsets => "altering offsets",
+            Self::PatchConfig => "patching configurations",
+            Self::ClusterLoggers => "setting logger levels across the cluster",
+            Self::InitialState => "creating connectors in a given state",
+        };
+        f.write_str(feature)
+    }
+}
+
+impl ClusterInfo {
+    /// Apache Kafka version of the cluster. Confluent Platform versions, such as `7.5.0-ccs`, are
+    /// mapped to the Kafka version they ship
+    pub fn kafka_version(&self) -> Result<Version> {
+        let (release, suffix) = self
+            .version
+            .split_once('-')
+            .unwrap_or((self.version.as_str(), ""));
+        let mut parts = release.split('.').map(str::parse::<u64>);
+        let mut next = || parts.next().transpose();
+        let invalid = || format!("Invalid cluster version {}", self.version);
+        let major = next().with_context(invalid)?.with_context(invalid)?;
+        let minor = next().with_context(invalid)?.unwrap_or_default();
+        let patch = next().with_context(invalid)?.unwrap_or_default();
+        let confluent = matches!(suffix, "ccs" | "ce") && major >= 5;
+        let (major, minor) = match (confluent, major) {
+            (false, _) => (major, minor),
+            (true, 5) => (2, minor),
+            (true, 6) => (2, minor + 6),
+            (true, major) => (major - 4, minor),
+        };
+        Ok(Version::new(major, minor, patch))
+    }
+}
+
+/// What a cluster offers, according to its version
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Capabilities {
+    /// Apache Kafka version of the cluster
+    #[serde(serialize_with = "serialize_version")]
+    pub version: Version,
+}
+
+fn serialize_version<S: serde::Serializer>(
+    version: &Version,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(version)
+}
+
+impl Capabilities {
+    pub fn new(version: Version) -> Self {
+        Self { version }
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        (self.version.major, self.version.minor) >= feature.since()
+    }
+
+    pub fn supported(&self) -> Vec<Feature> {
+        Feature::ALL
+            .into_iter()
+            .filter(|feature| self.supports(*feature))
+            .collect()
+    }
+}
+
+impl Connect {
+    /// Capabilities of the cluster, read from its version once and kept for the life of the client
+    pub async fn capabilities(&self) -> Result<Capabilities> {
+        let capabilities = self
+            .capabilities
+            .get_or_try_init(|| async {
+                let version = self.info().await?.kafka_version()?;
+                anyhow::Ok(Capabilities::new(version))
+            })
+            .await?;
+        Ok(capabilities.clone())
+    }
+
+    /// Fails with [`ConnectError::UnsupportedByCluster`] when the cluster lacks the feature
+    pub async fn require(&self, feature: Feature) -> Result<()> {
+        let capabilities = self.capabilities().await?;
+        if !capabilities.supports(feature) {
+            anyhow::bail!(ConnectError::UnsupportedByCluster(format!(
+                "The cluster runs kafka {}, {} needs {}.{} or later",
+                capabilities.version,
+                feature,
+                feature.since().0,
+                feature.since().1
+            )));
+        }
+        Ok(())
+    }
+
+    /// Same as [`Connect::require`], letting the request through when the version cannot be read
+    pub(crate) async fn gate(&self, feature: Feature) -> Result<()> {
+        match self.require(feature).await {
+            Err(err)
+                if matches!(
+                    err.downcast_ref(),
+                    Some(ConnectError::UnsupportedByCluster(_))
+                ) =>
+            {
+                Err(err)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Tells an unknown endpoint apart from a missing connector, once a request failed with 404
+    /// or 405. The version is only looked up then, so supported requests cost nothing more
+    pub(crate) async fn check_endpoint(
+        &self,
+        status_code: StatusCode,
+        feature: Feature,
+    ) -> Result<()> {
+        match status_code {
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => self.gate(feature).await,
+            _ => Ok(()),
+        }
+    }
+}
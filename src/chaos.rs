@@ -0,0 +1,190 @@
+//! Chaos testing: randomly disrupts connectors to validate the resilience of their consumers
+//! Only available with the `chaos` feature
+//!
+//! **This pauses and restarts real connectors.** Point it at staging clusters only, and list every
+//! connector that must not be touched in [`ChaosOptions::protected`]
+use crate::Connect;
+use anyhow::Result;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::SeedableRng;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChaosAction {
+    /// Pauses the connector until the next round
+    Pause,
+    /// Restarts the connector and all of its tasks
+    Restart,
+    /// Resumes a connector paused by a previous round
+    Resume,
+}
+
+impl fmt::Display for ChaosAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            Self::Pause => "pause",
+            Self::Restart => "restart",
+            Self::Resume => "resume",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChaosOptions {
+    /// Time between two rounds
+    pub interval: Duration,
+    /// Maximum number of connectors disrupted per round
+    pub victims_per_round: usize,
+    /// Actions picked from at random. Resume is ignored, paused connectors are always resumed
+    pub actions: Vec<ChaosAction>,
+    /// Connectors never disrupted
+    pub protected: HashSet<String>,
+    /// Seed of the random choices, to reproduce a run
+    pub seed: Option<u64>,
+}
+
+impl Default for ChaosOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            victims_per_round: 1,
+            actions: vec![ChaosAction::Pause, ChaosAction::Restart],
+            protected: HashSet::new(),
+            seed: None,
+        }
+    }
+}
+
+/// An action taken, and whether it succeeded
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditEntry {
+    /// Unix timestamp, in seconds
+    pub at: u64,
+    pub round: usize,
+    pub connector: String,
+    pub action: ChaosAction,
+    pub error: Option<String>,
+}
+
+pub struct Chaos {
+    client: Connect,
+    options: ChaosOptions,
+    rng: StdRng,
+    round: usize,
+    paused: BTreeSet<String>,
+    audit: Vec<AuditEntry>,
+}
+
+impl Chaos {
+    pub fn new(client: Connect, options: ChaosOptions) -> Self {
+        let seed = options.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos() as u64)
+                .unwrap_or_default()
+        });
+        Self {
+            client,
+            options,
+            rng: StdRng::seed_from_u64(seed),
+            round: 0,
+            paused: BTreeSet::new(),
+            audit: Vec::new(),
+        }
+    }
+
+    /// Every action taken so far
+    pub fn audit(&self) -> &[AuditEntry] {
+        &self.audit
+    }
+
+    /// Resumes the connectors paused by the previous round, then disrupts new ones.
+    /// Returns the entries added to the audit
+    pub async fn round(&mut self) -> Result<&[AuditEntry]> {
+        let start = self.audit.len();
+        self.round += 1;
+        self.resume_paused().await;
+        let mut candidates: Vec<String> = self
+            .client
+            .connector_names()
+            .await?
+            .into_iter()
+            .filter(|name| !self.options.protected.contains(name))
+            .collect();
+        candidates.sort();
+        let victims: Vec<String> = candidates
+            .sample(&mut self.rng, self.options.victims_per_round)
+            .cloned()
+            .collect();
+        let actions: Vec<ChaosAction> = self
+            .options
+            .actions
+            .iter()
+            .copied()
+            .filter(|action| *action != ChaosAction::Resume)
+            .collect();
+        for victim in victims {
+            let Some(action) = actions.choose(&mut self.rng).copied() else {
+                break;
+            };
+            let result = match action {
+                ChaosAction::Pause => self.client.pause_connector(&victim).await,
+                ChaosAction::Restart => self
+                    .client
+                    .restart_connector(&victim, true, false)
+                    .await
+                    .map(|_| ()),
+                ChaosAction::Resume => unreachable!("resume is filtered out"),
+            };
+            if action == ChaosAction::Pause && result.is_ok() {
+                self.paused.insert(victim.clone());
+            }
+            self.record(victim, action, result);
+        }
+        Ok(&self.audit[start..])
+    }
+
+    /// Runs the given number of rounds, then resumes whatever is still paused
+    pub async fn run(&mut self, rounds: usize) -> Result<()> {
+        for round in 0..rounds {
+            if round > 0 {
+                tokio::time::sleep(self.options.interval).await;
+            }
+            self.round().await?;
+        }
+        self.finish().await;
+        Ok(())
+    }
+
+    /// Resumes every connector left paused. Always call it when stopping early
+    pub async fn finish(&mut self) {
+        self.resume_paused().await;
+    }
+
+    async fn resume_paused(&mut self) {
+        for name in std::mem::take(&mut self.paused) {
+            let result = self.client.resume_connector(&name).await;
+            self.record(name, ChaosAction::Resume, result);
+        }
+    }
+
+    fn record(&mut self, connector: String, action: ChaosAction, result: Result<()>) {
+        let at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        self.audit.push(AuditEntry {
+            at,
+            round: self.round,
+            connector,
+            action,
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+}
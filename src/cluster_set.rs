@@ -0,0 +1,84 @@
+//! Several clusters managed together, such as one per region
+use crate::Connect;
+use anyhow::Result;
+use futures::future;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Duration;
+
+/// Clients of several clusters, by name
+#[derive(Clone, Debug, Default)]
+pub struct ClusterSet {
+    clusters: BTreeMap<String, Connect>,
+}
+
+/// Outcome of a query run on every cluster of a set
+#[derive(Debug)]
+pub struct FanOut<T> {
+    /// Results of the clusters that answered in time
+    pub results: BTreeMap<String, T>,
+    /// Errors of the clusters the query failed on
+    pub errors: BTreeMap<String, anyhow::Error>,
+    /// Clusters that did not answer within the timeout
+    pub timed_out: Vec<String>,
+}
+
+impl<T> FanOut<T> {
+    /// Whether every cluster answered successfully
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty() && self.timed_out.is_empty()
+    }
+}
+
+impl ClusterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: &str, client: Connect) -> &mut Self {
+        self.clusters.insert(name.to_string(), client);
+        self
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Connect> {
+        self.clusters.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Connect> {
+        self.clusters.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.clusters.keys().map(String::as_str)
+    }
+
+    /// Runs the query on every cluster at once. A cluster failing or not answering within `timeout`
+    /// is reported as such, without holding back the results of the others
+    pub async fn fan_out<T, F, Fut>(&self, timeout: Duration, query: F) -> FanOut<T>
+    where
+        F: Fn(Connect) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let queries = self.clusters.iter().map(|(name, client)| {
+            let answer = tokio::time::timeout(timeout, query(client.clone()));
+            async move { (name.clone(), answer.await) }
+        });
+        let mut fan_out = FanOut {
+            results: BTreeMap::new(),
+            errors: BTreeMap::new(),
+            timed_out: Vec::new(),
+        };
+        for (name, answer) in future::join_all(queries).await {
+            match answer {
+                Ok(Ok(result)) => {
+                    fan_out.results.insert(name, result);
+                }
+                Ok(Err(e)) => {
+                    fan_out.errors.insert(name, e);
+                }
+                Err(_) => fan_out.timed_out.push(name),
+            }
+        }
+        fan_out
+    }
+}
@@ -0,0 +1,94 @@
+//! Deserializers of typed values out of the strings of a connector configuration
+//! Meant for the fields of structs read with [`crate::Connect::connector_config_as`], such as
+//! `#[serde(deserialize_with = "connect_rs::coerce::from_str")] tasks_max: u32`. Scalars that are
+//! not strings are accepted too, so the same structs can be read from manifests
+use serde::de::{self, Deserializer, Visitor};
+use serde::Deserialize;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+struct ScalarVisitor<T>(PhantomData<T>);
+
+impl<T> Visitor<'_> for ScalarVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string holding the value")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+}
+
+/// Any scalar, as a string
+struct Text(String);
+
+impl<'de> Deserialize<'de> for Text {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_str(deserializer).map(Text)
+    }
+}
+
+/// Parses the value with its `FromStr` implementation, for numbers and booleans
+pub fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserializer.deserialize_any(ScalarVisitor(PhantomData))
+}
+
+/// Same as [`from_str`], for optional keys, with empty values read as None. Needs
+/// `#[serde(default)]` to accept missing keys
+pub fn option_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let value: Option<Text> = Option::deserialize(deserializer)?;
+    value
+        .map(|Text(value)| value)
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| value.trim().parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Splits a comma separated list, such as `topics`, parsing every item
+pub fn list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let Text(value) = Text::deserialize(deserializer)?;
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse().map_err(de::Error::custom))
+        .collect()
+}
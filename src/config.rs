@@ -0,0 +1,472 @@
+//! Connector configuration
+//! Kafka connect transports every config value as a string, so this module wraps the raw map
+//! and takes care of encoding and decoding the typed values.
+use crate::config_keys;
+use crate::converters::Converter;
+use crate::error_handling::ErrorHandling;
+use crate::redact;
+use crate::transforms::TransformChain;
+use anyhow::Result;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// Configuration of a connector
+/// Dereferences to the underlying string map, so it can be used wherever a map is expected.
+/// Numbers and booleans are accepted when deserializing, and converted to their string form
+/// `Debug` masks the secrets, see [`crate::redact`]
+#[derive(Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct ConnectorConfig(HashMap<String, String>);
+
+impl fmt::Debug for ConnectorConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redactor = redact::installed();
+        let redacted: BTreeMap<&String, &str> = self
+            .0
+            .iter()
+            .map(|(key, value)| (key, redactor.value(key, value)))
+            .collect();
+        f.debug_tuple("ConnectorConfig").field(&redacted).finish()
+    }
+}
+
+impl ConnectorConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Returns a boolean value. Kafka accepts true and false regardless of case
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>> {
+        match self.get_str(key).map(str::trim) {
+            None => Ok(None),
+            Some(value) if value.eq_ignore_ascii_case("true") => Ok(Some(true)),
+            Some(value) if value.eq_ignore_ascii_case("false") => Ok(Some(false)),
+            Some(value) => anyhow::bail!("Value {} for key {} is not a valid boolean", value, key),
+        }
+    }
+
+    pub fn get_i64(&self, key: &str) -> Result<Option<i64>> {
+        match self.get_str(key).map(str::trim) {
+            None => Ok(None),
+            Some(value) => match value.parse() {
+                Ok(value) => Ok(Some(value)),
+                Err(_) => anyhow::bail!("Value {} for key {} is not a valid integer", value, key),
+            },
+        }
+    }
+
+    /// Returns a list value. Lists are encoded as comma separated strings
+    pub fn get_list(&self, key: &str) -> Option<Vec<String>> {
+        self.get_str(key).map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect()
+        })
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.0.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn set_bool(&mut self, key: impl Into<String>, value: bool) -> &mut Self {
+        self.set(key, value.to_string())
+    }
+
+    pub fn set_i64(&mut self, key: impl Into<String>, value: i64) -> &mut Self {
+        self.set(key, value.to_string())
+    }
+
+    pub fn set_list<I, S>(&mut self, key: impl Into<String>, values: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let value = values
+            .into_iter()
+            .map(|item| item.as_ref().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.set(key, value)
+    }
+
+    /// Labels of the connector, the keys under [`config_keys::LABEL_PREFIX`]
+    pub fn labels(&self) -> BTreeMap<&str, &str> {
+        self.iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix(config_keys::LABEL_PREFIX)
+                    .map(|label| (label, value.as_str()))
+            })
+            .collect()
+    }
+
+    pub fn label(&self, label: &str) -> Option<&str> {
+        self.get_str(&format!("{}{}", config_keys::LABEL_PREFIX, label))
+    }
+
+    pub fn set_label(&mut self, label: &str, value: impl Into<String>) -> &mut Self {
+        self.set(format!("{}{}", config_keys::LABEL_PREFIX, label), value)
+    }
+
+    /// Whether the `connector.class` names a sink, as sink connectors are named after their kind
+    /// while sources such as Debezium may not be. Some sources read `topics` too, such as
+    /// MirrorMaker, so the keys set say nothing of the kind. The `type` reported by the cluster
+    /// is authoritative for deployed connectors
+    pub fn is_sink(&self) -> bool {
+        self.get_str(config_keys::CONNECTOR_CLASS)
+            .map(|class| class.rsplit('.').next().unwrap_or(class))
+            .is_some_and(|name| name.contains("Sink"))
+    }
+
+    /// Makes sure that every one of the given keys is set to a non empty value
+    pub fn require(&self, keys: &[&str]) -> Result<()> {
+        let missing: Vec<&str> = keys
+            .iter()
+            .copied()
+            .filter(|key| {
+                self.get_str(key)
+                    .is_none_or(|value| value.trim().is_empty())
+            })
+            .collect();
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Missing required configuration keys: {}",
+                missing.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> HashMap<String, String> {
+        self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for ConnectorConfig {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Scalar {
+            String(String),
+            Bool(bool),
+            Integer(i64),
+            Unsigned(u64),
+            Float(f64),
+        }
+
+        let map = HashMap::<String, Scalar>::deserialize(deserializer)?;
+        Ok(map
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Scalar::String(value) => value,
+                    Scalar::Bool(value) => value.to_string(),
+                    Scalar::Integer(value) => value.to_string(),
+                    Scalar::Unsigned(value) => value.to_string(),
+                    Scalar::Float(value) => value.to_string(),
+                };
+                (key, value)
+            })
+            .collect())
+    }
+}
+
+// the schema accepts the scalars that deserializing converts to strings
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for ConnectorConfig {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "ConnectorConfig".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Configuration of a connector, every value being sent as a string",
+            "type": "object",
+            "additionalProperties": { "type": ["string", "number", "boolean"] }
+        })
+    }
+}
+
+impl Deref for ConnectorConfig {
+    type Target = HashMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ConnectorConfig {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<HashMap<String, String>> for ConnectorConfig {
+    fn from(map: HashMap<String, String>) -> Self {
+        Self(map)
+    }
+}
+
+impl From<ConnectorConfig> for HashMap<String, String> {
+    fn from(config: ConnectorConfig) -> Self {
+        config.0
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for ConnectorConfig {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl IntoIterator for ConnectorConfig {
+    type Item = (String, String);
+    type IntoIter = std::collections::hash_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ConnectorConfig {
+    type Item = (&'a String, &'a String);
+    type IntoIter = std::collections::hash_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Differences between two configurations, keyed and sorted by configuration key. `Debug` masks
+/// the secrets
+#[derive(Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConfigDiff {
+    pub added: BTreeMap<String, String>,
+    pub removed: BTreeMap<String, String>,
+    /// Values changed, from the old one to the new one
+    pub changed: BTreeMap<String, (String, String)>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Debug for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redactor = redact::installed();
+        let redacted = |values: &BTreeMap<String, String>| -> BTreeMap<String, String> {
+            values
+                .iter()
+                .map(|(key, value)| (key.clone(), redactor.value(key, value).to_string()))
+                .collect()
+        };
+        let changed: BTreeMap<&String, (&str, &str)> = self
+            .changed
+            .iter()
+            .map(|(key, (old, new))| (key, (redactor.value(key, old), redactor.value(key, new))))
+            .collect();
+        f.debug_struct("ConfigDiff")
+            .field("added", &redacted(&self.added))
+            .field("removed", &redacted(&self.removed))
+            .field("changed", &changed)
+            .finish()
+    }
+}
+
+/// One change per line, the values of secrets being masked
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shown = |key: &str, value: &str| {
+            if redact::is_secret_key(key) {
+                redact::MASK.to_string()
+            } else {
+                value.to_string()
+            }
+        };
+        let mut lines = Vec::new();
+        for (key, value) in &self.added {
+            lines.push(format!("+ {}={}", key, shown(key, value)));
+        }
+        for (key, value) in &self.removed {
+            lines.push(format!("- {}={}", key, shown(key, value)));
+        }
+        for (key, (old, new)) in &self.changed {
+            lines.push(format!(
+                "~ {}: {} -> {}",
+                key,
+                shown(key, old),
+                shown(key, new)
+            ));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl ConnectorConfig {
+    /// Changes leading from this configuration to the other
+    pub fn diff(&self, other: &ConnectorConfig) -> ConfigDiff {
+        let mut diff = ConfigDiff::default();
+        for (key, value) in &self.0 {
+            match other.0.get(key) {
+                None => {
+                    diff.removed.insert(key.clone(), value.clone());
+                }
+                Some(new) if new != value => {
+                    diff.changed
+                        .insert(key.clone(), (value.clone(), new.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, value) in &other.0 {
+            if !self.0.contains_key(key) {
+                diff.added.insert(key.clone(), value.clone());
+            }
+        }
+        diff
+    }
+}
+
+/// Builder for assembling connector configurations
+#[derive(Clone, Debug)]
+pub struct ConnectorConfigBuilder {
+    config: ConnectorConfig,
+    topics: Vec<String>,
+    transforms: TransformChain,
+}
+
+impl ConnectorConfigBuilder {
+    pub fn new(connector_class: impl Into<String>) -> Self {
+        let mut config = ConnectorConfig::new();
+        config.set(config_keys::CONNECTOR_CLASS, connector_class);
+        Self {
+            config,
+            topics: Vec::new(),
+            transforms: TransformChain::new(),
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.config.set(config_keys::NAME, name);
+        self
+    }
+
+    pub fn tasks_max(mut self, tasks_max: u32) -> Self {
+        self.config
+            .set(config_keys::TASKS_MAX, tasks_max.to_string());
+        self
+    }
+
+    /// Adds a topic to the `topics` list
+    pub fn topic(mut self, topic: impl Into<String>) -> Self {
+        self.topics.push(topic.into());
+        self
+    }
+
+    pub fn topics<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.topics.extend(topics.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn topics_regex(mut self, regex: impl Into<String>) -> Self {
+        self.config.set(config_keys::TOPICS_REGEX, regex);
+        self
+    }
+
+    /// Sets the key converter, either from a class name or a fully configured converter
+    pub fn key_converter(mut self, converter: impl Into<Converter>) -> Self {
+        self.config.set_key_converter(&converter.into());
+        self
+    }
+
+    /// Sets the value converter, either from a class name or a fully configured converter
+    pub fn value_converter(mut self, converter: impl Into<Converter>) -> Self {
+        self.config.set_value_converter(&converter.into());
+        self
+    }
+
+    pub fn error_handling(mut self, error_handling: &ErrorHandling) -> Self {
+        error_handling.apply_to(&mut self.config);
+        self
+    }
+
+    /// Appends a transform chain to the ones added before, see [`TransformChain::extend`].
+    /// The whole chain gets merged with the transforms already configured
+    pub fn transforms(mut self, transforms: TransformChain) -> Self {
+        self.transforms = std::mem::take(&mut self.transforms).extend(transforms);
+        self
+    }
+
+    /// Sets a label, see [`ConnectorConfig::labels`]
+    pub fn label(mut self, label: &str, value: impl Into<String>) -> Self {
+        self.config.set_label(label, value);
+        self
+    }
+
+    /// Sets an arbitrary key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    /// Merges every key of the given configuration, overriding the existing ones
+    pub fn extend(mut self, config: impl Into<ConnectorConfig>) -> Self {
+        self.config.extend(config.into());
+        self
+    }
+
+    /// Builds the configuration, validating the base keys
+    pub fn build(mut self) -> Result<ConnectorConfig> {
+        if !self.topics.is_empty() {
+            let mut topics = self
+                .config
+                .get_list(config_keys::TOPICS)
+                .unwrap_or_default();
+            topics.append(&mut self.topics);
+            self.config.set_list(config_keys::TOPICS, topics);
+        }
+        self.transforms.apply_to(&mut self.config)?;
+        let config = self.config;
+        match config.get_str(config_keys::CONNECTOR_CLASS) {
+            Some(class) if !class.trim().is_empty() => {}
+            _ => anyhow::bail!("{} must not be empty", config_keys::CONNECTOR_CLASS),
+        }
+        if let Some(tasks_max) = config.get_i64(config_keys::TASKS_MAX)? {
+            if tasks_max < 1 {
+                anyhow::bail!("{} must be at least 1", config_keys::TASKS_MAX);
+            }
+        }
+        if config.contains_key(config_keys::TOPICS)
+            && config.contains_key(config_keys::TOPICS_REGEX)
+        {
+            anyhow::bail!(
+                "Only one of {} and {} may be set",
+                config_keys::TOPICS,
+                config_keys::TOPICS_REGEX
+            );
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,45 @@
+//! Well known connector configuration keys
+//! These are shared by every connector, regardless of the plugin being used
+
+pub const NAME: &str = "name";
+pub const CONNECTOR_CLASS: &str = "connector.class";
+pub const TASKS_MAX: &str = "tasks.max";
+pub const TOPICS: &str = "topics";
+pub const TOPICS_REGEX: &str = "topics.regex";
+pub const KEY_CONVERTER: &str = "key.converter";
+pub const VALUE_CONVERTER: &str = "value.converter";
+pub const HEADER_CONVERTER: &str = "header.converter";
+pub const CONFIG_ACTION_RELOAD: &str = "config.action.reload";
+/// Prefix of the labels of a connector, such as `metadata.labels.team`. Connectors ignore them
+pub const LABEL_PREFIX: &str = "metadata.labels.";
+/// Time of the last forced reconfiguration of the tasks, changed to make the workers regenerate
+/// the task configurations. Connectors ignore it
+pub const RECONFIGURED_AT: &str = "metadata.reconfigured.at";
+
+// Error handling
+pub const ERRORS_TOLERANCE: &str = "errors.tolerance";
+pub const ERRORS_RETRY_TIMEOUT: &str = "errors.retry.timeout";
+pub const ERRORS_RETRY_DELAY_MAX_MS: &str = "errors.retry.delay.max.ms";
+pub const ERRORS_LOG_ENABLE: &str = "errors.log.enable";
+pub const ERRORS_LOG_INCLUDE_MESSAGES: &str = "errors.log.include.messages";
+pub const ERRORS_DEADLETTERQUEUE_TOPIC_NAME: &str = "errors.deadletterqueue.topic.name";
+pub const ERRORS_DEADLETTERQUEUE_TOPIC_REPLICATION_FACTOR: &str =
+    "errors.deadletterqueue.topic.replication.factor";
+pub const ERRORS_DEADLETTERQUEUE_CONTEXT_HEADERS_ENABLE: &str =
+    "errors.deadletterqueue.context.headers.enable";
+
+// Single message transforms
+pub const TRANSFORMS: &str = "transforms";
+pub const PREDICATES: &str = "predicates";
+
+/// Returns the key of a property belonging to the transform with the given alias,
+/// e.g. `transforms.route.type`
+pub fn transform_key(alias: &str, property: &str) -> String {
+    format!("{}.{}.{}", TRANSFORMS, alias, property)
+}
+
+/// Returns the key of a property belonging to the predicate with the given alias,
+/// e.g. `predicates.is_tombstone.type`
+pub fn predicate_key(alias: &str, property: &str) -> String {
+    format!("{}.{}.{}", PREDICATES, alias, property)
+}
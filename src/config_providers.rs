@@ -0,0 +1,173 @@
+//! References to config providers, such as `${file:/etc/secrets.properties:password}`
+//! Workers resolve the references when starting a connector, but the REST API returns them as
+//! they were written. Comparing a manifest holding the resolved values with a live configuration
+//! holding references would report drift where there is none, so both sides can be resolved
+//! locally with [`ConfigResolver`]s before being compared
+use crate::apply::{ApplyOutcome, ConnectorSpec};
+use crate::config::ConnectorConfig;
+use crate::normalize::Normalizer;
+use crate::Connect;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+
+static REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\{([A-Za-z0-9_.-]+):(?:([^:}]*):)?([^:}]+)\}").unwrap());
+
+/// A `${provider:path:key}` reference, the path being optional
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ProviderReference {
+    pub provider: String,
+    pub path: Option<String>,
+    pub key: String,
+}
+
+impl fmt::Display for ProviderReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "${{{}:{}:{}}}", self.provider, path, self.key),
+            None => write!(f, "${{{}:{}}}", self.provider, self.key),
+        }
+    }
+}
+
+/// References found in a value, in order
+pub fn references(value: &str) -> Vec<ProviderReference> {
+    REFERENCE
+        .captures_iter(value)
+        .map(|captures| ProviderReference {
+            provider: captures[1].to_string(),
+            path: captures.get(2).map(|path| path.as_str().to_string()),
+            key: captures[3].to_string(),
+        })
+        .collect()
+}
+
+/// Resolves the references of a provider, as configured on the workers under
+/// `config.providers`
+#[async_trait]
+pub trait ConfigResolver: Send + Sync {
+    /// Name of the provider, such as `file`
+    fn provider(&self) -> &str;
+
+    /// Value of the reference, `None` when the provider has no such value
+    async fn resolve(&self, reference: &ProviderReference) -> Result<Option<String>>;
+}
+
+/// Resolves `${env:VARIABLE}`, like the EnvVarConfigProvider
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvResolver;
+
+#[async_trait]
+impl ConfigResolver for EnvResolver {
+    fn provider(&self) -> &str {
+        "env"
+    }
+
+    async fn resolve(&self, reference: &ProviderReference) -> Result<Option<String>> {
+        Ok(std::env::var(&reference.key).ok())
+    }
+}
+
+/// Resolves `${file:path:key}` from a properties file, like the FileConfigProvider. The files
+/// must be readable locally, at the path the workers use
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileResolver;
+
+#[async_trait]
+impl ConfigResolver for FileResolver {
+    fn provider(&self) -> &str {
+        "file"
+    }
+
+    async fn resolve(&self, reference: &ProviderReference) -> Result<Option<String>> {
+        let Some(path) = &reference.path else {
+            return Ok(None);
+        };
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Could not read {}", path))?;
+        Ok(properties(&content).remove(&reference.key))
+    }
+}
+
+/// Entries of a java properties file, without support for line continuations
+fn properties(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .filter_map(|line| {
+            let split = line.find(['=', ':'])?;
+            Some((
+                line[..split].trim().to_string(),
+                line[split + 1..].trim().to_string(),
+            ))
+        })
+        .collect()
+}
+
+impl ConnectorConfig {
+    /// Keys whose value holds references, along with the references
+    pub fn provider_references(&self) -> Vec<(&str, ProviderReference)> {
+        let mut found: Vec<(&str, ProviderReference)> = self
+            .iter()
+            .flat_map(|(key, value)| {
+                references(value)
+                    .into_iter()
+                    .map(move |reference| (key.as_str(), reference))
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(b.0));
+        found
+    }
+
+    /// Copy of the configuration with the references replaced by their values. References of
+    /// providers without a resolver, or without a value, are left as they are
+    pub async fn resolve_providers(
+        &self,
+        resolvers: &[&dyn ConfigResolver],
+    ) -> Result<ConnectorConfig> {
+        let mut resolved = self.clone();
+        for (key, value) in self.iter() {
+            let mut replaced = value.clone();
+            for reference in references(value) {
+                let Some(resolver) = resolvers
+                    .iter()
+                    .find(|resolver| resolver.provider() == reference.provider)
+                else {
+                    continue;
+                };
+                if let Some(found) = resolver.resolve(&reference).await? {
+                    replaced = replaced.replace(&reference.to_string(), &found);
+                }
+            }
+            resolved.set(key.clone(), replaced);
+        }
+        Ok(resolved)
+    }
+}
+
+impl Connect {
+    /// Same as [`Connect::apply`], resolving the references of both the spec and the live
+    /// configuration before comparing them. The spec is written as it is
+    pub async fn apply_with_resolvers(
+        &self,
+        spec: &ConnectorSpec,
+        resolvers: &[&dyn ConfigResolver],
+    ) -> Result<ApplyOutcome> {
+        let names = self.connector_names().await?;
+        if names.contains(&spec.name) {
+            let live = self.connector_config(&spec.name).await?;
+            let live = live.resolve_providers(resolvers).await?;
+            let desired = spec.effective_config().resolve_providers(resolvers).await?;
+            if Normalizer::default().equivalent(&live, &desired) {
+                return Ok(ApplyOutcome::Unchanged);
+            }
+        }
+        self.apply(spec).await
+    }
+}
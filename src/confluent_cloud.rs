@@ -0,0 +1,246 @@
+//! Managed connectors of Confluent Cloud
+//! Only available with the `confluent_cloud` feature
+//!
+//! The managed API mirrors most of the kafka-connect REST API under a per cluster base path, and is
+//! authenticated with a cloud API key. Offsets are altered through asynchronous requests, and the
+//! worker level endpoints (cluster info, restarts, stop, loggers) do not exist
+use crate::api::ConnectApi;
+use crate::config::ConnectorConfig;
+use crate::error::ConnectError;
+use crate::models::*;
+use crate::offsets::ConnectorOffsets;
+use crate::{error_message, json, Connect, ConnectBuilder};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+pub const API_ADDRESS: &str = "https://api.confluent.cloud";
+
+/// Client of the connectors of one Kafka cluster of Confluent Cloud
+#[derive(Clone, Debug)]
+pub struct ConfluentCloud {
+    client: Connect,
+}
+
+/// Acknowledgement of an offsets request, which the cloud applies asynchronously
+#[derive(Clone, Debug, Deserialize)]
+struct OffsetsRequest {
+    #[serde(rename = "type")]
+    kind: String,
+    requested_at: Option<String>,
+}
+
+impl ConfluentCloud {
+    /// Client of the connectors of a Kafka cluster, such as `lkc-123`, in its environment, such as
+    /// `env-123`. The API key must be a cloud API key, not a Kafka cluster one
+    pub fn new(environment: &str, cluster: &str, api_key: &str, api_secret: &str) -> Result<Self> {
+        Self::with_address(API_ADDRESS, environment, cluster, api_key, api_secret)
+    }
+
+    /// Same as [`ConfluentCloud::new`], against another deployment of the API
+    pub fn with_address(
+        address: &str,
+        environment: &str,
+        cluster: &str,
+        api_key: &str,
+        api_secret: &str,
+    ) -> Result<Self> {
+        let address = format!(
+            "{}/connect/v1/environments/{}/clusters/{}",
+            address.trim_end_matches('/'),
+            environment,
+            cluster
+        );
+        let client = ConnectBuilder::new(&address)
+            .basic_auth(api_key, Some(api_secret))
+            .build()?;
+        Ok(Self { client })
+    }
+
+    /// Underlying client, for the endpoints shared with self-managed clusters
+    pub fn client(&self) -> &Connect {
+        &self.client
+    }
+
+    async fn request_offsets(&self, connector: &str, body: serde_json::Value) -> Result<String> {
+        let response = self
+            .client
+            .client
+            .post(format!(
+                "{}/connectors/{}/offsets/request",
+                self.client.address, connector
+            ))
+            .json(&body)
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::OK | StatusCode::ACCEPTED => {
+                let request: OffsetsRequest =
+                    json::from_response(response, self.client.max_response_size).await?;
+                Ok(format!(
+                    "The {} offsets request was accepted at {}",
+                    request.kind,
+                    request.requested_at.as_deref().unwrap_or("an unknown time")
+                ))
+            }
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(connector.to_string()))
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
+    }
+}
+
+fn unsupported<T>(operation: &str) -> Result<T> {
+    anyhow::bail!(ConnectError::UnsupportedByCluster(format!(
+        "{} is not available for managed connectors",
+        operation
+    )))
+}
+
+#[async_trait]
+impl ConnectApi for ConfluentCloud {
+    async fn info(&self) -> Result<ClusterInfo> {
+        unsupported("Cluster info")
+    }
+
+    async fn connector_names(&self) -> Result<Vec<String>> {
+        self.client.connector_names().await
+    }
+
+    async fn connectors(
+        &self,
+        expand_status: bool,
+        expand_info: bool,
+    ) -> Result<HashMap<String, Connector>> {
+        self.client.connectors(expand_status, expand_info).await
+    }
+
+    async fn connector_status(&self, connector: &str) -> Result<ConnectorStatus> {
+        self.client.connector_status(connector).await
+    }
+
+    async fn connector_active_topics(&self, _connector: &str) -> Result<Option<Vec<String>>> {
+        unsupported("Reading active topics")
+    }
+
+    async fn create_connector(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        self.client.create_connector(name, config).await
+    }
+
+    async fn create_connector_with_state(
+        &self,
+        _name: &str,
+        _config: &ConnectorConfig,
+        _initial_state: Status,
+    ) -> Result<ConnectorInfo> {
+        unsupported("Creating connectors in a state")
+    }
+
+    async fn update_connector_config(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        self.client.update_connector_config(name, config).await
+    }
+
+    async fn patch_connector_config(
+        &self,
+        name: &str,
+        patch: &HashMap<String, Option<String>>,
+    ) -> Result<ConnectorInfo> {
+        self.client.patch_connector_config(name, patch).await
+    }
+
+    async fn restart_connector(
+        &self,
+        _name: &str,
+        _include_tasks: bool,
+        _only_failed: bool,
+    ) -> Result<Option<ConnectorStatus>> {
+        unsupported("Restarting")
+    }
+
+    async fn restart_task(&self, _connector: &str, _task: u64) -> Result<()> {
+        unsupported("Restarting")
+    }
+
+    async fn pause_connector(&self, connector: &str) -> Result<()> {
+        self.client.pause_connector(connector).await
+    }
+
+    async fn resume_connector(&self, connector: &str) -> Result<()> {
+        self.client.resume_connector(connector).await
+    }
+
+    async fn stop_connector(&self, _connector: &str) -> Result<()> {
+        unsupported("Stopping")
+    }
+
+    async fn delete_connector(&self, connector: &str) -> Result<()> {
+        self.client.delete_connector(connector).await
+    }
+
+    async fn connector_config(&self, connector: &str) -> Result<ConnectorConfig> {
+        self.client.connector_config(connector).await
+    }
+
+    async fn connector_offsets(&self, connector: &str) -> Result<ConnectorOffsets> {
+        self.client.connector_offsets(connector).await
+    }
+
+    /// Requests the offsets to be altered. The request is applied asynchronously, and the
+    /// connector does not need to be stopped
+    async fn alter_connector_offsets(
+        &self,
+        connector: &str,
+        offsets: &ConnectorOffsets,
+    ) -> Result<String> {
+        let body = json!({ "type": "PATCH", "offsets": offsets.offsets });
+        self.request_offsets(connector, body).await
+    }
+
+    /// Requests the offsets to be reset, which is applied asynchronously
+    async fn reset_connector_offsets(&self, connector: &str) -> Result<String> {
+        self.request_offsets(connector, json!({ "type": "DELETE" }))
+            .await
+    }
+
+    async fn validate_connector_config(&self, config: &ConnectorConfig) -> Result<ConfigInfos> {
+        self.client.validate_connector_config(config).await
+    }
+
+    async fn connector_plugins(&self) -> Result<Vec<PluginInfo>> {
+        self.client.connector_plugins().await
+    }
+
+    async fn loggers(&self) -> Result<HashMap<String, LoggerLevel>> {
+        unsupported("Reading loggers")
+    }
+
+    async fn logger(&self, _logger: &str) -> Result<LoggerLevel> {
+        unsupported("Reading loggers")
+    }
+
+    async fn set_logger_level(
+        &self,
+        _logger: &str,
+        _level: &str,
+        _scope: LoggerScope,
+    ) -> Result<Vec<String>> {
+        unsupported("Setting logger levels")
+    }
+}
@@ -0,0 +1,454 @@
+//! Debezium source connectors
+//! The keys follow the naming used by Debezium 2.x
+//!
+//! The offsets types read the positions of the connectors, through
+//! [`crate::Connect::connector_offsets_as`]
+use crate::config::{ConnectorConfig, ConnectorConfigBuilder};
+use crate::offsets::SourceConnectorOffset;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+pub const POSTGRES_CONNECTOR_CLASS: &str = "io.debezium.connector.postgresql.PostgresConnector";
+pub const MYSQL_CONNECTOR_CLASS: &str = "io.debezium.connector.mysql.MySqlConnector";
+
+const DATABASE_HOSTNAME: &str = "database.hostname";
+const DATABASE_PORT: &str = "database.port";
+const DATABASE_USER: &str = "database.user";
+const DATABASE_PASSWORD: &str = "database.password";
+const DATABASE_DBNAME: &str = "database.dbname";
+const DATABASE_SERVER_ID: &str = "database.server.id";
+const TOPIC_PREFIX: &str = "topic.prefix";
+const SNAPSHOT_MODE: &str = "snapshot.mode";
+const TABLE_INCLUDE_LIST: &str = "table.include.list";
+const TABLE_EXCLUDE_LIST: &str = "table.exclude.list";
+const SCHEMA_INCLUDE_LIST: &str = "schema.include.list";
+const DATABASE_INCLUDE_LIST: &str = "database.include.list";
+const PLUGIN_NAME: &str = "plugin.name";
+const SLOT_NAME: &str = "slot.name";
+const PUBLICATION_NAME: &str = "publication.name";
+const SCHEMA_HISTORY_BOOTSTRAP_SERVERS: &str = "schema.history.internal.kafka.bootstrap.servers";
+const SCHEMA_HISTORY_TOPIC: &str = "schema.history.internal.kafka.topic";
+
+/// Snapshot modes supported by the postgres connector
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PostgresSnapshotMode {
+    Initial,
+    Always,
+    InitialOnly,
+    NoData,
+    WhenNeeded,
+}
+
+impl PostgresSnapshotMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Initial => "initial",
+            Self::Always => "always",
+            Self::InitialOnly => "initial_only",
+            Self::NoData => "no_data",
+            Self::WhenNeeded => "when_needed",
+        }
+    }
+}
+
+/// Logical decoding plugins that the postgres connector can use
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PostgresPlugin {
+    PgOutput,
+    Decoderbufs,
+}
+
+impl PostgresPlugin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PgOutput => "pgoutput",
+            Self::Decoderbufs => "decoderbufs",
+        }
+    }
+}
+
+/// Snapshot modes supported by the mysql connector
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MySqlSnapshotMode {
+    Initial,
+    InitialOnly,
+    WhenNeeded,
+    Never,
+    NoData,
+    Recovery,
+    Always,
+}
+
+impl MySqlSnapshotMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Initial => "initial",
+            Self::InitialOnly => "initial_only",
+            Self::WhenNeeded => "when_needed",
+            Self::Never => "never",
+            Self::NoData => "no_data",
+            Self::Recovery => "recovery",
+            Self::Always => "always",
+        }
+    }
+}
+
+/// Builder for the Debezium postgres source connector
+#[derive(Clone, Debug, Default)]
+pub struct PostgresConnectorBuilder {
+    config: ConnectorConfig,
+}
+
+impl PostgresConnectorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.config.set(DATABASE_HOSTNAME, hostname);
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.config.set(DATABASE_PORT, port.to_string());
+        self
+    }
+
+    pub fn user(mut self, user: impl Into<String>) -> Self {
+        self.config.set(DATABASE_USER, user);
+        self
+    }
+
+    pub fn password(mut self, password: impl Into<String>) -> Self {
+        self.config.set(DATABASE_PASSWORD, password);
+        self
+    }
+
+    pub fn dbname(mut self, dbname: impl Into<String>) -> Self {
+        self.config.set(DATABASE_DBNAME, dbname);
+        self
+    }
+
+    /// Prefix of every topic the connector writes to
+    pub fn topic_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.set(TOPIC_PREFIX, prefix);
+        self
+    }
+
+    pub fn plugin(mut self, plugin: PostgresPlugin) -> Self {
+        self.config.set(PLUGIN_NAME, plugin.as_str());
+        self
+    }
+
+    pub fn slot_name(mut self, slot_name: impl Into<String>) -> Self {
+        self.config.set(SLOT_NAME, slot_name);
+        self
+    }
+
+    pub fn publication_name(mut self, publication_name: impl Into<String>) -> Self {
+        self.config.set(PUBLICATION_NAME, publication_name);
+        self
+    }
+
+    pub fn snapshot_mode(mut self, mode: PostgresSnapshotMode) -> Self {
+        self.config.set(SNAPSHOT_MODE, mode.as_str());
+        self
+    }
+
+    pub fn schema_include_list<I, S>(mut self, schemas: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(SCHEMA_INCLUDE_LIST, schemas);
+        self
+    }
+
+    /// Tables are fully qualified, e.g. `public.orders`
+    pub fn table_include_list<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(TABLE_INCLUDE_LIST, tables);
+        self
+    }
+
+    pub fn table_exclude_list<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(TABLE_EXCLUDE_LIST, tables);
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(self) -> Result<ConnectorConfig> {
+        self.config.require(&[
+            DATABASE_HOSTNAME,
+            DATABASE_USER,
+            DATABASE_DBNAME,
+            TOPIC_PREFIX,
+        ])?;
+        ConnectorConfigBuilder::new(POSTGRES_CONNECTOR_CLASS)
+            .tasks_max(1)
+            .extend(self.config)
+            .build()
+    }
+}
+
+/// Builder for the Debezium mysql source connector
+#[derive(Clone, Debug, Default)]
+pub struct MySqlConnectorBuilder {
+    config: ConnectorConfig,
+}
+
+impl MySqlConnectorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.config.set(DATABASE_HOSTNAME, hostname);
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.config.set(DATABASE_PORT, port.to_string());
+        self
+    }
+
+    pub fn user(mut self, user: impl Into<String>) -> Self {
+        self.config.set(DATABASE_USER, user);
+        self
+    }
+
+    pub fn password(mut self, password: impl Into<String>) -> Self {
+        self.config.set(DATABASE_PASSWORD, password);
+        self
+    }
+
+    /// Numeric id of the connector, which must be unique across the mysql cluster
+    pub fn server_id(mut self, server_id: u32) -> Self {
+        self.config.set(DATABASE_SERVER_ID, server_id.to_string());
+        self
+    }
+
+    /// Prefix of every topic the connector writes to
+    pub fn topic_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.set(TOPIC_PREFIX, prefix);
+        self
+    }
+
+    pub fn snapshot_mode(mut self, mode: MySqlSnapshotMode) -> Self {
+        self.config.set(SNAPSHOT_MODE, mode.as_str());
+        self
+    }
+
+    pub fn database_include_list<I, S>(mut self, databases: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(DATABASE_INCLUDE_LIST, databases);
+        self
+    }
+
+    /// Tables are fully qualified, e.g. `inventory.orders`
+    pub fn table_include_list<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(TABLE_INCLUDE_LIST, tables);
+        self
+    }
+
+    pub fn table_exclude_list<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(TABLE_EXCLUDE_LIST, tables);
+        self
+    }
+
+    /// Kafka cluster and topic where the connector stores the history of the database schema
+    pub fn schema_history(
+        mut self,
+        bootstrap_servers: impl Into<String>,
+        topic: impl Into<String>,
+    ) -> Self {
+        self.config
+            .set(SCHEMA_HISTORY_BOOTSTRAP_SERVERS, bootstrap_servers)
+            .set(SCHEMA_HISTORY_TOPIC, topic);
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(self) -> Result<ConnectorConfig> {
+        self.config.require(&[
+            DATABASE_HOSTNAME,
+            DATABASE_USER,
+            DATABASE_SERVER_ID,
+            TOPIC_PREFIX,
+            SCHEMA_HISTORY_BOOTSTRAP_SERVERS,
+            SCHEMA_HISTORY_TOPIC,
+        ])?;
+        ConnectorConfigBuilder::new(MYSQL_CONNECTOR_CLASS)
+            .tasks_max(1)
+            .extend(self.config)
+            .build()
+    }
+}
+
+/// Source partition of the Debezium connectors, named after `topic.prefix`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DebeziumPartition {
+    pub server: String,
+}
+
+/// Source offset of the MySQL connector, a position in the binlog
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MySqlOffset {
+    /// Binlog file, such as `mysql-bin.000003`
+    pub file: String,
+    pub pos: u64,
+    /// GTID set executed, when GTIDs are enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gtids: Option<String>,
+    /// Events to skip after `pos`, when the connector stopped in the middle of a transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<u64>,
+    /// Rows to skip in the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ts_sec: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<u64>,
+    /// Other fields, such as `snapshot`, kept as they are
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Position in the binlog. Positions are only ordered within binlogs of the same base name, GTID
+/// sets are not compared
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinlogPosition {
+    /// Name of the binlog without its sequence number, such as `mysql-bin`
+    pub base_name: String,
+    pub sequence: u64,
+    pub pos: u64,
+    pub event: u64,
+    pub row: u64,
+}
+
+impl PartialOrd for BinlogPosition {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.base_name != other.base_name {
+            return None;
+        }
+        Some((self.sequence, self.pos, self.event, self.row).cmp(&(
+            other.sequence,
+            other.pos,
+            other.event,
+            other.row,
+        )))
+    }
+}
+
+impl MySqlOffset {
+    pub fn position(&self) -> Result<BinlogPosition> {
+        let Some((base_name, sequence)) = self.file.rsplit_once('.') else {
+            anyhow::bail!("Invalid binlog file {}", self.file);
+        };
+        let Ok(sequence) = sequence.parse() else {
+            anyhow::bail!("Invalid binlog file {}", self.file);
+        };
+        Ok(BinlogPosition {
+            base_name: base_name.to_string(),
+            sequence,
+            pos: self.pos,
+            event: self.event.unwrap_or_default(),
+            row: self.row.unwrap_or_default(),
+        })
+    }
+}
+
+/// Log sequence number of Postgres, written as `16/B374D848`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct Lsn(pub u64);
+
+impl fmt::Display for Lsn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 & 0xFFFF_FFFF)
+    }
+}
+
+impl FromStr for Lsn {
+    type Err = anyhow::Error;
+
+    fn from_str(lsn: &str) -> Result<Self> {
+        let parse = || -> Option<u64> {
+            let (high, low) = lsn.split_once('/')?;
+            let high = u64::from_str_radix(high, 16).ok()?;
+            let low = u64::from_str_radix(low, 16).ok()?;
+            (high <= u32::MAX as u64 && low <= u32::MAX as u64).then_some(high << 32 | low)
+        };
+        parse()
+            .map(Self)
+            .with_context(|| format!("Invalid LSN {}", lsn))
+    }
+}
+
+/// Source offset of the Postgres connector, a position in the write-ahead log
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PostgresOffset {
+    pub lsn: Lsn,
+    #[serde(rename = "txId", default, skip_serializing_if = "Option::is_none")]
+    pub tx_id: Option<u64>,
+    /// Last LSN processed, ahead of `lsn` within a transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lsn_proc: Option<Lsn>,
+    /// LSN of the last commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lsn_commit: Option<Lsn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ts_usec: Option<u64>,
+    /// Other fields, such as `snapshot` or `messageType`, kept as they are
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl PostgresOffset {
+    /// Furthest position known to be processed
+    pub fn position(&self) -> Lsn {
+        self.lsn_proc
+            .map_or(self.lsn, |processed| processed.max(self.lsn))
+    }
+}
+
+pub type MySqlSourceOffset = SourceConnectorOffset<DebeziumPartition, MySqlOffset>;
+pub type PostgresSourceOffset = SourceConnectorOffset<DebeziumPartition, PostgresOffset>;
@@ -0,0 +1,208 @@
+//! Confluent Elasticsearch sink connector
+use crate::config::{ConnectorConfig, ConnectorConfigBuilder};
+use crate::config_keys;
+use anyhow::Result;
+use std::time::Duration;
+
+pub const ELASTICSEARCH_SINK_CONNECTOR_CLASS: &str =
+    "io.confluent.connect.elasticsearch.ElasticsearchSinkConnector";
+
+const CONNECTION_URL: &str = "connection.url";
+const CONNECTION_USERNAME: &str = "connection.username";
+const CONNECTION_PASSWORD: &str = "connection.password";
+const WRITE_METHOD: &str = "write.method";
+const BEHAVIOR_ON_NULL_VALUES: &str = "behavior.on.null.values";
+const BEHAVIOR_ON_MALFORMED_DOCUMENTS: &str = "behavior.on.malformed.documents";
+const DROP_INVALID_MESSAGE: &str = "drop.invalid.message";
+const KEY_IGNORE: &str = "key.ignore";
+const SCHEMA_IGNORE: &str = "schema.ignore";
+const BATCH_SIZE: &str = "batch.size";
+const MAX_RETRIES: &str = "max.retries";
+const RETRY_BACKOFF_MS: &str = "retry.backoff.ms";
+const DATA_STREAM_TYPE: &str = "data.stream.type";
+const DATA_STREAM_DATASET: &str = "data.stream.dataset";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteMethod {
+    Insert,
+    Upsert,
+}
+
+impl WriteMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::Upsert => "upsert",
+        }
+    }
+}
+
+/// What to do with records that have a null value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NullValueBehavior {
+    Ignore,
+    Delete,
+    Fail,
+}
+
+impl NullValueBehavior {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ignore => "ignore",
+            Self::Delete => "delete",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+/// What to do with documents rejected by elasticsearch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MalformedDocumentBehavior {
+    Ignore,
+    Warn,
+    Fail,
+}
+
+impl MalformedDocumentBehavior {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ignore => "ignore",
+            Self::Warn => "warn",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+/// Type of data stream to write to, instead of regular indices
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataStreamType {
+    Logs,
+    Metrics,
+}
+
+impl DataStreamType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Logs => "LOGS",
+            Self::Metrics => "METRICS",
+        }
+    }
+}
+
+/// Builder for the Elasticsearch sink connector
+#[derive(Clone, Debug)]
+pub struct ElasticsearchSinkBuilder {
+    config: ConnectorConfig,
+    topics: Vec<String>,
+}
+
+impl ElasticsearchSinkBuilder {
+    pub fn new<I, S>(connection_urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut config = ConnectorConfig::new();
+        config.set_list(CONNECTION_URL, connection_urls);
+        Self {
+            config,
+            topics: Vec::new(),
+        }
+    }
+
+    pub fn tasks_max(mut self, tasks_max: u32) -> Self {
+        self.config
+            .set(config_keys::TASKS_MAX, tasks_max.to_string());
+        self
+    }
+
+    pub fn topic(mut self, topic: impl Into<String>) -> Self {
+        self.topics.push(topic.into());
+        self
+    }
+
+    /// Basic authentication credentials
+    pub fn credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.config
+            .set(CONNECTION_USERNAME, username)
+            .set(CONNECTION_PASSWORD, password);
+        self
+    }
+
+    pub fn write_method(mut self, method: WriteMethod) -> Self {
+        self.config.set(WRITE_METHOD, method.as_str());
+        self
+    }
+
+    pub fn on_null_values(mut self, behavior: NullValueBehavior) -> Self {
+        self.config.set(BEHAVIOR_ON_NULL_VALUES, behavior.as_str());
+        self
+    }
+
+    pub fn on_malformed_documents(mut self, behavior: MalformedDocumentBehavior) -> Self {
+        self.config
+            .set(BEHAVIOR_ON_MALFORMED_DOCUMENTS, behavior.as_str());
+        self
+    }
+
+    /// Drops records that cannot be converted to a document instead of failing the task
+    pub fn drop_invalid_message(mut self, drop: bool) -> Self {
+        self.config.set_bool(DROP_INVALID_MESSAGE, drop);
+        self
+    }
+
+    /// Uses `topic+partition+offset` as the document id instead of the record key
+    pub fn key_ignore(mut self, ignore: bool) -> Self {
+        self.config.set_bool(KEY_IGNORE, ignore);
+        self
+    }
+
+    /// Lets elasticsearch infer the mapping instead of deriving it from the record schema
+    pub fn schema_ignore(mut self, ignore: bool) -> Self {
+        self.config.set_bool(SCHEMA_IGNORE, ignore);
+        self
+    }
+
+    pub fn data_stream(mut self, kind: DataStreamType, dataset: impl Into<String>) -> Self {
+        self.config
+            .set(DATA_STREAM_TYPE, kind.as_str())
+            .set(DATA_STREAM_DATASET, dataset);
+        self
+    }
+
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.config.set(BATCH_SIZE, batch_size.to_string());
+        self
+    }
+
+    pub fn retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.config
+            .set(MAX_RETRIES, max_retries.to_string())
+            .set(RETRY_BACKOFF_MS, backoff.as_millis().to_string());
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(self) -> Result<ConnectorConfig> {
+        self.config.require(&[CONNECTION_URL])?;
+        if self.config.contains_key(CONNECTION_USERNAME)
+            != self.config.contains_key(CONNECTION_PASSWORD)
+        {
+            anyhow::bail!(
+                "{} and {} must be set together",
+                CONNECTION_USERNAME,
+                CONNECTION_PASSWORD
+            );
+        }
+        super::require_sink_topics(&self.topics, &self.config)?;
+        ConnectorConfigBuilder::new(ELASTICSEARCH_SINK_CONNECTOR_CLASS)
+            .topics(self.topics)
+            .extend(self.config)
+            .build()
+    }
+}
@@ -0,0 +1,301 @@
+//! Confluent JDBC source and sink connectors
+use crate::config::{ConnectorConfig, ConnectorConfigBuilder};
+use crate::config_keys;
+use anyhow::Result;
+use std::time::Duration;
+
+pub const JDBC_SOURCE_CONNECTOR_CLASS: &str = "io.confluent.connect.jdbc.JdbcSourceConnector";
+pub const JDBC_SINK_CONNECTOR_CLASS: &str = "io.confluent.connect.jdbc.JdbcSinkConnector";
+
+const CONNECTION_URL: &str = "connection.url";
+const CONNECTION_USER: &str = "connection.user";
+const CONNECTION_PASSWORD: &str = "connection.password";
+const MODE: &str = "mode";
+const INCREMENTING_COLUMN_NAME: &str = "incrementing.column.name";
+const TIMESTAMP_COLUMN_NAME: &str = "timestamp.column.name";
+const POLL_INTERVAL_MS: &str = "poll.interval.ms";
+const TABLE_WHITELIST: &str = "table.whitelist";
+const QUERY: &str = "query";
+const TOPIC_PREFIX: &str = "topic.prefix";
+const INSERT_MODE: &str = "insert.mode";
+const PK_MODE: &str = "pk.mode";
+const PK_FIELDS: &str = "pk.fields";
+const AUTO_CREATE: &str = "auto.create";
+const AUTO_EVOLVE: &str = "auto.evolve";
+const TABLE_NAME_FORMAT: &str = "table.name.format";
+const BATCH_SIZE: &str = "batch.size";
+
+/// Connection settings shared by the source and the sink
+#[derive(Clone, Debug)]
+pub struct JdbcConnection {
+    pub url: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+impl JdbcConnection {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            user: None,
+            password: None,
+        }
+    }
+
+    pub fn credentials(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    fn apply(&self, config: &mut ConnectorConfig) {
+        config.set(CONNECTION_URL, self.url.as_str());
+        if let Some(user) = &self.user {
+            config.set(CONNECTION_USER, user.as_str());
+        }
+        if let Some(password) = &self.password {
+            config.set(CONNECTION_PASSWORD, password.as_str());
+        }
+    }
+}
+
+/// How the source connector detects new rows
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JdbcSourceMode {
+    /// Copies the whole table on every poll
+    Bulk,
+    Incrementing {
+        column: String,
+    },
+    Timestamp {
+        columns: Vec<String>,
+    },
+    TimestampIncrementing {
+        timestamp_columns: Vec<String>,
+        incrementing_column: String,
+    },
+}
+
+impl JdbcSourceMode {
+    fn apply(&self, config: &mut ConnectorConfig) {
+        match self {
+            Self::Bulk => {
+                config.set(MODE, "bulk");
+            }
+            Self::Incrementing { column } => {
+                config
+                    .set(MODE, "incrementing")
+                    .set(INCREMENTING_COLUMN_NAME, column.as_str());
+            }
+            Self::Timestamp { columns } => {
+                config
+                    .set(MODE, "timestamp")
+                    .set_list(TIMESTAMP_COLUMN_NAME, columns);
+            }
+            Self::TimestampIncrementing {
+                timestamp_columns,
+                incrementing_column,
+            } => {
+                config
+                    .set(MODE, "timestamp+incrementing")
+                    .set_list(TIMESTAMP_COLUMN_NAME, timestamp_columns)
+                    .set(INCREMENTING_COLUMN_NAME, incrementing_column.as_str());
+            }
+        }
+    }
+}
+
+/// Builder for the JDBC source connector
+#[derive(Clone, Debug)]
+pub struct JdbcSourceBuilder {
+    config: ConnectorConfig,
+}
+
+impl JdbcSourceBuilder {
+    pub fn new(connection: JdbcConnection, mode: JdbcSourceMode) -> Self {
+        let mut config = ConnectorConfig::new();
+        connection.apply(&mut config);
+        mode.apply(&mut config);
+        Self { config }
+    }
+
+    pub fn tasks_max(mut self, tasks_max: u32) -> Self {
+        self.config
+            .set(config_keys::TASKS_MAX, tasks_max.to_string());
+        self
+    }
+
+    pub fn tables<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(TABLE_WHITELIST, tables);
+        self
+    }
+
+    /// Uses a custom query instead of copying whole tables
+    pub fn query(mut self, query: impl Into<String>) -> Self {
+        self.config.set(QUERY, query);
+        self
+    }
+
+    /// Prefix of the topics, or the full topic name when a query is used
+    pub fn topic_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.set(TOPIC_PREFIX, prefix);
+        self
+    }
+
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.config
+            .set(POLL_INTERVAL_MS, interval.as_millis().to_string());
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(self) -> Result<ConnectorConfig> {
+        self.config.require(&[CONNECTION_URL, TOPIC_PREFIX])?;
+        if self.config.contains_key(QUERY) && self.config.contains_key(TABLE_WHITELIST) {
+            anyhow::bail!("Only one of {} and {} may be set", QUERY, TABLE_WHITELIST);
+        }
+        ConnectorConfigBuilder::new(JDBC_SOURCE_CONNECTOR_CLASS)
+            .extend(self.config)
+            .build()
+    }
+}
+
+/// How the sink writes records to the database
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InsertMode {
+    Insert,
+    Upsert,
+    Update,
+}
+
+impl InsertMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::Upsert => "upsert",
+            Self::Update => "update",
+        }
+    }
+}
+
+/// Where the sink takes the primary key from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PkMode {
+    None,
+    Kafka,
+    RecordKey,
+    RecordValue,
+}
+
+impl PkMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Kafka => "kafka",
+            Self::RecordKey => "record_key",
+            Self::RecordValue => "record_value",
+        }
+    }
+}
+
+/// Builder for the JDBC sink connector
+#[derive(Clone, Debug)]
+pub struct JdbcSinkBuilder {
+    config: ConnectorConfig,
+    topics: Vec<String>,
+    insert_mode: InsertMode,
+    pk_mode: PkMode,
+}
+
+impl JdbcSinkBuilder {
+    pub fn new(connection: JdbcConnection) -> Self {
+        let mut config = ConnectorConfig::new();
+        connection.apply(&mut config);
+        Self {
+            config,
+            topics: Vec::new(),
+            insert_mode: InsertMode::Insert,
+            pk_mode: PkMode::None,
+        }
+    }
+
+    pub fn tasks_max(mut self, tasks_max: u32) -> Self {
+        self.config
+            .set(config_keys::TASKS_MAX, tasks_max.to_string());
+        self
+    }
+
+    pub fn topic(mut self, topic: impl Into<String>) -> Self {
+        self.topics.push(topic.into());
+        self
+    }
+
+    pub fn insert_mode(mut self, mode: InsertMode) -> Self {
+        self.insert_mode = mode;
+        self
+    }
+
+    /// Sets where the primary key comes from, and which fields it is made of
+    pub fn primary_key<I, S>(mut self, mode: PkMode, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.pk_mode = mode;
+        self.config.set_list(PK_FIELDS, fields);
+        self
+    }
+
+    pub fn auto_create(mut self, enabled: bool) -> Self {
+        self.config.set_bool(AUTO_CREATE, enabled);
+        self
+    }
+
+    pub fn auto_evolve(mut self, enabled: bool) -> Self {
+        self.config.set_bool(AUTO_EVOLVE, enabled);
+        self
+    }
+
+    /// Format of the destination table name, `${topic}` is replaced by the topic name
+    pub fn table_name_format(mut self, format: impl Into<String>) -> Self {
+        self.config.set(TABLE_NAME_FORMAT, format);
+        self
+    }
+
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.config.set(BATCH_SIZE, batch_size.to_string());
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(mut self) -> Result<ConnectorConfig> {
+        if self.insert_mode != InsertMode::Insert && self.pk_mode == PkMode::None {
+            anyhow::bail!(
+                "Insert mode {} requires a primary key mode other than none",
+                self.insert_mode.as_str()
+            );
+        }
+        self.config
+            .set(INSERT_MODE, self.insert_mode.as_str())
+            .set(PK_MODE, self.pk_mode.as_str());
+        super::require_sink_topics(&self.topics, &self.config)?;
+        ConnectorConfigBuilder::new(JDBC_SINK_CONNECTOR_CLASS)
+            .topics(self.topics)
+            .extend(self.config)
+            .build()
+    }
+}
@@ -0,0 +1,324 @@
+//! MirrorMaker2 connectors, used to replicate data between kafka clusters
+//! The offsets types read and alter the position of a MirrorSourceConnector in the source
+//! cluster, through [`crate::Connect::connector_offsets_as`]
+use crate::config::{ConnectorConfig, ConnectorConfigBuilder};
+use crate::config_keys;
+use crate::offsets::{ConnectorOffsets, SourceConnectorOffset};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+pub const MIRROR_SOURCE_CONNECTOR_CLASS: &str =
+    "org.apache.kafka.connect.mirror.MirrorSourceConnector";
+pub const MIRROR_CHECKPOINT_CONNECTOR_CLASS: &str =
+    "org.apache.kafka.connect.mirror.MirrorCheckpointConnector";
+pub const MIRROR_HEARTBEAT_CONNECTOR_CLASS: &str =
+    "org.apache.kafka.connect.mirror.MirrorHeartbeatConnector";
+
+const SOURCE_CLUSTER_ALIAS: &str = "source.cluster.alias";
+const TARGET_CLUSTER_ALIAS: &str = "target.cluster.alias";
+const SOURCE_CLUSTER_PREFIX: &str = "source.cluster.";
+const TARGET_CLUSTER_PREFIX: &str = "target.cluster.";
+const BOOTSTRAP_SERVERS: &str = "bootstrap.servers";
+const TOPICS_EXCLUDE: &str = "topics.exclude";
+const GROUPS: &str = "groups";
+const GROUPS_EXCLUDE: &str = "groups.exclude";
+const REPLICATION_FACTOR: &str = "replication.factor";
+const SYNC_TOPIC_CONFIGS_ENABLED: &str = "sync.topic.configs.enabled";
+const SYNC_TOPIC_ACLS_ENABLED: &str = "sync.topic.acls.enabled";
+const REFRESH_TOPICS_INTERVAL_SECONDS: &str = "refresh.topics.interval.seconds";
+const REFRESH_GROUPS_INTERVAL_SECONDS: &str = "refresh.groups.interval.seconds";
+const EMIT_CHECKPOINTS_INTERVAL_SECONDS: &str = "emit.checkpoints.interval.seconds";
+const SYNC_GROUP_OFFSETS_ENABLED: &str = "sync.group.offsets.enabled";
+const SYNC_GROUP_OFFSETS_INTERVAL_SECONDS: &str = "sync.group.offsets.interval.seconds";
+const EMIT_HEARTBEATS_INTERVAL_SECONDS: &str = "emit.heartbeats.interval.seconds";
+const HEARTBEATS_TOPIC_REPLICATION_FACTOR: &str = "heartbeats.topic.replication.factor";
+
+/// The pair of clusters replicated by a MirrorMaker2 connector
+#[derive(Clone, Debug)]
+pub struct MirrorClusters {
+    config: ConnectorConfig,
+}
+
+impl MirrorClusters {
+    pub fn new(
+        source_alias: impl Into<String>,
+        source_bootstrap_servers: impl Into<String>,
+        target_alias: impl Into<String>,
+        target_bootstrap_servers: impl Into<String>,
+    ) -> Self {
+        let mut config = ConnectorConfig::new();
+        config
+            .set(SOURCE_CLUSTER_ALIAS, source_alias)
+            .set(TARGET_CLUSTER_ALIAS, target_alias)
+            .set(
+                format!("{}{}", SOURCE_CLUSTER_PREFIX, BOOTSTRAP_SERVERS),
+                source_bootstrap_servers,
+            )
+            .set(
+                format!("{}{}", TARGET_CLUSTER_PREFIX, BOOTSTRAP_SERVERS),
+                target_bootstrap_servers,
+            );
+        Self { config }
+    }
+
+    /// Sets a client property for the source cluster, e.g. `security.protocol`
+    pub fn source_property(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.config
+            .set(format!("{}{}", SOURCE_CLUSTER_PREFIX, key), value);
+        self
+    }
+
+    /// Sets a client property for the target cluster, e.g. `security.protocol`
+    pub fn target_property(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.config
+            .set(format!("{}{}", TARGET_CLUSTER_PREFIX, key), value);
+        self
+    }
+}
+
+fn seconds(interval: Duration) -> String {
+    interval.as_secs().to_string()
+}
+
+/// Builder for the connector replicating topics
+#[derive(Clone, Debug)]
+pub struct MirrorSourceBuilder {
+    config: ConnectorConfig,
+}
+
+impl MirrorSourceBuilder {
+    pub fn new(clusters: MirrorClusters) -> Self {
+        Self {
+            config: clusters.config,
+        }
+    }
+
+    pub fn tasks_max(mut self, tasks_max: u32) -> Self {
+        self.config
+            .set(config_keys::TASKS_MAX, tasks_max.to_string());
+        self
+    }
+
+    /// Topics to replicate, every entry is a regular expression
+    pub fn topics<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(config_keys::TOPICS, topics);
+        self
+    }
+
+    pub fn topics_exclude<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(TOPICS_EXCLUDE, topics);
+        self
+    }
+
+    /// Replication factor of the topics created on the target cluster
+    pub fn replication_factor(mut self, replication_factor: u16) -> Self {
+        self.config
+            .set(REPLICATION_FACTOR, replication_factor.to_string());
+        self
+    }
+
+    pub fn sync_topic_configs(mut self, enabled: bool) -> Self {
+        self.config.set_bool(SYNC_TOPIC_CONFIGS_ENABLED, enabled);
+        self
+    }
+
+    pub fn sync_topic_acls(mut self, enabled: bool) -> Self {
+        self.config.set_bool(SYNC_TOPIC_ACLS_ENABLED, enabled);
+        self
+    }
+
+    /// How often new topics are looked up on the source cluster
+    pub fn refresh_topics_interval(mut self, interval: Duration) -> Self {
+        self.config
+            .set(REFRESH_TOPICS_INTERVAL_SECONDS, seconds(interval));
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(self) -> Result<ConnectorConfig> {
+        self.config.require(&[config_keys::TOPICS])?;
+        ConnectorConfigBuilder::new(MIRROR_SOURCE_CONNECTOR_CLASS)
+            .extend(self.config)
+            .build()
+    }
+}
+
+/// Builder for the connector translating consumer group offsets
+#[derive(Clone, Debug)]
+pub struct MirrorCheckpointBuilder {
+    config: ConnectorConfig,
+}
+
+impl MirrorCheckpointBuilder {
+    pub fn new(clusters: MirrorClusters) -> Self {
+        Self {
+            config: clusters.config,
+        }
+    }
+
+    pub fn tasks_max(mut self, tasks_max: u32) -> Self {
+        self.config
+            .set(config_keys::TASKS_MAX, tasks_max.to_string());
+        self
+    }
+
+    /// Consumer groups to replicate, every entry is a regular expression
+    pub fn groups<I, S>(mut self, groups: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(GROUPS, groups);
+        self
+    }
+
+    pub fn groups_exclude<I, S>(mut self, groups: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.set_list(GROUPS_EXCLUDE, groups);
+        self
+    }
+
+    pub fn emit_checkpoints_interval(mut self, interval: Duration) -> Self {
+        self.config
+            .set(EMIT_CHECKPOINTS_INTERVAL_SECONDS, seconds(interval));
+        self
+    }
+
+    /// Writes the translated offsets to the consumer groups of the target cluster
+    pub fn sync_group_offsets(mut self, interval: Duration) -> Self {
+        self.config
+            .set_bool(SYNC_GROUP_OFFSETS_ENABLED, true)
+            .set(SYNC_GROUP_OFFSETS_INTERVAL_SECONDS, seconds(interval));
+        self
+    }
+
+    /// How often new consumer groups are looked up on the source cluster
+    pub fn refresh_groups_interval(mut self, interval: Duration) -> Self {
+        self.config
+            .set(REFRESH_GROUPS_INTERVAL_SECONDS, seconds(interval));
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(self) -> Result<ConnectorConfig> {
+        ConnectorConfigBuilder::new(MIRROR_CHECKPOINT_CONNECTOR_CLASS)
+            .extend(self.config)
+            .build()
+    }
+}
+
+/// Builder for the connector emitting heartbeats, used to monitor replication
+#[derive(Clone, Debug)]
+pub struct MirrorHeartbeatBuilder {
+    config: ConnectorConfig,
+}
+
+impl MirrorHeartbeatBuilder {
+    pub fn new(clusters: MirrorClusters) -> Self {
+        Self {
+            config: clusters.config,
+        }
+    }
+
+    pub fn emit_heartbeats_interval(mut self, interval: Duration) -> Self {
+        self.config
+            .set(EMIT_HEARTBEATS_INTERVAL_SECONDS, seconds(interval));
+        self
+    }
+
+    pub fn heartbeats_topic_replication_factor(mut self, replication_factor: u16) -> Self {
+        self.config.set(
+            HEARTBEATS_TOPIC_REPLICATION_FACTOR,
+            replication_factor.to_string(),
+        );
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(self) -> Result<ConnectorConfig> {
+        ConnectorConfigBuilder::new(MIRROR_HEARTBEAT_CONNECTOR_CLASS)
+            .tasks_max(1)
+            .extend(self.config)
+            .build()
+    }
+}
+
+/// Source partition of the MirrorSourceConnector, a partition of the source cluster
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MirrorPartition {
+    /// Alias of the source cluster
+    pub cluster: String,
+    pub topic: String,
+    pub partition: i32,
+}
+
+/// Source offset of the MirrorSourceConnector, the last offset replicated from the partition
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MirrorOffset {
+    pub offset: i64,
+}
+
+pub type MirrorSourceOffset = SourceConnectorOffset<MirrorPartition, MirrorOffset>;
+
+impl MirrorSourceOffset {
+    /// Offset of a partition, to alter the offsets of a MirrorSourceConnector
+    pub fn mirror(
+        cluster: impl Into<String>,
+        topic: impl Into<String>,
+        partition: i32,
+        offset: i64,
+    ) -> Self {
+        Self {
+            partition: MirrorPartition {
+                cluster: cluster.into(),
+                topic: topic.into(),
+                partition,
+            },
+            offset: Some(MirrorOffset { offset }),
+        }
+    }
+}
+
+impl ConnectorOffsets<MirrorPartition, MirrorOffset> {
+    /// Offset replicated from each partition, partitions being reset left out
+    pub fn positions(&self) -> BTreeMap<&MirrorPartition, i64> {
+        self.offsets
+            .iter()
+            .filter_map(|offset| {
+                offset
+                    .offset
+                    .map(|position| (&offset.partition, position.offset))
+            })
+            .collect()
+    }
+}
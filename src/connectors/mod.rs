@@ -0,0 +1,28 @@
+//! Typed configuration builders for commonly used connectors
+//! Every family of connectors lives behind its own feature
+
+#[cfg(feature = "debezium")]
+pub mod debezium;
+#[cfg(feature = "elasticsearch")]
+pub mod elasticsearch;
+#[cfg(feature = "jdbc")]
+pub mod jdbc;
+#[cfg(feature = "mirrormaker")]
+pub mod mirror;
+#[cfg(feature = "s3")]
+pub mod s3;
+
+/// Fails unless the sink consumes some topic, given to its builder or set in its configuration
+#[cfg(any(feature = "elasticsearch", feature = "jdbc", feature = "s3"))]
+fn require_sink_topics(
+    topics: &[String],
+    config: &crate::config::ConnectorConfig,
+) -> anyhow::Result<()> {
+    let has_topics = !topics.is_empty()
+        || config.contains_key(crate::config_keys::TOPICS)
+        || config.contains_key(crate::config_keys::TOPICS_REGEX);
+    if !has_topics {
+        anyhow::bail!("A sink connector needs at least one topic");
+    }
+    Ok(())
+}
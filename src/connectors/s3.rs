@@ -0,0 +1,210 @@
+//! Confluent S3 sink connector
+use crate::config::{ConnectorConfig, ConnectorConfigBuilder};
+use crate::config_keys;
+use anyhow::Result;
+use std::time::Duration;
+
+pub const S3_SINK_CONNECTOR_CLASS: &str = "io.confluent.connect.s3.S3SinkConnector";
+pub const S3_STORAGE_CLASS: &str = "io.confluent.connect.s3.storage.S3Storage";
+
+const S3_BUCKET_NAME: &str = "s3.bucket.name";
+const S3_REGION: &str = "s3.region";
+const STORAGE_CLASS: &str = "storage.class";
+const FORMAT_CLASS: &str = "format.class";
+const FLUSH_SIZE: &str = "flush.size";
+const ROTATE_INTERVAL_MS: &str = "rotate.interval.ms";
+const TOPICS_DIR: &str = "topics.dir";
+const PARTITIONER_CLASS: &str = "partitioner.class";
+const PARTITION_FIELD_NAME: &str = "partition.field.name";
+const PARTITION_DURATION_MS: &str = "partition.duration.ms";
+const PATH_FORMAT: &str = "path.format";
+const LOCALE: &str = "locale";
+const TIMEZONE: &str = "timezone";
+
+/// Format of the objects written to the bucket
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum S3Format {
+    Avro,
+    Json,
+    Parquet,
+    ByteArray,
+}
+
+impl S3Format {
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Avro => "io.confluent.connect.s3.format.avro.AvroFormat",
+            Self::Json => "io.confluent.connect.s3.format.json.JsonFormat",
+            Self::Parquet => "io.confluent.connect.s3.format.parquet.ParquetFormat",
+            Self::ByteArray => "io.confluent.connect.s3.format.bytearray.ByteArrayFormat",
+        }
+    }
+}
+
+/// How records are laid out across the bucket
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum S3Partitioner {
+    /// Partitions by kafka partition
+    Default,
+    /// Partitions by the value of the given record fields
+    Field {
+        fields: Vec<String>,
+    },
+    /// Partitions by time, `path_format` follows the java `DateTimeFormatter` syntax
+    TimeBased {
+        duration: Duration,
+        path_format: String,
+        locale: String,
+        timezone: String,
+    },
+    Daily {
+        locale: String,
+        timezone: String,
+    },
+    Hourly {
+        locale: String,
+        timezone: String,
+    },
+}
+
+impl S3Partitioner {
+    fn apply(&self, config: &mut ConnectorConfig) {
+        match self {
+            Self::Default => {
+                config.set(
+                    PARTITIONER_CLASS,
+                    "io.confluent.connect.storage.partitioner.DefaultPartitioner",
+                );
+            }
+            Self::Field { fields } => {
+                config
+                    .set(
+                        PARTITIONER_CLASS,
+                        "io.confluent.connect.storage.partitioner.FieldPartitioner",
+                    )
+                    .set_list(PARTITION_FIELD_NAME, fields);
+            }
+            Self::TimeBased {
+                duration,
+                path_format,
+                locale,
+                timezone,
+            } => {
+                config
+                    .set(
+                        PARTITIONER_CLASS,
+                        "io.confluent.connect.storage.partitioner.TimeBasedPartitioner",
+                    )
+                    .set(PARTITION_DURATION_MS, duration.as_millis().to_string())
+                    .set(PATH_FORMAT, path_format.as_str())
+                    .set(LOCALE, locale.as_str())
+                    .set(TIMEZONE, timezone.as_str());
+            }
+            Self::Daily { locale, timezone } => {
+                config
+                    .set(
+                        PARTITIONER_CLASS,
+                        "io.confluent.connect.storage.partitioner.DailyPartitioner",
+                    )
+                    .set(LOCALE, locale.as_str())
+                    .set(TIMEZONE, timezone.as_str());
+            }
+            Self::Hourly { locale, timezone } => {
+                config
+                    .set(
+                        PARTITIONER_CLASS,
+                        "io.confluent.connect.storage.partitioner.HourlyPartitioner",
+                    )
+                    .set(LOCALE, locale.as_str())
+                    .set(TIMEZONE, timezone.as_str());
+            }
+        }
+    }
+}
+
+/// Builder for the S3 sink connector
+#[derive(Clone, Debug)]
+pub struct S3SinkConfigBuilder {
+    config: ConnectorConfig,
+    topics: Vec<String>,
+    format: S3Format,
+    partitioner: S3Partitioner,
+}
+
+impl S3SinkConfigBuilder {
+    pub fn new(bucket: impl Into<String>, region: impl Into<String>) -> Self {
+        let mut config = ConnectorConfig::new();
+        config
+            .set(S3_BUCKET_NAME, bucket)
+            .set(S3_REGION, region)
+            .set(STORAGE_CLASS, S3_STORAGE_CLASS);
+        Self {
+            config,
+            topics: Vec::new(),
+            format: S3Format::Json,
+            partitioner: S3Partitioner::Default,
+        }
+    }
+
+    pub fn tasks_max(mut self, tasks_max: u32) -> Self {
+        self.config
+            .set(config_keys::TASKS_MAX, tasks_max.to_string());
+        self
+    }
+
+    pub fn topic(mut self, topic: impl Into<String>) -> Self {
+        self.topics.push(topic.into());
+        self
+    }
+
+    /// Number of records written to a single object before it gets committed
+    pub fn flush_size(mut self, flush_size: u64) -> Self {
+        self.config.set(FLUSH_SIZE, flush_size.to_string());
+        self
+    }
+
+    /// Maximum amount of time an object stays open before it gets committed
+    pub fn rotate_interval(mut self, interval: Duration) -> Self {
+        self.config
+            .set(ROTATE_INTERVAL_MS, interval.as_millis().to_string());
+        self
+    }
+
+    /// Format of the objects, unless `format.class` is set directly
+    pub fn format(mut self, format: S3Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn partitioner(mut self, partitioner: S3Partitioner) -> Self {
+        self.partitioner = partitioner;
+        self
+    }
+
+    /// Top level directory of the objects
+    pub fn topics_dir(mut self, dir: impl Into<String>) -> Self {
+        self.config.set(TOPICS_DIR, dir);
+        self
+    }
+
+    /// Sets any other key
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn build(mut self) -> Result<ConnectorConfig> {
+        self.config
+            .require(&[S3_BUCKET_NAME, S3_REGION, FLUSH_SIZE])?;
+        // a format class set by hand is kept
+        if !self.config.contains_key(FORMAT_CLASS) {
+            self.config.set(FORMAT_CLASS, self.format.class());
+        }
+        self.partitioner.apply(&mut self.config);
+        super::require_sink_topics(&self.topics, &self.config)?;
+        ConnectorConfigBuilder::new(S3_SINK_CONNECTOR_CLASS)
+            .topics(self.topics)
+            .extend(self.config)
+            .build()
+    }
+}
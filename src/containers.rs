@@ -0,0 +1,162 @@
+//! Kafka and kafka-connect containers for integration tests, started through testcontainers
+//! Only available with the `containers` feature. A running docker daemon is required
+use crate::Connect;
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, GenericImage, ImageExt};
+
+const CONNECT_PORT: u16 = 8083;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static CLUSTERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Images and timeouts of the containers
+#[derive(Clone, Debug)]
+pub struct ContainersOptions {
+    /// Image and tag of a KRaft enabled kafka broker
+    pub kafka_image: (String, String),
+    pub connect_image: (String, String),
+    /// Maximum time given to each container to start, and to the REST API to answer
+    pub startup_timeout: Duration,
+}
+
+impl Default for ContainersOptions {
+    fn default() -> Self {
+        Self {
+            kafka_image: ("apache/kafka".to_string(), "3.7.0".to_string()),
+            connect_image: (
+                "confluentinc/cp-kafka-connect".to_string(),
+                "7.6.0".to_string(),
+            ),
+            startup_timeout: Duration::from_secs(180),
+        }
+    }
+}
+
+/// A single broker and a single worker on a private network, removed when dropped
+pub struct ConnectCluster {
+    client: Connect,
+    url: String,
+    // kept alive for the lifetime of the cluster
+    _connect: ContainerAsync<GenericImage>,
+    _kafka: ContainerAsync<GenericImage>,
+}
+
+impl ConnectCluster {
+    pub async fn start() -> Result<Self> {
+        Self::start_with(ContainersOptions::default()).await
+    }
+
+    /// Starts both containers, and waits for the REST API of the worker to answer
+    pub async fn start_with(options: ContainersOptions) -> Result<Self> {
+        let id = format!(
+            "{}-{}-{}",
+            std::process::id(),
+            CLUSTERS.fetch_add(1, Ordering::Relaxed),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis())
+                .unwrap_or_default()
+        );
+        let network = format!("connect-rs-{}", id);
+        let kafka_host = format!("kafka-{}", id);
+        let bootstrap = format!("{}:9092", kafka_host);
+
+        let (name, tag) = &options.kafka_image;
+        let kafka = GenericImage::new(name.as_str(), tag.as_str())
+            .with_wait_for(WaitFor::message_on_stdout("Kafka Server started"))
+            .with_network(&network)
+            .with_container_name(&kafka_host)
+            .with_env_var("KAFKA_NODE_ID", "1")
+            .with_env_var("KAFKA_PROCESS_ROLES", "broker,controller")
+            .with_env_var("KAFKA_LISTENERS", "PLAINTEXT://:9092,CONTROLLER://:9093")
+            .with_env_var(
+                "KAFKA_ADVERTISED_LISTENERS",
+                format!("PLAINTEXT://{}", bootstrap),
+            )
+            .with_env_var("KAFKA_CONTROLLER_LISTENER_NAMES", "CONTROLLER")
+            .with_env_var(
+                "KAFKA_LISTENER_SECURITY_PROTOCOL_MAP",
+                "CONTROLLER:PLAINTEXT,PLAINTEXT:PLAINTEXT",
+            )
+            .with_env_var("KAFKA_CONTROLLER_QUORUM_VOTERS", "1@localhost:9093")
+            .with_env_var("KAFKA_OFFSETS_TOPIC_REPLICATION_FACTOR", "1")
+            .with_env_var("KAFKA_TRANSACTION_STATE_LOG_REPLICATION_FACTOR", "1")
+            .with_env_var("KAFKA_TRANSACTION_STATE_LOG_MIN_ISR", "1")
+            .with_startup_timeout(options.startup_timeout)
+            .start()
+            .await
+            .context("Could not start the kafka container")?;
+
+        let (name, tag) = &options.connect_image;
+        let connect = GenericImage::new(name.as_str(), tag.as_str())
+            .with_exposed_port(CONNECT_PORT.tcp())
+            .with_wait_for(WaitFor::message_on_either_std("Kafka Connect started"))
+            .with_network(&network)
+            .with_env_var("CONNECT_BOOTSTRAP_SERVERS", &bootstrap)
+            .with_env_var("CONNECT_REST_ADVERTISED_HOST_NAME", "localhost")
+            .with_env_var("CONNECT_REST_PORT", CONNECT_PORT.to_string())
+            .with_env_var("CONNECT_GROUP_ID", "connect-rs")
+            .with_env_var("CONNECT_CONFIG_STORAGE_TOPIC", "connect-rs-configs")
+            .with_env_var("CONNECT_OFFSET_STORAGE_TOPIC", "connect-rs-offsets")
+            .with_env_var("CONNECT_STATUS_STORAGE_TOPIC", "connect-rs-status")
+            .with_env_var("CONNECT_CONFIG_STORAGE_REPLICATION_FACTOR", "1")
+            .with_env_var("CONNECT_OFFSET_STORAGE_REPLICATION_FACTOR", "1")
+            .with_env_var("CONNECT_STATUS_STORAGE_REPLICATION_FACTOR", "1")
+            .with_env_var(
+                "CONNECT_KEY_CONVERTER",
+                "org.apache.kafka.connect.json.JsonConverter",
+            )
+            .with_env_var(
+                "CONNECT_VALUE_CONVERTER",
+                "org.apache.kafka.connect.json.JsonConverter",
+            )
+            .with_env_var(
+                "CONNECT_PLUGIN_PATH",
+                "/usr/share/java,/usr/share/filestream-connectors",
+            )
+            .with_startup_timeout(options.startup_timeout)
+            .start()
+            .await
+            .context("Could not start the kafka-connect container")?;
+
+        let host = connect.get_host().await?;
+        let port = connect.get_host_port_ipv4(CONNECT_PORT).await?;
+        let url = format!("http://{}:{}", host, port);
+        let client = Connect::new(&url, "", None);
+        wait_until_ready(&client, options.startup_timeout).await?;
+        Ok(Self {
+            client,
+            url,
+            _connect: connect,
+            _kafka: kafka,
+        })
+    }
+
+    pub fn client(&self) -> &Connect {
+        &self.client
+    }
+
+    /// Address of the REST API, as reachable from the host
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// The worker logs that it started before joining the group, so the API is polled until it answers
+async fn wait_until_ready(client: &Connect, timeout: Duration) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        let ready = tokio::time::timeout(POLL_INTERVAL * 4, client.connector_names()).await;
+        if let Ok(Ok(_)) = ready {
+            return Ok(());
+        }
+        if started.elapsed() > timeout {
+            anyhow::bail!("kafka-connect did not become ready in time");
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
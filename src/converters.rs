@@ -0,0 +1,112 @@
+//! Key and value converters
+//! Every converter property is prefixed with the converter key, e.g. `value.converter.schemas.enable`
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+
+pub const STRING_CONVERTER_CLASS: &str = "org.apache.kafka.connect.storage.StringConverter";
+pub const BYTE_ARRAY_CONVERTER_CLASS: &str =
+    "org.apache.kafka.connect.converters.ByteArrayConverter";
+pub const JSON_CONVERTER_CLASS: &str = "org.apache.kafka.connect.json.JsonConverter";
+pub const AVRO_CONVERTER_CLASS: &str = "io.confluent.connect.avro.AvroConverter";
+pub const PROTOBUF_CONVERTER_CLASS: &str = "io.confluent.connect.protobuf.ProtobufConverter";
+
+/// Schema registry used by the avro and protobuf converters
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaRegistry {
+    pub url: String,
+    pub basic_auth: Option<(String, String)>,
+}
+
+impl SchemaRegistry {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            basic_auth: None,
+        }
+    }
+
+    pub fn basic_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.basic_auth = Some((username.into(), password.into()));
+        self
+    }
+
+    fn apply(&self, prefix: &str, config: &mut ConnectorConfig) {
+        config.set(format!("{}.schema.registry.url", prefix), self.url.as_str());
+        if let Some((username, password)) = &self.basic_auth {
+            config
+                .set(
+                    format!("{}.basic.auth.credentials.source", prefix),
+                    "USER_INFO",
+                )
+                .set(
+                    format!("{}.basic.auth.user.info", prefix),
+                    format!("{}:{}", username, password),
+                );
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Converter {
+    String,
+    ByteArray,
+    Json {
+        schemas_enable: bool,
+    },
+    Avro(SchemaRegistry),
+    Protobuf(SchemaRegistry),
+    /// Any other converter class, without additional properties
+    Custom(String),
+}
+
+impl Converter {
+    pub fn class(&self) -> &str {
+        match self {
+            Self::String => STRING_CONVERTER_CLASS,
+            Self::ByteArray => BYTE_ARRAY_CONVERTER_CLASS,
+            Self::Json { .. } => JSON_CONVERTER_CLASS,
+            Self::Avro(_) => AVRO_CONVERTER_CLASS,
+            Self::Protobuf(_) => PROTOBUF_CONVERTER_CLASS,
+            Self::Custom(class) => class,
+        }
+    }
+
+    /// Sets the converter under the given key, along with its properties
+    pub fn apply_to(&self, key: &str, config: &mut ConnectorConfig) {
+        // properties of a previously configured converter don't apply anymore
+        let prefix = format!("{}.", key);
+        config.retain(|existing, _| !existing.starts_with(&prefix));
+        config.set(key, self.class());
+        match self {
+            Self::Json { schemas_enable } => {
+                config.set_bool(format!("{}.schemas.enable", key), *schemas_enable);
+            }
+            Self::Avro(registry) | Self::Protobuf(registry) => registry.apply(key, config),
+            Self::String | Self::ByteArray | Self::Custom(_) => {}
+        }
+    }
+}
+
+impl From<&str> for Converter {
+    fn from(class: &str) -> Self {
+        Self::Custom(class.to_string())
+    }
+}
+
+impl From<String> for Converter {
+    fn from(class: String) -> Self {
+        Self::Custom(class)
+    }
+}
+
+impl ConnectorConfig {
+    pub fn set_key_converter(&mut self, converter: &Converter) -> &mut Self {
+        converter.apply_to(config_keys::KEY_CONVERTER, self);
+        self
+    }
+
+    pub fn set_value_converter(&mut self, converter: &Converter) -> &mut Self {
+        converter.apply_to(config_keys::VALUE_CONVERTER, self);
+        self
+    }
+}
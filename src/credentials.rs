@@ -0,0 +1,83 @@
+//! Credentials looked up for every request, for secrets that rotate or live in a secret store
+use crate::ENGINE;
+use anyhow::Result;
+use async_trait::async_trait;
+use base64::Engine;
+use http::Extensions;
+use reqwest::{header, Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use std::sync::Arc;
+
+/// How a request is authenticated
+#[derive(Clone, PartialEq, Eq)]
+pub enum Credentials {
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    Bearer(String),
+}
+
+impl Credentials {
+    fn header_value(&self) -> Result<header::HeaderValue> {
+        let value = match self {
+            Self::Basic { username, password } => format!(
+                "Basic {}",
+                ENGINE.encode(format!(
+                    "{}:{}",
+                    username,
+                    password.as_deref().unwrap_or("")
+                ))
+            ),
+            Self::Bearer(token) => format!("Bearer {}", token),
+        };
+        let mut value = header::HeaderValue::from_str(&value)?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
+}
+
+// the secrets are kept out of logs
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .finish_non_exhaustive(),
+            Self::Bearer(_) => f.write_str("Bearer"),
+        }
+    }
+}
+
+/// Source of the credentials of the requests, asked before every attempt
+#[async_trait]
+pub trait CredentialsProvider: Send + Sync + 'static {
+    /// None sends the request unauthenticated
+    async fn credentials(&self) -> Result<Option<Credentials>>;
+}
+
+#[async_trait]
+impl CredentialsProvider for Credentials {
+    async fn credentials(&self) -> Result<Option<Credentials>> {
+        Ok(Some(self.clone()))
+    }
+}
+
+pub(crate) struct CredentialsMiddleware(pub(crate) Arc<dyn CredentialsProvider>);
+
+#[async_trait]
+impl Middleware for CredentialsMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if let Some(credentials) = self.0.credentials().await? {
+            req.headers_mut()
+                .insert(header::AUTHORIZATION, credentials.header_value()?);
+        }
+        next.run(req, extensions).await
+    }
+}
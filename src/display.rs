@@ -0,0 +1,176 @@
+//! Human readable tables of connector listings and statuses, for quick reports
+//! Only available with the `display` feature. States are colored with ANSI escapes when asked to,
+//! which should only be done when printing to a terminal
+use crate::models::{Connector, ConnectorStatus, Status};
+use std::collections::{BTreeMap, HashMap};
+
+/// Renders a table as text, see [`render`]
+pub trait FmtTable {
+    fn fmt_table(&self, color: bool) -> String;
+}
+
+/// Width of the text once printed, ANSI escapes being invisible
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skips the escape up to its final letter, such as in `\x1b[1;31m`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// Renders rows as left aligned columns, separated by two spaces. Cells may hold ANSI escapes
+pub fn render(headers: &[&str], rows: &[Vec<String>]) -> Vec<String> {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(visible_width(cell));
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                let padding = width.saturating_sub(visible_width(cell));
+                format!("{}{}", cell, " ".repeat(padding))
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let mut lines = vec![line(headers.to_vec())];
+    lines.extend(
+        rows.iter()
+            .map(|row| line(row.iter().map(String::as_str).collect())),
+    );
+    lines
+}
+
+/// The state, colored when `color` is set: green when running, red when failed, yellow when
+/// transient and dim otherwise
+pub fn paint_state(state: Status, color: bool) -> String {
+    if !color {
+        return state.to_string();
+    }
+    let code = match state {
+        Status::Running => "32",
+        Status::Failed => "1;31",
+        Status::Restarting | Status::Unassigned => "33",
+        Status::Paused | Status::Stopped => "2",
+    };
+    format!("\x1b[{}m{}\x1b[0m", code, state)
+}
+
+/// Headers of the rows of [`listing_row`]
+pub const LISTING_HEADERS: [&str; 5] = ["CONNECTOR", "TYPE", "STATE", "FAILED TASKS", "WORKERS"];
+
+/// Row of a connector in a listing
+pub fn listing_row(status: &ConnectorStatus, color: bool) -> Vec<String> {
+    vec![
+        status.name.clone(),
+        status.kind.clone(),
+        paint_state(status.connector.state, color),
+        status.failed_tasks().len().to_string(),
+        status.workers().into_iter().collect::<Vec<_>>().join(","),
+    ]
+}
+
+/// Lines of a table with a row per connector, in the order given, after the header
+pub fn listing<'a>(
+    statuses: impl IntoIterator<Item = &'a ConnectorStatus>,
+    color: bool,
+) -> Vec<String> {
+    let rows: Vec<Vec<String>> = statuses
+        .into_iter()
+        .map(|status| listing_row(status, color))
+        .collect();
+    render(&LISTING_HEADERS, &rows)
+}
+
+/// The connector on a line, then a row per task with the root cause of its failure
+impl FmtTable for ConnectorStatus {
+    fn fmt_table(&self, color: bool) -> String {
+        let header = format!(
+            "{} ({}): {} on {}",
+            self.name,
+            self.kind,
+            paint_state(self.connector.state, color),
+            self.connector.worker_id
+        );
+        let rows: Vec<Vec<String>> = self
+            .tasks
+            .iter()
+            .map(|task| {
+                let error = task
+                    .exception()
+                    .map(|exception| {
+                        let root = exception.root_cause();
+                        format!(
+                            "{}: {}",
+                            root.class,
+                            root.message.as_deref().unwrap_or_default()
+                        )
+                    })
+                    .unwrap_or_default();
+                vec![
+                    task.id.to_string(),
+                    paint_state(task.state, color),
+                    task.worker_id.clone(),
+                    error,
+                ]
+            })
+            .collect();
+        let mut lines = vec![header];
+        if !rows.is_empty() {
+            lines.extend(render(&["TASK", "STATE", "WORKER", "ERROR"], &rows));
+        }
+        lines.join("\n")
+    }
+}
+
+impl FmtTable for [ConnectorStatus] {
+    fn fmt_table(&self, color: bool) -> String {
+        listing(self, color).join("\n")
+    }
+}
+
+impl FmtTable for BTreeMap<String, ConnectorStatus> {
+    fn fmt_table(&self, color: bool) -> String {
+        listing(self.values(), color).join("\n")
+    }
+}
+
+/// Listing of [`crate::Connect::connectors`], sorted by name. Connectors listed without their
+/// status only show their type
+impl FmtTable for HashMap<String, Connector> {
+    fn fmt_table(&self, color: bool) -> String {
+        let mut connectors: Vec<(&String, &Connector)> = self.iter().collect();
+        connectors.sort_by(|a, b| a.0.cmp(b.0));
+        let rows: Vec<Vec<String>> = connectors
+            .into_iter()
+            .map(|(name, connector)| match &connector.status {
+                Some(status) => listing_row(status, color),
+                None => vec![
+                    name.clone(),
+                    connector
+                        .info
+                        .as_ref()
+                        .map(|info| info.kind.clone())
+                        .unwrap_or_default(),
+                ],
+            })
+            .collect();
+        render(&LISTING_HEADERS, &rows).join("\n")
+    }
+}
@@ -0,0 +1,344 @@
+//! Dry runs, previewing what a workflow would change on a cluster
+//! A client with a [`DryRun`] never calls the endpoints that mutate the cluster. Each mutating
+//! method checks what it can without changing anything, such as whether the connector exists or
+//! whether the configuration validates, then records the [`Mutation`] it would have made and
+//! returns as if it succeeded. Create and update return the configuration sent, without tasks nor
+//! type, as those are only known once the connector runs
+use crate::config::ConnectorConfig;
+use crate::models::{ConnectorInfo, LoggerScope, Status};
+use crate::Connect;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Change a dry run did not make
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Mutation {
+    Create {
+        connector: String,
+        initial_state: Option<Status>,
+    },
+    /// Update of the configuration, creating the connector when missing. Only the names of the keys
+    /// are kept, as the values may be secrets
+    UpdateConfig {
+        connector: String,
+        created: bool,
+        changed_keys: Vec<String>,
+    },
+    PatchConfig {
+        connector: String,
+        changed_keys: Vec<String>,
+    },
+    Restart {
+        connector: String,
+        include_tasks: bool,
+        only_failed: bool,
+    },
+    RestartTask {
+        connector: String,
+        task: u64,
+    },
+    Pause {
+        connector: String,
+    },
+    Resume {
+        connector: String,
+    },
+    Stop {
+        connector: String,
+    },
+    Delete {
+        connector: String,
+    },
+    AlterOffsets {
+        connector: String,
+    },
+    ResetOffsets {
+        connector: String,
+    },
+    SetLoggerLevel {
+        logger: String,
+        level: String,
+        scope: String,
+    },
+}
+
+impl Mutation {
+    /// The connector changed, unset for the loggers
+    fn connector(&self) -> Option<&str> {
+        match self {
+            Self::Create { connector, .. }
+            | Self::UpdateConfig { connector, .. }
+            | Self::PatchConfig { connector, .. }
+            | Self::Restart { connector, .. }
+            | Self::RestartTask { connector, .. }
+            | Self::Pause { connector }
+            | Self::Resume { connector }
+            | Self::Stop { connector }
+            | Self::Delete { connector }
+            | Self::AlterOffsets { connector }
+            | Self::ResetOffsets { connector } => Some(connector),
+            Self::SetLoggerLevel { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Create {
+                connector,
+                initial_state,
+            } => match initial_state {
+                Some(state) => write!(f, "would create connector {} as {}", connector, state),
+                None => write!(f, "would create connector {}", connector),
+            },
+            Self::UpdateConfig {
+                connector,
+                created: true,
+                ..
+            } => write!(f, "would create connector {}", connector),
+            Self::UpdateConfig {
+                connector,
+                changed_keys,
+                ..
+            }
+            | Self::PatchConfig {
+                connector,
+                changed_keys,
+            } => write!(
+                f,
+                "would change {} of connector {}",
+                changed_keys.join(", "),
+                connector
+            ),
+            Self::Restart {
+                connector,
+                include_tasks,
+                only_failed,
+            } => match (include_tasks, only_failed) {
+                (false, _) => write!(f, "would restart connector {}", connector),
+                (true, false) => write!(f, "would restart connector {} and its tasks", connector),
+                (true, true) => write!(
+                    f,
+                    "would restart the failed instances of connector {}",
+                    connector
+                ),
+            },
+            Self::RestartTask { connector, task } => {
+                write!(f, "would restart task {} of connector {}", task, connector)
+            }
+            Self::Pause { connector } => write!(f, "would pause connector {}", connector),
+            Self::Resume { connector } => write!(f, "would resume connector {}", connector),
+            Self::Stop { connector } => write!(f, "would stop connector {}", connector),
+            Self::Delete { connector } => write!(f, "would delete connector {}", connector),
+            Self::AlterOffsets { connector } => {
+                write!(f, "would alter the offsets of connector {}", connector)
+            }
+            Self::ResetOffsets { connector } => {
+                write!(f, "would reset the offsets of connector {}", connector)
+            }
+            Self::SetLoggerLevel {
+                logger,
+                level,
+                scope,
+            } => write!(
+                f,
+                "would set logger {} to {} on the {}",
+                logger, level, scope
+            ),
+        }
+    }
+}
+
+/// Mutations recorded during a dry run. Clones share the same record
+#[derive(Clone, Debug, Default)]
+pub struct DryRun {
+    mutations: Arc<Mutex<Vec<Mutation>>>,
+}
+
+impl DryRun {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mutations recorded so far, in order
+    pub fn mutations(&self) -> Vec<Mutation> {
+        self.mutations.lock().unwrap().clone()
+    }
+
+    /// Mutations recorded so far, clearing the record
+    pub fn take(&self) -> Vec<Mutation> {
+        std::mem::take(&mut *self.mutations.lock().unwrap())
+    }
+
+    /// Whether the last change recorded for the connector deletes it
+    fn deleted(&self, connector: &str) -> bool {
+        self.mutations
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|mutation| mutation.connector() == Some(connector))
+            .is_some_and(|mutation| matches!(mutation, Mutation::Delete { .. }))
+    }
+
+    fn record(&self, mutation: Mutation) {
+        self.mutations.lock().unwrap().push(mutation);
+    }
+}
+
+/// Keys whose value differs between the configurations, sorted
+fn changed_keys(before: &ConnectorConfig, after: &ConnectorConfig) -> Vec<String> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect()
+}
+
+impl Connect {
+    /// Same client previewing its mutations in the dry run, for a single workflow
+    pub fn with_dry_run(&self, dry_run: DryRun) -> Connect {
+        Connect {
+            dry_run: Some(dry_run),
+            ..self.clone()
+        }
+    }
+
+    /// The dry run of the client, when it does not mutate the cluster
+    pub fn dry_run(&self) -> Option<&DryRun> {
+        self.dry_run.as_ref()
+    }
+
+    async fn validated(&self, name: &str, config: &ConnectorConfig) -> Result<ConnectorConfig> {
+        let mut config = config.clone();
+        config
+            .entry(crate::config_keys::NAME.to_string())
+            .or_insert_with(|| name.to_string());
+        let validation = self.validate_connector_config(&config).await?;
+        if !validation.is_valid() {
+            let errors: Vec<String> = validation
+                .errors()
+                .into_iter()
+                .map(|(key, errors)| format!("{}: {}", key, errors.join(", ")))
+                .collect();
+            anyhow::bail!("Invalid configuration: {}", errors.join("; "));
+        }
+        Ok(config)
+    }
+
+    pub(crate) async fn preview_create(
+        &self,
+        dry_run: &DryRun,
+        name: &str,
+        config: &ConnectorConfig,
+        initial_state: Option<Status>,
+    ) -> Result<ConnectorInfo> {
+        // a connector deleted earlier in the dry run is created again, as when recreating it
+        if !dry_run.deleted(name)
+            && self
+                .connector_names()
+                .await?
+                .iter()
+                .any(|known| known == name)
+        {
+            anyhow::bail!("Connector already exists: {}", name);
+        }
+        let config = self.validated(name, config).await?;
+        dry_run.record(Mutation::Create {
+            connector: name.to_string(),
+            initial_state,
+        });
+        Ok(ConnectorInfo::new(name, "", config, 0))
+    }
+
+    pub(crate) async fn preview_update(
+        &self,
+        dry_run: &DryRun,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        let live = if self
+            .connector_names()
+            .await?
+            .iter()
+            .any(|known| known == name)
+        {
+            Some(self.connector_config(name).await?)
+        } else {
+            None
+        };
+        let config = self.validated(name, config).await?;
+        dry_run.record(Mutation::UpdateConfig {
+            connector: name.to_string(),
+            created: live.is_none(),
+            changed_keys: changed_keys(&live.unwrap_or_default(), &config),
+        });
+        Ok(ConnectorInfo::new(name, "", config, 0))
+    }
+
+    pub(crate) async fn preview_patch(
+        &self,
+        dry_run: &DryRun,
+        name: &str,
+        patch: &HashMap<String, Option<String>>,
+    ) -> Result<ConnectorInfo> {
+        let live = self.connector_config(name).await?;
+        let mut patched = live.clone();
+        for (key, value) in patch {
+            match value {
+                Some(value) => {
+                    patched.set(key.clone(), value.clone());
+                }
+                None => {
+                    patched.remove(key);
+                }
+            }
+        }
+        let patched = self.validated(name, &patched).await?;
+        dry_run.record(Mutation::PatchConfig {
+            connector: name.to_string(),
+            changed_keys: changed_keys(&live, &patched),
+        });
+        Ok(ConnectorInfo::new(name, "", patched, 0))
+    }
+
+    /// Records the mutation of an existing connector, and of its task when given. Returns the
+    /// description of the mutation
+    pub(crate) async fn preview_on_connector(
+        &self,
+        dry_run: &DryRun,
+        connector: &str,
+        task: Option<u64>,
+        mutation: Mutation,
+    ) -> Result<String> {
+        let status = self.connector_status(connector).await?;
+        if let Some(task) = task {
+            if !status.tasks.iter().any(|known| known.id == task) {
+                anyhow::bail!("Task {} of connector {} does not exist", task, connector);
+            }
+        }
+        let description = mutation.to_string();
+        dry_run.record(mutation);
+        Ok(description)
+    }
+
+    pub(crate) async fn preview_logger(
+        &self,
+        dry_run: &DryRun,
+        logger: &str,
+        level: &str,
+        scope: LoggerScope,
+    ) -> Result<Vec<String>> {
+        dry_run.record(Mutation::SetLoggerLevel {
+            logger: logger.to_string(),
+            level: level.to_string(),
+            scope: scope.as_str().to_string(),
+        });
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,79 @@
+//! Errors callers may want to react to
+//! Every method returns an `anyhow::Error`, from which these can be recovered through `downcast_ref`
+use crate::config::ConfigDiff;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectError {
+    ConnectorNotFound(String),
+    PluginNotFound(String),
+    LoggerNotFound(String),
+    /// The cluster is rebalancing, the request may succeed later
+    Rebalancing,
+    /// The body of a response exceeded the limit set with `ConnectBuilder::max_response_size`
+    ResponseTooLarge {
+        limit: usize,
+    },
+    /// The cluster does not offer the operation
+    UnsupportedByCluster(String),
+    /// The configuration of the connector is not the one expected, the diff leading from the
+    /// expected configuration to the live one
+    ConfigConflict {
+        connector: String,
+        diff: ConfigDiff,
+    },
+    /// The class of a new connector is not installed on the cluster, or older than the minimum
+    /// version, see [`crate::plugins`]. Installed plugins are listed with their version
+    PluginMissing {
+        class: String,
+        min_version: Option<String>,
+        installed: Vec<String>,
+    },
+    /// The name was rejected before sending the request, see [`crate::naming`]
+    InvalidConnectorName {
+        name: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConnectorNotFound(name) => write!(f, "Connector {} does not exist", name),
+            Self::PluginNotFound(class) => write!(f, "Connector plugin {} does not exist", class),
+            Self::LoggerNotFound(name) => write!(f, "Logger {} does not exist", name),
+            Self::Rebalancing => write!(f, "A rebalance may be needed, forthcoming, or underway"),
+            Self::ResponseTooLarge { limit } => write!(
+                f,
+                "The response is larger than the limit of {} bytes",
+                limit
+            ),
+            Self::UnsupportedByCluster(reason) => write!(f, "{}", reason),
+            Self::PluginMissing {
+                class,
+                min_version,
+                installed,
+            } => {
+                match min_version {
+                    Some(min_version) => write!(
+                        f,
+                        "Connector plugin {} {} or later is not installed",
+                        class, min_version
+                    )?,
+                    None => write!(f, "Connector plugin {} is not installed", class)?,
+                }
+                write!(f, ", installed plugins: {}", installed.join(", "))
+            }
+            Self::InvalidConnectorName { name, reason } => {
+                write!(f, "Invalid connector name {:?}: {}", name, reason)
+            }
+            Self::ConfigConflict { connector, diff } => write!(
+                f,
+                "The configuration of connector {} changed since it was read:\n{}",
+                connector, diff
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConnectError {}
@@ -0,0 +1,172 @@
+//! Error handling and dead letter queue configuration
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use std::time::Duration;
+
+/// Whether a task fails on the first bad record, or skips it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorTolerance {
+    None,
+    All,
+}
+
+impl ErrorTolerance {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::All => "all",
+        }
+    }
+}
+
+/// Topic where failed records of a sink connector are sent
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeadLetterQueue {
+    pub topic: String,
+    pub replication_factor: u16,
+    /// Adds headers describing the failure to each record
+    pub context_headers: bool,
+}
+
+/// Settings of the `errors.*` family
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ErrorHandling {
+    pub tolerance: ErrorTolerance,
+    pub dead_letter_queue: Option<DeadLetterQueue>,
+    pub log_enable: bool,
+    /// Logs the failed records as well, which may leak sensitive data
+    pub log_include_messages: bool,
+    pub retry_timeout: Option<Duration>,
+    pub retry_delay_max: Option<Duration>,
+}
+
+impl ErrorHandling {
+    /// Tolerates every error and sends the failed records to the given topic,
+    /// with a replication factor of 3, context headers and logging enabled
+    pub fn dead_letter_queue(topic: impl Into<String>) -> Self {
+        Self {
+            tolerance: ErrorTolerance::All,
+            dead_letter_queue: Some(DeadLetterQueue {
+                topic: topic.into(),
+                replication_factor: 3,
+                context_headers: true,
+            }),
+            log_enable: true,
+            log_include_messages: false,
+            retry_timeout: None,
+            retry_delay_max: None,
+        }
+    }
+
+    /// Tolerates every error, only logging the failed records
+    pub fn log_and_skip() -> Self {
+        Self {
+            tolerance: ErrorTolerance::All,
+            dead_letter_queue: None,
+            log_enable: true,
+            log_include_messages: false,
+            retry_timeout: None,
+            retry_delay_max: None,
+        }
+    }
+
+    /// Fails the task on the first error, which is the kafka connect default
+    pub fn fail_fast() -> Self {
+        Self {
+            tolerance: ErrorTolerance::None,
+            dead_letter_queue: None,
+            log_enable: true,
+            log_include_messages: false,
+            retry_timeout: None,
+            retry_delay_max: None,
+        }
+    }
+
+    pub fn replication_factor(mut self, replication_factor: u16) -> Self {
+        if let Some(dlq) = self.dead_letter_queue.as_mut() {
+            dlq.replication_factor = replication_factor;
+        }
+        self
+    }
+
+    pub fn log_include_messages(mut self, include: bool) -> Self {
+        self.log_include_messages = include;
+        self
+    }
+
+    /// Retries failed operations for up to `timeout`, waiting at most `delay_max` between attempts
+    pub fn retry(mut self, timeout: Duration, delay_max: Duration) -> Self {
+        self.retry_timeout = Some(timeout);
+        self.retry_delay_max = Some(delay_max);
+        self
+    }
+
+    pub fn apply_to(&self, config: &mut ConnectorConfig) {
+        config
+            .set(config_keys::ERRORS_TOLERANCE, self.tolerance.as_str())
+            .set_bool(config_keys::ERRORS_LOG_ENABLE, self.log_enable)
+            .set_bool(
+                config_keys::ERRORS_LOG_INCLUDE_MESSAGES,
+                self.log_include_messages,
+            );
+        match &self.dead_letter_queue {
+            Some(dlq) => {
+                config
+                    .set(
+                        config_keys::ERRORS_DEADLETTERQUEUE_TOPIC_NAME,
+                        dlq.topic.as_str(),
+                    )
+                    .set(
+                        config_keys::ERRORS_DEADLETTERQUEUE_TOPIC_REPLICATION_FACTOR,
+                        dlq.replication_factor.to_string(),
+                    )
+                    .set_bool(
+                        config_keys::ERRORS_DEADLETTERQUEUE_CONTEXT_HEADERS_ENABLE,
+                        dlq.context_headers,
+                    );
+            }
+            None => {
+                config.remove(config_keys::ERRORS_DEADLETTERQUEUE_TOPIC_NAME);
+                config.remove(config_keys::ERRORS_DEADLETTERQUEUE_TOPIC_REPLICATION_FACTOR);
+                config.remove(config_keys::ERRORS_DEADLETTERQUEUE_CONTEXT_HEADERS_ENABLE);
+            }
+        }
+        if let Some(timeout) = self.retry_timeout {
+            config.set(
+                config_keys::ERRORS_RETRY_TIMEOUT,
+                timeout.as_millis().to_string(),
+            );
+        }
+        if let Some(delay_max) = self.retry_delay_max {
+            config.set(
+                config_keys::ERRORS_RETRY_DELAY_MAX_MS,
+                delay_max.as_millis().to_string(),
+            );
+        }
+    }
+}
+
+/// Warns when a sink connector has no error handling configured,
+/// meaning that a single bad record is enough to fail its tasks
+pub fn lint_error_handling(config: &ConnectorConfig) -> Option<String> {
+    if !config.is_sink() {
+        return None;
+    }
+    let tolerates_errors = config
+        .get_str(config_keys::ERRORS_TOLERANCE)
+        .is_some_and(|tolerance| tolerance.eq_ignore_ascii_case("all"));
+    let has_dlq = config
+        .get_str(config_keys::ERRORS_DEADLETTERQUEUE_TOPIC_NAME)
+        .is_some_and(|topic| !topic.is_empty());
+    match (tolerates_errors, has_dlq) {
+        (false, _) => Some(format!(
+            "Sink connector has {} unset or set to none, a single bad record will fail its tasks",
+            config_keys::ERRORS_TOLERANCE
+        )),
+        (true, false) => Some(format!(
+            "Sink connector tolerates errors without {}, failed records will be lost",
+            config_keys::ERRORS_DEADLETTERQUEUE_TOPIC_NAME
+        )),
+        (true, true) => None,
+    }
+}
@@ -0,0 +1,97 @@
+//! Watch events and audit records published to a Kafka topic
+//! Only available with the `rdkafka` feature
+//!
+//! Every record is a JSON object keyed by connector name, so that the changes of a connector stay
+//! ordered within a partition. The fields of the event are flattened next to `kind` and `at`,
+//! e.g. `{"kind":"watch","at":1700000000,"event":"state_changed","name":"sink",...}`
+use crate::watch::{WatchEvent, Watcher};
+use anyhow::Result;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    kind: &'a str,
+    /// Unix timestamp, in seconds
+    at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<&'a str>,
+    #[serde(flatten)]
+    record: &'a T,
+}
+
+/// Producer of the events of a cluster
+#[derive(Clone)]
+pub struct EventEmitter {
+    producer: FutureProducer,
+    topic: String,
+    source: Option<String>,
+    timeout: Duration,
+}
+
+impl EventEmitter {
+    pub fn new(bootstrap_servers: &str, topic: &str) -> Result<Self> {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", bootstrap_servers);
+        Self::from_config(&config, topic)
+    }
+
+    /// Producer configured with any librdkafka property, such as the security ones
+    pub fn from_config(config: &ClientConfig, topic: &str) -> Result<Self> {
+        Ok(Self {
+            producer: config.create()?,
+            topic: topic.to_string(),
+            source: None,
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    /// Name of the cluster added to every record, to tell clusters apart in a shared topic
+    pub fn source(mut self, source: &str) -> Self {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    /// How long records may wait for room in the queue of the producer, 30 seconds by default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Publishes any record, such as an audit entry, under the kind given
+    pub async fn emit<T: Serialize>(&self, kind: &str, key: &str, record: &T) -> Result<()> {
+        let at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let payload = serde_json::to_vec(&Envelope {
+            kind,
+            at,
+            source: self.source.as_deref(),
+            record,
+        })?;
+        let record = FutureRecord::to(&self.topic).key(key).payload(&payload);
+        self.producer
+            .send(record, self.timeout)
+            .await
+            .map_err(|(err, _)| err)?;
+        Ok(())
+    }
+
+    pub async fn emit_watch_event(&self, event: &WatchEvent) -> Result<()> {
+        self.emit("watch", event.connector(), event).await
+    }
+
+    /// Publishes the events of the watcher until polling or publishing fails. The first poll,
+    /// which reports every connector as added, is not published
+    pub async fn forward(&self, mut watcher: Watcher) -> Result<()> {
+        watcher.next().await?;
+        loop {
+            for event in watcher.next().await?.events {
+                self.emit_watch_event(&event).await?;
+            }
+        }
+    }
+}
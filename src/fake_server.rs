@@ -0,0 +1,613 @@
+//! In-memory implementation of the kafka-connect REST API, for end to end tests without a cluster
+//! Only available with the `fake_server` feature
+//!
+//! Requests change the state of the server the way they would change a cluster: created connectors
+//! show up in listings, paused connectors report paused tasks, and so on. Transitions are immediate
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::models::*;
+use crate::offsets::{ConnectorOffsets, OffsetsMessage};
+use crate::retry::RetryClassification;
+use crate::Connect;
+use anyhow::Result;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+const WORKER: &str = "fake-worker:8083";
+const VERSION: &str = "3.7.0";
+
+/// A connector as stored by the server
+#[derive(Clone, Debug)]
+pub struct FakeConnector {
+    pub config: ConnectorConfig,
+    pub state: Status,
+    pub tasks: Vec<Status>,
+    /// Trace reported by failed tasks, by task id
+    pub traces: HashMap<u64, String>,
+    pub offsets: ConnectorOffsets,
+}
+
+impl FakeConnector {
+    fn new(config: ConnectorConfig) -> Self {
+        let mut connector = Self {
+            config,
+            state: Status::Running,
+            tasks: Vec::new(),
+            traces: HashMap::new(),
+            offsets: ConnectorOffsets {
+                offsets: Vec::new(),
+            },
+        };
+        connector.start_tasks(Status::Running);
+        connector
+    }
+
+    fn start_tasks(&mut self, state: Status) {
+        let count = self
+            .config
+            .get_i64(config_keys::TASKS_MAX)
+            .ok()
+            .flatten()
+            .unwrap_or(1)
+            .max(1);
+        self.tasks = vec![state; count as usize];
+        self.traces.clear();
+    }
+
+    fn kind(&self) -> &'static str {
+        if self.config.is_sink() {
+            "sink"
+        } else {
+            "source"
+        }
+    }
+
+    fn info(&self, name: &str) -> ConnectorInfo {
+        ConnectorInfo {
+            name: name.to_string(),
+            config: self.config.clone(),
+            tasks: (0..self.tasks.len() as u64)
+                .map(|task| TaskInfo {
+                    connector: name.to_string(),
+                    task,
+                    extra: HashMap::new(),
+                })
+                .collect(),
+            kind: self.kind().to_string(),
+            extra: HashMap::new(),
+        }
+    }
+
+    fn status(&self, name: &str) -> ConnectorStatus {
+        ConnectorStatus {
+            connector: ConnectorState {
+                connector: None,
+                state: self.state,
+                worker_id: WORKER.to_string(),
+                trace: None,
+                extra: HashMap::new(),
+            },
+            name: name.to_string(),
+            tasks: self
+                .tasks
+                .iter()
+                .enumerate()
+                .map(|(id, state)| TaskStatus {
+                    id: id as u64,
+                    state: *state,
+                    worker_id: WORKER.to_string(),
+                    trace: self.traces.get(&(id as u64)).cloned(),
+                    extra: HashMap::new(),
+                })
+                .collect(),
+            kind: self.kind().to_string(),
+            extra: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    connectors: BTreeMap<String, FakeConnector>,
+    loggers: BTreeMap<String, String>,
+    plugins: Vec<PluginInfo>,
+}
+
+/// A kafka-connect server listening on a random local port, stopped when dropped
+pub struct FakeConnectServer {
+    address: String,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<()>,
+}
+
+impl FakeConnectServer {
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = format!("http://{}", listener.local_addr()?);
+        let mut state = State::default();
+        state.loggers.insert("root".to_string(), "INFO".to_string());
+        let state = Arc::new(Mutex::new(state));
+        let shared = state.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = shared.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |request| {
+                        let state = state.clone();
+                        async move { Ok::<_, Infallible>(handle(&state, request).await) }
+                    });
+                    // connections closed by the client are not an error of the server
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        Ok(Self {
+            address,
+            state,
+            task,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.address
+    }
+
+    /// A client pointing at the server, which does not retry so failures surface at once
+    pub fn client(&self) -> Connect {
+        Connect::builder(&self.address)
+            .retry_classification(RetryClassification::none())
+            .build()
+            .unwrap()
+    }
+
+    /// A copy of a connector, as currently stored
+    pub fn connector(&self, name: &str) -> Option<FakeConnector> {
+        self.state.lock().unwrap().connectors.get(name).cloned()
+    }
+
+    /// Adds a connector directly, bypassing the API
+    pub fn insert_connector(&self, name: &str, config: ConnectorConfig) {
+        let mut config = config;
+        config.set(config_keys::NAME, name);
+        self.state
+            .lock()
+            .unwrap()
+            .connectors
+            .insert(name.to_string(), FakeConnector::new(config));
+    }
+
+    /// Lists the plugin among the installed ones. Connectors are created whatever their class
+    pub fn install_plugin(&self, plugin: PluginInfo) {
+        self.state.lock().unwrap().plugins.push(plugin);
+    }
+
+    /// Fails a task of a connector with the given trace, as a crashing task would
+    pub fn fail_task(&self, connector: &str, task: u64, trace: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let Some(stored) = state.connectors.get_mut(connector) else {
+            anyhow::bail!("Connector {} does not exist", connector);
+        };
+        let Some(slot) = stored.tasks.get_mut(task as usize) else {
+            anyhow::bail!("Connector {} has no task {}", connector, task);
+        };
+        *slot = Status::Failed;
+        stored.traces.insert(task, trace.to_string());
+        Ok(())
+    }
+
+    /// Fails a connector itself
+    pub fn fail_connector(&self, connector: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let Some(stored) = state.connectors.get_mut(connector) else {
+            anyhow::bail!("Connector {} does not exist", connector);
+        };
+        stored.state = Status::Failed;
+        Ok(())
+    }
+}
+
+impl Drop for FakeConnectServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+type FakeResponse = Response<Full<Bytes>>;
+
+/// Body of the requests creating a connector
+#[derive(Deserialize)]
+struct CreateConnector {
+    name: String,
+    config: ConnectorConfig,
+    initial_state: Option<Status>,
+}
+
+#[derive(Deserialize)]
+struct SetLevel {
+    level: String,
+}
+
+async fn handle(state: &Mutex<State>, request: Request<Incoming>) -> FakeResponse {
+    let method = request.method().clone();
+    let path = request.uri().path().trim_matches('/').to_string();
+    let query: Vec<(String, String)> = request
+        .uri()
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let body = match request.into_body().collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+    };
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut state = state.lock().unwrap();
+    match (&method, segments.as_slice()) {
+        (&Method::GET, []) => json(
+            StatusCode::OK,
+            &ClusterInfo {
+                version: VERSION.to_string(),
+                commit: "fake".to_string(),
+                kafka_cluster_id: "fake-cluster".to_string(),
+                extra: HashMap::new(),
+            },
+        ),
+        (&Method::GET, ["connectors"]) => list(&state, &query),
+        (&Method::POST, ["connectors"]) => {
+            let request: CreateConnector = match parse(&body) {
+                Ok(request) => request,
+                Err(response) => return *response,
+            };
+            if state.connectors.contains_key(&request.name) {
+                return error(
+                    StatusCode::CONFLICT,
+                    &format!("Connector {} already exists", request.name),
+                );
+            }
+            create(
+                &mut state,
+                &request.name,
+                request.config,
+                request.initial_state,
+                StatusCode::CREATED,
+            )
+        }
+        (_, ["connectors", name, rest @ ..]) => {
+            connector_request(&mut state, &method, name, rest, &query, &body)
+        }
+        (&Method::GET, ["connector-plugins"]) => json(StatusCode::OK, &state.plugins),
+        (&Method::PUT, ["connector-plugins", class, "config", "validate"]) => json(
+            StatusCode::OK,
+            &ConfigInfos {
+                name: class.to_string(),
+                error_count: 0,
+                groups: Vec::new(),
+                configs: Vec::new(),
+            },
+        ),
+        (&Method::GET, ["admin", "loggers"]) => {
+            let loggers: BTreeMap<&String, LoggerLevel> = state
+                .loggers
+                .iter()
+                .map(|(name, level)| (name, logger_level(level)))
+                .collect();
+            json(StatusCode::OK, &loggers)
+        }
+        (&Method::GET, ["admin", "loggers", logger]) => match state.loggers.get(*logger) {
+            Some(level) => json(StatusCode::OK, &logger_level(level)),
+            None => error(
+                StatusCode::NOT_FOUND,
+                &format!("Logger {} not found", logger),
+            ),
+        },
+        (&Method::PUT, ["admin", "loggers", logger]) => {
+            let request: SetLevel = match parse(&body) {
+                Ok(request) => request,
+                Err(response) => return *response,
+            };
+            let prefix = format!("{}.", logger);
+            let mut affected: Vec<String> = state
+                .loggers
+                .keys()
+                .filter(|name| name.as_str() == *logger || name.starts_with(&prefix))
+                .cloned()
+                .collect();
+            if affected.is_empty() {
+                affected.push(logger.to_string());
+            }
+            for name in &affected {
+                state.loggers.insert(name.clone(), request.level.clone());
+            }
+            json(StatusCode::OK, &affected)
+        }
+        _ => error(StatusCode::NOT_FOUND, "Not found"),
+    }
+}
+
+fn connector_request(
+    state: &mut State,
+    method: &Method,
+    name: &str,
+    rest: &[&str],
+    query: &[(String, String)],
+    body: &Bytes,
+) -> FakeResponse {
+    // PUT on the configuration is the only request creating missing connectors
+    if let (&Method::PUT, ["config"]) = (method, rest) {
+        let config: ConnectorConfig = match parse(body) {
+            Ok(config) => config,
+            Err(response) => return *response,
+        };
+        let Some(stored) = state.connectors.get_mut(name) else {
+            return create(state, name, config, None, StatusCode::CREATED);
+        };
+        let mut config = config;
+        config.set(config_keys::NAME, name);
+        stored.config = config;
+        // the tasks are restarted in the state of the connector, a stopped one having none
+        match stored.state {
+            Status::Stopped => stored.tasks.clear(),
+            Status::Paused => stored.start_tasks(Status::Paused),
+            _ => stored.start_tasks(Status::Running),
+        }
+        return json(StatusCode::OK, &stored.info(name));
+    }
+    let Some(stored) = state.connectors.get_mut(name) else {
+        return error(
+            StatusCode::NOT_FOUND,
+            &format!("Connector {} not found", name),
+        );
+    };
+    match (method, rest) {
+        (&Method::GET, []) => json(StatusCode::OK, &stored.info(name)),
+        (&Method::DELETE, []) => {
+            state.connectors.remove(name);
+            empty(StatusCode::NO_CONTENT)
+        }
+        (&Method::GET, ["config"]) => json(StatusCode::OK, &stored.config),
+        (&Method::GET, ["status"]) => json(StatusCode::OK, &stored.status(name)),
+        (&Method::PUT, ["pause"]) => {
+            stored.state = Status::Paused;
+            stored
+                .tasks
+                .iter_mut()
+                .for_each(|task| *task = Status::Paused);
+            empty(StatusCode::ACCEPTED)
+        }
+        (&Method::PUT, ["resume"]) => {
+            if stored.state == Status::Stopped {
+                stored.start_tasks(Status::Running);
+            }
+            stored.state = Status::Running;
+            stored
+                .tasks
+                .iter_mut()
+                .for_each(|task| *task = Status::Running);
+            empty(StatusCode::ACCEPTED)
+        }
+        (&Method::PUT, ["stop"]) => {
+            stored.state = Status::Stopped;
+            stored.tasks.clear();
+            stored.traces.clear();
+            empty(StatusCode::NO_CONTENT)
+        }
+        (&Method::POST, ["restart"]) => {
+            let flag = |key: &str| query.iter().any(|(k, v)| k == key && v == "true");
+            let (include_tasks, only_failed) = (flag("includeTasks"), flag("onlyFailed"));
+            if !include_tasks && !only_failed {
+                stored.state = Status::Running;
+                return empty(StatusCode::NO_CONTENT);
+            }
+            // the response reports what is being restarted, the fake then completes the restart at once
+            let mut restarting = stored.status(name);
+            if !only_failed || stored.state == Status::Failed {
+                restarting.connector.state = Status::Restarting;
+                stored.state = Status::Running;
+            }
+            if include_tasks {
+                for (task, status) in restarting.tasks.iter_mut().enumerate() {
+                    if !only_failed || status.state == Status::Failed {
+                        status.state = Status::Restarting;
+                        status.trace = None;
+                        stored.tasks[task] = Status::Running;
+                        stored.traces.remove(&(task as u64));
+                    }
+                }
+            }
+            json(StatusCode::ACCEPTED, &restarting)
+        }
+        (&Method::GET, ["offsets"]) => json(StatusCode::OK, &stored.offsets),
+        (&Method::PATCH, ["offsets"]) | (&Method::DELETE, ["offsets"]) => {
+            if stored.state != Status::Stopped {
+                return error(
+                    StatusCode::BAD_REQUEST,
+                    &format!("Connector {} must be stopped to modify its offsets", name),
+                );
+            }
+            if method == Method::DELETE {
+                stored.offsets.offsets.clear();
+                return json(
+                    StatusCode::OK,
+                    &OffsetsMessage {
+                        message: "The offsets for this connector have been reset successfully"
+                            .to_string(),
+                    },
+                );
+            }
+            let altered: ConnectorOffsets = match parse(body) {
+                Ok(offsets) => offsets,
+                Err(response) => return *response,
+            };
+            for offset in altered.offsets {
+                stored
+                    .offsets
+                    .offsets
+                    .retain(|existing| existing.partition != offset.partition);
+                if offset.offset.is_some() {
+                    stored.offsets.offsets.push(offset);
+                }
+            }
+            json(
+                StatusCode::OK,
+                &OffsetsMessage {
+                    message: "The offsets for this connector have been altered successfully"
+                        .to_string(),
+                },
+            )
+        }
+        _ => error(StatusCode::NOT_FOUND, "Not found"),
+    }
+}
+
+fn list(state: &State, query: &[(String, String)]) -> FakeResponse {
+    let expanded: Vec<&str> = query
+        .iter()
+        .filter(|(key, _)| key == "expand")
+        .map(|(_, value)| value.as_str())
+        .collect();
+    if expanded.is_empty() {
+        let names: Vec<&String> = state.connectors.keys().collect();
+        return json(StatusCode::OK, &names);
+    }
+    let connectors: BTreeMap<&String, Connector> = state
+        .connectors
+        .iter()
+        .map(|(name, stored)| {
+            let connector = Connector {
+                info: expanded.contains(&"info").then(|| stored.info(name)),
+                status: expanded.contains(&"status").then(|| stored.status(name)),
+            };
+            (name, connector)
+        })
+        .collect();
+    json(StatusCode::OK, &connectors)
+}
+
+fn create(
+    state: &mut State,
+    name: &str,
+    mut config: ConnectorConfig,
+    initial_state: Option<Status>,
+    status: StatusCode,
+) -> FakeResponse {
+    if config.get_str(config_keys::CONNECTOR_CLASS).is_none() {
+        return error(
+            StatusCode::BAD_REQUEST,
+            &format!(
+                "Missing required configuration \"{}\"",
+                config_keys::CONNECTOR_CLASS
+            ),
+        );
+    }
+    config.set(config_keys::NAME, name);
+    let mut connector = FakeConnector::new(config);
+    match initial_state {
+        Some(Status::Stopped) => {
+            connector.state = Status::Stopped;
+            connector.tasks.clear();
+        }
+        Some(Status::Paused) => {
+            connector.state = Status::Paused;
+            connector.start_tasks(Status::Paused);
+        }
+        _ => {}
+    }
+    let info = connector.info(name);
+    state.connectors.insert(name.to_string(), connector);
+    json(status, &info)
+}
+
+fn logger_level(level: &str) -> LoggerLevel {
+    LoggerLevel {
+        level: level.to_string(),
+        last_modified: None,
+        extra: HashMap::new(),
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &Bytes) -> Result<T, Box<FakeResponse>> {
+    serde_json::from_slice(body)
+        .map_err(|e| Box::new(error(StatusCode::BAD_REQUEST, &e.to_string())))
+}
+
+fn json<T: Serialize + ?Sized>(status: StatusCode, body: &T) -> FakeResponse {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+fn empty(status: StatusCode) -> FakeResponse {
+    let mut response = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = status;
+    response
+}
+
+fn error(status: StatusCode, message: &str) -> FakeResponse {
+    json(
+        status,
+        &ErrorResponse {
+            error_code: status.as_u16(),
+            message: message.to_string(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(tasks: &str) -> ConnectorConfig {
+        let mut config = ConnectorConfig::default();
+        config.set(config_keys::CONNECTOR_CLASS, "FileStreamSource");
+        config.set(config_keys::TASKS_MAX, tasks);
+        config
+    }
+
+    #[tokio::test]
+    async fn config_update_keeps_a_stopped_connector_without_tasks() {
+        let server = FakeConnectServer::start().await.unwrap();
+        let client = server.client();
+        server.insert_connector("source", config("1"));
+        client.stop_connector("source").await.unwrap();
+        client
+            .update_connector_config("source", &config("3"))
+            .await
+            .unwrap();
+        let stored = server.connector("source").unwrap();
+        assert_eq!(stored.state, Status::Stopped);
+        assert!(stored.tasks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn config_update_restarts_the_tasks_in_the_connector_state() {
+        let server = FakeConnectServer::start().await.unwrap();
+        let client = server.client();
+        server.insert_connector("source", config("1"));
+        client.pause_connector("source").await.unwrap();
+        client
+            .update_connector_config("source", &config("2"))
+            .await
+            .unwrap();
+        let stored = server.connector("source").unwrap();
+        assert_eq!(stored.tasks, vec![Status::Paused; 2]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Selection of connectors by name, type, state, worker and configuration
+//! Every condition of a filter must hold. Conditions on the state and the worker need the
+//! statuses, conditions on the configuration need the info, which [`ConnectorFilter::expand`]
+//! tells to request
+//!
+//! Labels, the keys under [`config_keys::LABEL_PREFIX`], can be selected the way kubectl does,
+//! e.g. `team=payments,env!=prod,owner,!deprecated`
+use crate::config_keys;
+use crate::models::{Connector, Status};
+use crate::Connect;
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+enum ConfigPredicate {
+    Present(String),
+    Absent(String),
+    Equals(String, String),
+    NotEquals(String, String),
+    Matches(String, Regex),
+}
+
+impl ConfigPredicate {
+    fn holds(&self, config: &HashMap<String, String>) -> bool {
+        match self {
+            Self::Present(key) => config.contains_key(key),
+            Self::Absent(key) => !config.contains_key(key),
+            Self::Equals(key, value) => config.get(key) == Some(value),
+            Self::NotEquals(key, value) => config.get(key) != Some(value),
+            Self::Matches(key, regex) => config.get(key).is_some_and(|value| regex.is_match(value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ConnectorFilter {
+    name: Option<Regex>,
+    kind: Option<String>,
+    states: Vec<Status>,
+    worker: Option<String>,
+    config: Vec<ConfigPredicate>,
+}
+
+impl ConnectorFilter {
+    /// Filter selecting every connector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connectors whose name matches the regex
+    pub fn name(mut self, regex: Regex) -> Self {
+        self.name = Some(regex);
+        self
+    }
+
+    pub fn name_regex(self, regex: &str) -> Result<Self> {
+        Ok(self.name(Regex::new(regex)?))
+    }
+
+    /// Connectors of the type, `source` or `sink`
+    pub fn kind(mut self, kind: &str) -> Self {
+        self.kind = Some(kind.to_ascii_lowercase());
+        self
+    }
+
+    /// Connectors in the state. Calling it again accepts any of the states given
+    pub fn state(mut self, state: Status) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Connectors running on the worker, either themselves or through one of their tasks
+    pub fn worker(mut self, worker_id: &str) -> Self {
+        self.worker = Some(worker_id.to_string());
+        self
+    }
+
+    pub fn has_config(mut self, key: &str) -> Self {
+        self.config.push(ConfigPredicate::Present(key.to_string()));
+        self
+    }
+
+    pub fn config_eq(mut self, key: &str, value: &str) -> Self {
+        self.config
+            .push(ConfigPredicate::Equals(key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn config_matches(mut self, key: &str, regex: Regex) -> Self {
+        self.config
+            .push(ConfigPredicate::Matches(key.to_string(), regex));
+        self
+    }
+
+    /// Connectors with the label set to the value
+    pub fn label(self, label: &str, value: &str) -> Self {
+        self.config_eq(&format!("{}{}", config_keys::LABEL_PREFIX, label), value)
+    }
+
+    /// Adds the requirements of a comma separated label selector: `label=value`, `label!=value`,
+    /// `label` for labels that are set and `!label` for labels that are not
+    pub fn label_selector(mut self, selector: &str) -> Result<Self> {
+        for requirement in selector.split(',').map(str::trim) {
+            let key = |label: &str| -> Result<String> {
+                let label = label.trim();
+                if label.is_empty() {
+                    anyhow::bail!("Invalid label selector {}", selector);
+                }
+                Ok(format!("{}{}", config_keys::LABEL_PREFIX, label))
+            };
+            let predicate = if let Some((label, value)) = requirement.split_once("!=") {
+                ConfigPredicate::NotEquals(key(label)?, value.trim().to_string())
+            } else if let Some((label, value)) = requirement.split_once('=') {
+                // kubectl accepts == as well
+                let value = value.strip_prefix('=').unwrap_or(value);
+                ConfigPredicate::Equals(key(label)?, value.trim().to_string())
+            } else if let Some(label) = requirement.strip_prefix('!') {
+                ConfigPredicate::Absent(key(label)?)
+            } else {
+                ConfigPredicate::Present(key(requirement)?)
+            };
+            self.config.push(predicate);
+        }
+        Ok(self)
+    }
+
+    /// Whether the filter needs the statuses and the info, in the order of [`Connect::connectors`]
+    pub fn expand(&self) -> (bool, bool) {
+        let needs_info = !self.config.is_empty();
+        let needs_status = !self.states.is_empty() || self.worker.is_some();
+        // one of them must be expanded, statuses are the cheaper
+        (needs_status || !needs_info, needs_info)
+    }
+
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.name.as_ref().is_none_or(|regex| regex.is_match(name))
+    }
+
+    pub fn matches(&self, name: &str, connector: &Connector) -> bool {
+        if !self.matches_name(name) {
+            return false;
+        }
+        if let Some(kind) = &self.kind {
+            let actual = connector
+                .status
+                .as_ref()
+                .map(|status| &status.kind)
+                .or(connector.info.as_ref().map(|info| &info.kind));
+            if actual.is_none_or(|actual| !actual.eq_ignore_ascii_case(kind)) {
+                return false;
+            }
+        }
+        if !self.states.is_empty() || self.worker.is_some() {
+            let Some(status) = &connector.status else {
+                return false;
+            };
+            if !self.states.is_empty() && !self.states.contains(&status.connector.state) {
+                return false;
+            }
+            if let Some(worker) = &self.worker {
+                if !status.workers().contains(worker.as_str()) {
+                    return false;
+                }
+            }
+        }
+        if !self.config.is_empty() {
+            let Some(info) = &connector.info else {
+                return false;
+            };
+            if !self
+                .config
+                .iter()
+                .all(|predicate| predicate.holds(&info.config))
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Connect {
+    /// Connectors selected by the filter, expanded with what the filter needs
+    pub async fn connectors_filtered(
+        &self,
+        filter: &ConnectorFilter,
+    ) -> Result<HashMap<String, Connector>> {
+        let (expand_status, expand_info) = filter.expand();
+        let mut connectors = self.connectors(expand_status, expand_info).await?;
+        connectors.retain(|name, connector| filter.matches(name, connector));
+        Ok(connectors)
+    }
+}
@@ -0,0 +1,186 @@
+//! Reading and writing manifests and snapshots as JSON, YAML or TOML
+//! YAML is available with the `yaml` feature and TOML with the `toml` feature. The format of a
+//! file is told by its extension.
+//!
+//! A manifest file holds one or several connectors:
+//! - in JSON, a spec or an array of specs
+//! - in YAML, one spec per document, documents being separated by `---`
+//! - in TOML, a spec at the top level, or several in a `[[connectors]]` array
+use crate::apply::ConnectorSpec;
+use crate::snapshot::ClusterSnapshot;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    Json,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl Format {
+    /// Extensions of the files in the formats enabled, lowercase
+    pub fn extensions() -> &'static [&'static str] {
+        &[
+            "json",
+            #[cfg(feature = "yaml")]
+            "yaml",
+            #[cfg(feature = "yaml")]
+            "yml",
+            #[cfg(feature = "toml")]
+            "toml",
+        ]
+    }
+
+    /// Format of the file according to its extension
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("json") => Ok(Self::Json),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            #[cfg(feature = "toml")]
+            Some("toml") => Ok(Self::Toml),
+            _ => anyhow::bail!(
+                "Unsupported format of {}, expected one of {}",
+                path.display(),
+                Self::extensions().join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => write!(f, "yaml"),
+            #[cfg(feature = "toml")]
+            Self::Toml => write!(f, "toml"),
+        }
+    }
+}
+
+/// Several specs in a TOML file
+#[cfg(feature = "toml")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Connectors {
+    connectors: Vec<ConnectorSpec>,
+}
+
+/// Specs of a manifest, in the order they are declared
+pub fn parse_manifests(content: &str, format: Format) -> Result<Vec<ConnectorSpec>> {
+    match format {
+        Format::Json => {
+            let value: serde_json::Value = serde_json::from_str(content)?;
+            if value.is_array() {
+                Ok(serde_json::from_value(value)?)
+            } else {
+                Ok(vec![serde_json::from_value(value)?])
+            }
+        }
+        #[cfg(feature = "yaml")]
+        Format::Yaml => {
+            use serde::Deserialize;
+            let mut specs = Vec::new();
+            for (index, document) in serde_yaml::Deserializer::from_str(content).enumerate() {
+                let value = serde_yaml::Value::deserialize(document)?;
+                // empty documents, such as after a trailing `---`, are skipped
+                if value.is_null() {
+                    continue;
+                }
+                let spec = serde_yaml::from_value(value)
+                    .with_context(|| format!("Invalid document {}", index + 1))?;
+                specs.push(spec);
+            }
+            Ok(specs)
+        }
+        #[cfg(feature = "toml")]
+        Format::Toml => {
+            let table: toml::Table = toml::from_str(content)?;
+            if table.contains_key("connectors") {
+                Ok(table.try_into::<Connectors>()?.connectors)
+            } else {
+                Ok(vec![table.try_into()?])
+            }
+        }
+    }
+}
+
+/// Writes the specs as a manifest, a single spec being written on its own
+pub fn manifests_to_string(specs: &[ConnectorSpec], format: Format) -> Result<String> {
+    match (format, specs) {
+        (Format::Json, [spec]) => Ok(serde_json::to_string_pretty(spec)?),
+        (Format::Json, specs) => Ok(serde_json::to_string_pretty(specs)?),
+        #[cfg(feature = "yaml")]
+        (Format::Yaml, specs) => {
+            let documents = specs
+                .iter()
+                .map(serde_yaml::to_string)
+                .collect::<std::result::Result<Vec<String>, _>>()?;
+            Ok(documents.join("---\n"))
+        }
+        #[cfg(feature = "toml")]
+        (Format::Toml, [spec]) => Ok(toml::to_string(spec)?),
+        #[cfg(feature = "toml")]
+        (Format::Toml, specs) => Ok(toml::to_string(&Connectors {
+            connectors: specs.to_vec(),
+        })?),
+    }
+}
+
+/// Specs of a manifest file, in the format of its extension
+pub fn load_manifests(path: &Path) -> Result<Vec<ConnectorSpec>> {
+    let format = Format::from_path(path)?;
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read manifest {}", path.display()))?;
+    parse_manifests(&content, format)
+        .with_context(|| format!("Invalid manifest {}", path.display()))
+}
+
+pub fn save_manifests(path: &Path, specs: &[ConnectorSpec]) -> Result<()> {
+    let content = manifests_to_string(specs, Format::from_path(path)?)?;
+    std::fs::write(path, content)
+        .with_context(|| format!("Could not write manifest {}", path.display()))
+}
+
+pub fn parse_snapshot(content: &str, format: Format) -> Result<ClusterSnapshot> {
+    match format {
+        Format::Json => Ok(serde_json::from_str(content)?),
+        #[cfg(feature = "yaml")]
+        Format::Yaml => Ok(serde_yaml::from_str(content)?),
+        #[cfg(feature = "toml")]
+        Format::Toml => Ok(toml::from_str(content)?),
+    }
+}
+
+pub fn snapshot_to_string(snapshot: &ClusterSnapshot, format: Format) -> Result<String> {
+    match format {
+        Format::Json => Ok(serde_json::to_string_pretty(snapshot)?),
+        #[cfg(feature = "yaml")]
+        Format::Yaml => Ok(serde_yaml::to_string(snapshot)?),
+        #[cfg(feature = "toml")]
+        Format::Toml => Ok(toml::to_string(snapshot)?),
+    }
+}
+
+/// Snapshot of a file, in the format of its extension
+pub fn load_snapshot(path: &Path) -> Result<ClusterSnapshot> {
+    let format = Format::from_path(path)?;
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read snapshot {}", path.display()))?;
+    parse_snapshot(&content, format).with_context(|| format!("Invalid snapshot {}", path.display()))
+}
+
+pub fn save_snapshot(path: &Path, snapshot: &ClusterSnapshot) -> Result<()> {
+    let content = snapshot_to_string(snapshot, Format::from_path(path)?)?;
+    std::fs::write(path, content)
+        .with_context(|| format!("Could not write snapshot {}", path.display()))
+}
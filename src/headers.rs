@@ -0,0 +1,40 @@
+//! Extra headers on the requests of a scoped client
+//! Some REST extensions expect headers the client does not know about, such as the user to
+//! impersonate or the id of a tenant. [`Connect::with_headers`] returns a client sending them with
+//! each of its requests, while the original client keeps sending none
+use crate::Connect;
+use anyhow::Result;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, RequestBuilder, RequestInitialiser};
+
+struct ExtraHeaders(HeaderMap);
+
+impl RequestInitialiser for ExtraHeaders {
+    fn init(&self, req: RequestBuilder) -> RequestBuilder {
+        req.headers(self.0.clone())
+    }
+}
+
+fn with_headers(client: &ClientWithMiddleware, headers: &HeaderMap) -> ClientWithMiddleware {
+    ClientBuilder::from_client(client.clone())
+        .with_init(ExtraHeaders(headers.clone()))
+        .build()
+}
+
+impl Connect {
+    /// Same client sending the headers with every request, replacing those of the same name
+    pub fn with_headers(&self, headers: HeaderMap) -> Connect {
+        Connect {
+            client: with_headers(&self.client, &headers),
+            probe: with_headers(&self.probe, &headers),
+            ..self.clone()
+        }
+    }
+
+    /// Same client sending the header with every request
+    pub fn with_header(&self, name: &str, value: &str) -> Result<Connect> {
+        let mut headers = HeaderMap::new();
+        headers.insert(HeaderName::try_from(name)?, HeaderValue::try_from(value)?);
+        Ok(self.with_headers(headers))
+    }
+}
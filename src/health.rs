@@ -0,0 +1,53 @@
+//! Aggregated health of a kafka-connect cluster
+use crate::models::{ConnectorStatus, Status};
+use serde::Serialize;
+
+/// Summary of the health across many connectors
+/// It can be collected from an iterator of statuses
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ClusterHealth {
+    pub total: usize,
+    pub healthy: usize,
+    pub running_tasks: usize,
+    pub failed_tasks: usize,
+    /// Connectors with at least one failure, either on the connector itself or on its tasks
+    pub failed_connectors: Vec<String>,
+    pub paused_connectors: Vec<String>,
+}
+
+impl ClusterHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, status: &ConnectorStatus) -> &mut Self {
+        self.total += 1;
+        if status.is_healthy() {
+            self.healthy += 1;
+        }
+        self.running_tasks += status.running_task_count();
+        let failed_tasks = status.failed_tasks().len();
+        self.failed_tasks += failed_tasks;
+        if status.connector.state == Status::Failed || failed_tasks > 0 {
+            self.failed_connectors.push(status.name.clone());
+        }
+        if status.connector.state == Status::Paused {
+            self.paused_connectors.push(status.name.clone());
+        }
+        self
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy == self.total
+    }
+}
+
+impl<'a> FromIterator<&'a ConnectorStatus> for ClusterHealth {
+    fn from_iter<T: IntoIterator<Item = &'a ConnectorStatus>>(iter: T) -> Self {
+        let mut health = Self::new();
+        for status in iter {
+            health.add(status);
+        }
+        health
+    }
+}
@@ -0,0 +1,196 @@
+//! HTTP server exposing the health of connectors to liveness and readiness probes
+//! Only available with the `health_server` feature
+//!
+//! `/healthz` answers 200 as long as the last poll of the cluster succeeded, and `/readyz` answers 200
+//! when every watched connector and all of its tasks are running. Both return a json summary
+use crate::health::ClusterHealth;
+use crate::Connect;
+use anyhow::Result;
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+const MIN_POLL_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug)]
+pub struct HealthServerOptions {
+    /// Connectors the readiness depends on. Every connector of the cluster when None
+    pub connectors: Option<Vec<String>>,
+    /// Time between two polls of the cluster
+    pub interval: Duration,
+}
+
+impl Default for HealthServerOptions {
+    fn default() -> Self {
+        Self {
+            connectors: None,
+            interval: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Outcome of the last poll, as returned by both endpoints
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct HealthReport {
+    /// Unix timestamp in seconds of the last poll, None before the first one completes
+    pub checked_at: Option<u64>,
+    pub health: ClusterHealth,
+    /// Watched connectors that do not exist on the cluster
+    pub missing_connectors: Vec<String>,
+    /// Error of the last poll, if it failed
+    pub error: Option<String>,
+}
+
+impl HealthReport {
+    pub fn is_live(&self) -> bool {
+        self.checked_at.is_some() && self.error.is_none()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.is_live() && self.health.is_healthy() && self.missing_connectors.is_empty()
+    }
+}
+
+/// Serves the probes until dropped
+pub struct HealthServer {
+    local_addr: SocketAddr,
+    report: Arc<RwLock<HealthReport>>,
+    poller: JoinHandle<()>,
+    server: JoinHandle<()>,
+}
+
+impl HealthServer {
+    pub async fn start(
+        client: Connect,
+        address: SocketAddr,
+        options: HealthServerOptions,
+    ) -> Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        let local_addr = listener.local_addr()?;
+        let report = Arc::new(RwLock::new(HealthReport::default()));
+        let poller = tokio::spawn(poll(client, options, report.clone()));
+        let shared = report.clone();
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let report = shared.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |request: Request<Incoming>| {
+                        let report = report.read().unwrap().clone();
+                        async move { Ok::<_, Infallible>(respond(request.uri().path(), &report)) }
+                    });
+                    // connections closed by the prober are not an error of the server
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        Ok(Self {
+            local_addr,
+            report,
+            poller,
+            server,
+        })
+    }
+
+    /// Address the server listens on, useful when binding port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Outcome of the last poll
+    pub fn report(&self) -> HealthReport {
+        self.report.read().unwrap().clone()
+    }
+}
+
+impl Drop for HealthServer {
+    fn drop(&mut self) {
+        self.poller.abort();
+        self.server.abort();
+    }
+}
+
+async fn poll(client: Connect, options: HealthServerOptions, report: Arc<RwLock<HealthReport>>) {
+    loop {
+        let checked_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .ok();
+        // the client retries transient errors for minutes, which would leave a stale report up meanwhile
+        let polled = tokio::time::timeout(
+            options.interval.max(MIN_POLL_TIMEOUT),
+            client.connectors(true, false),
+        )
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("The cluster did not answer in time")));
+        let next = match polled {
+            Ok(connectors) => {
+                let statuses: Vec<_> = connectors
+                    .into_values()
+                    .filter_map(|connector| connector.status)
+                    .filter(|status| {
+                        options
+                            .connectors
+                            .as_ref()
+                            .is_none_or(|watched| watched.contains(&status.name))
+                    })
+                    .collect();
+                let missing_connectors = options
+                    .connectors
+                    .iter()
+                    .flatten()
+                    .filter(|name| !statuses.iter().any(|status| &status.name == *name))
+                    .cloned()
+                    .collect();
+                HealthReport {
+                    checked_at,
+                    health: statuses.iter().collect(),
+                    missing_connectors,
+                    error: None,
+                }
+            }
+            Err(e) => HealthReport {
+                checked_at,
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+        };
+        *report.write().unwrap() = next;
+        tokio::time::sleep(options.interval).await;
+    }
+}
+
+fn respond(path: &str, report: &HealthReport) -> Response<Full<Bytes>> {
+    let ok = match path {
+        "/healthz" => report.is_live(),
+        "/readyz" => report.is_ready(),
+        _ => {
+            let mut response = Response::new(Full::new(Bytes::new()));
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            return response;
+        }
+    };
+    let body = serde_json::to_vec(report).unwrap_or_default();
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
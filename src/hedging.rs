@@ -0,0 +1,85 @@
+//! Hedged reads across the workers of a cluster
+//! Any worker answers the read endpoints, so when the first one is slow, the same GET is sent to another
+//! worker and the first successful response wins
+use async_trait::async_trait;
+use futures::future::{self, Either};
+use http::Extensions;
+use reqwest::{Method, Request, Response, Url};
+use reqwest_middleware::{Middleware, Next};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+pub(crate) struct Hedging {
+    address: String,
+    alternates: Vec<String>,
+    threshold: Duration,
+    next_alternate: AtomicUsize,
+}
+
+impl Hedging {
+    pub(crate) fn new(address: &str, alternates: Vec<String>, threshold: Duration) -> Self {
+        Self {
+            address: address.trim_end_matches('/').to_string(),
+            alternates: alternates
+                .into_iter()
+                .map(|alternate| alternate.trim_end_matches('/').to_string())
+                .collect(),
+            threshold,
+            next_alternate: AtomicUsize::new(0),
+        }
+    }
+
+    /// Same request sent to the next alternate worker, round robin
+    fn hedge_of(&self, req: &Request) -> Option<Request> {
+        if req.method() != Method::GET || self.alternates.is_empty() {
+            return None;
+        }
+        let path = req.url().as_str().strip_prefix(&self.address)?;
+        let index = self.next_alternate.fetch_add(1, Ordering::Relaxed) % self.alternates.len();
+        let url = Url::parse(&format!("{}{}", self.alternates[index], path)).ok()?;
+        let mut hedge = req.try_clone()?;
+        *hedge.url_mut() = url;
+        Some(hedge)
+    }
+}
+
+fn is_success(result: &reqwest_middleware::Result<Response>) -> bool {
+    result
+        .as_ref()
+        .is_ok_and(|response| !response.status().is_server_error())
+}
+
+#[async_trait]
+impl Middleware for Hedging {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let Some(hedge) = self.hedge_of(&req) else {
+            return next.run(req, extensions).await;
+        };
+        let mut primary = next.clone().run(req, extensions);
+        match future::select(primary, Box::pin(tokio::time::sleep(self.threshold))).await {
+            Either::Left((result, _)) => return result,
+            Either::Right((_, pending)) => primary = pending,
+        }
+        let mut hedge_extensions = Extensions::new();
+        let hedged = next.run(hedge, &mut hedge_extensions);
+        let (first, other) = match future::select(primary, hedged).await {
+            Either::Left((result, other)) => (result, other),
+            Either::Right((result, other)) => (result, other),
+        };
+        if is_success(&first) {
+            return first;
+        }
+        // the first answer failed, the other worker may still succeed
+        let second = other.await;
+        if is_success(&second) {
+            second
+        } else {
+            first
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Revisions of the configurations applied to connectors, to undo a bad deploy
+//! Every configuration applied through [`Connect::apply_with_history`] is recorded as a new
+//! revision of its connector, along with the configuration found before the first change. A
+//! rollback applies an earlier revision again, which records it as the latest one.
+//! [`MemoryHistory`] keeps the revisions in memory, other stores implement [`RevisionStore`]
+use crate::apply::{ApplyOutcome, ConnectorSpec};
+use crate::config::ConnectorConfig;
+use crate::normalize::Normalizer;
+use crate::Connect;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+    /// Number of the revision, increasing for each connector from 1
+    pub number: u64,
+    /// Unix timestamp, in seconds
+    pub recorded_at: u64,
+    pub config: ConnectorConfig,
+}
+
+/// Storage of the revisions of connectors
+#[async_trait]
+pub trait RevisionStore: Send + Sync {
+    /// Records the configuration as the latest revision of the connector, returning its number
+    async fn record(&self, connector: &str, config: &ConnectorConfig) -> Result<u64>;
+
+    /// Revisions of the connector kept by the store, oldest first
+    async fn revisions(&self, connector: &str) -> Result<Vec<Revision>>;
+}
+
+/// Revisions kept in memory, the oldest ones being dropped beyond a limit per connector. Clones
+/// share the same revisions
+#[derive(Clone, Debug)]
+pub struct MemoryHistory {
+    max_revisions: usize,
+    revisions: Arc<Mutex<HashMap<String, VecDeque<Revision>>>>,
+}
+
+impl MemoryHistory {
+    pub fn new(max_revisions: usize) -> Self {
+        Self {
+            max_revisions: max_revisions.max(1),
+            revisions: Arc::default(),
+        }
+    }
+}
+
+impl Default for MemoryHistory {
+    fn default() -> Self {
+        Self::new(20)
+    }
+}
+
+#[async_trait]
+impl RevisionStore for MemoryHistory {
+    async fn record(&self, connector: &str, config: &ConnectorConfig) -> Result<u64> {
+        let mut revisions = self.revisions.lock().unwrap();
+        let revisions = revisions.entry(connector.to_string()).or_default();
+        let number = revisions.back().map_or(1, |latest| latest.number + 1);
+        if revisions.len() == self.max_revisions {
+            revisions.pop_front();
+        }
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        revisions.push_back(Revision {
+            number,
+            recorded_at,
+            config: config.clone(),
+        });
+        Ok(number)
+    }
+
+    async fn revisions(&self, connector: &str) -> Result<Vec<Revision>> {
+        let revisions = self.revisions.lock().unwrap();
+        Ok(revisions
+            .get(connector)
+            .map(|revisions| revisions.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+impl Connect {
+    /// Same as [`Connect::apply`], recording the configuration applied in the history. A dry run
+    /// leaves the history as it is, as nothing is applied
+    pub async fn apply_with_history(
+        &self,
+        spec: &ConnectorSpec,
+        history: &dyn RevisionStore,
+    ) -> Result<ApplyOutcome> {
+        if self.dry_run.is_some() {
+            return self.apply(spec).await;
+        }
+        let names = self.connector_names().await?;
+        // the configuration before the first change is the one to go back to
+        if names.contains(&spec.name) && history.revisions(&spec.name).await?.is_empty() {
+            let live = self.connector_config(&spec.name).await?;
+            if !Normalizer::default().equivalent(&live, &spec.effective_config()) {
+                history.record(&spec.name, &live).await?;
+            }
+        }
+        let outcome = self
+            .apply_among(spec, &Normalizer::default(), &names)
+            .await?;
+        if outcome != ApplyOutcome::Unchanged {
+            history.record(&spec.name, &spec.effective_config()).await?;
+        }
+        Ok(outcome)
+    }
+
+    /// Applies the configuration of an earlier revision of the connector
+    pub async fn rollback(
+        &self,
+        connector: &str,
+        revision: u64,
+        history: &dyn RevisionStore,
+    ) -> Result<ApplyOutcome> {
+        let Some(revision) = history
+            .revisions(connector)
+            .await?
+            .into_iter()
+            .find(|kept| kept.number == revision)
+        else {
+            anyhow::bail!(
+                "Revision {} of connector {} is not in the history",
+                revision,
+                connector
+            );
+        };
+        let spec = ConnectorSpec::new(connector, revision.config);
+        self.apply_with_history(&spec, history).await
+    }
+}
@@ -0,0 +1,197 @@
+//! Task metrics read from the JMX beans of the workers through Jolokia
+//! Only available with the `jolokia` feature
+//!
+//! The REST API only reports states. Workers running the Jolokia agent expose the throughput and
+//! latency of every task they run, which are merged with the statuses into one view per connector
+use crate::models::{ConnectorStatus, Status};
+use crate::{json, Connect};
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+const SOURCE_TASK_METRICS: &str = "kafka.connect:type=source-task-metrics,connector=*,task=*";
+const SINK_TASK_METRICS: &str = "kafka.connect:type=sink-task-metrics,connector=*,task=*";
+
+/// Metrics of a task, left empty when the worker does not report them
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct TaskMetrics {
+    pub source_record_poll_rate: Option<f64>,
+    pub source_record_write_rate: Option<f64>,
+    pub poll_batch_avg_time_ms: Option<f64>,
+    pub sink_record_read_rate: Option<f64>,
+    pub sink_record_send_rate: Option<f64>,
+    pub put_batch_avg_time_ms: Option<f64>,
+    pub put_batch_max_time_ms: Option<f64>,
+}
+
+impl TaskMetrics {
+    fn set(&mut self, attribute: &str, value: &Value) {
+        let field = match attribute {
+            "source-record-poll-rate" => &mut self.source_record_poll_rate,
+            "source-record-write-rate" => &mut self.source_record_write_rate,
+            "poll-batch-avg-time-ms" => &mut self.poll_batch_avg_time_ms,
+            "sink-record-read-rate" => &mut self.sink_record_read_rate,
+            "sink-record-send-rate" => &mut self.sink_record_send_rate,
+            "put-batch-avg-time-ms" => &mut self.put_batch_avg_time_ms,
+            "put-batch-max-time-ms" => &mut self.put_batch_max_time_ms,
+            _ => return,
+        };
+        // beans report NaN, serialized as a string, before the first measurement
+        *field = value.as_f64();
+    }
+}
+
+/// Task of a connector, with its status and its metrics
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TaskView {
+    pub id: u64,
+    pub state: Status,
+    pub worker_id: String,
+    /// None when no Jolokia endpoint reports the task
+    pub metrics: Option<TaskMetrics>,
+}
+
+/// Status of a connector merged with the metrics of its tasks
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConnectorMetrics {
+    pub name: String,
+    pub state: Status,
+    pub tasks: Vec<TaskView>,
+}
+
+impl ConnectorMetrics {
+    pub fn new(status: &ConnectorStatus, metrics: &HashMap<(String, u64), TaskMetrics>) -> Self {
+        let tasks = status
+            .tasks
+            .iter()
+            .map(|task| TaskView {
+                id: task.id,
+                state: task.state,
+                worker_id: task.worker_id.clone(),
+                metrics: metrics.get(&(status.name.clone(), task.id)).cloned(),
+            })
+            .collect();
+        Self {
+            name: status.name.clone(),
+            state: status.connector.state,
+            tasks,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ReadResponse {
+    status: u16,
+    #[serde(default)]
+    value: HashMap<String, HashMap<String, Value>>,
+    error: Option<String>,
+}
+
+/// Client of the Jolokia agent of a worker
+#[derive(Clone, Debug)]
+pub struct Jolokia {
+    client: Client,
+    url: String,
+    basic_auth: Option<(String, String)>,
+    max_response_size: Option<usize>,
+}
+
+impl Jolokia {
+    /// The url of the agent, such as `http://worker:8778/jolokia`
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.trim_end_matches('/').to_string(),
+            basic_auth: None,
+            max_response_size: None,
+        }
+    }
+
+    pub fn basic_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.basic_auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Fails reads whose body exceeds the given number of bytes
+    pub fn max_response_size(mut self, bytes: usize) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    async fn read(&self, mbean: &str) -> Result<HashMap<String, HashMap<String, Value>>> {
+        let body = serde_json::json!({ "type": "read", "mbean": mbean });
+        let mut request = self.client.post(format!("{}/read", self.url)).json(&body);
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, Some(password));
+        }
+        let response = request.send().await?;
+        let status_code = response.status();
+        if !status_code.is_success() {
+            anyhow::bail!("Jolokia answered {} reading {}", status_code, mbean);
+        }
+        let response: ReadResponse = json::from_response(response, self.max_response_size).await?;
+        match response.status {
+            200 => Ok(response.value),
+            // no task of this kind runs on the worker
+            404 => Ok(HashMap::new()),
+            status => anyhow::bail!(
+                "Jolokia answered {} reading {}: {}",
+                status,
+                mbean,
+                response.error.unwrap_or_default()
+            ),
+        }
+    }
+
+    /// Metrics of the tasks running on the worker, by connector name and task id
+    pub async fn task_metrics(&self) -> Result<HashMap<(String, u64), TaskMetrics>> {
+        let mut metrics: HashMap<(String, u64), TaskMetrics> = HashMap::new();
+        for mbean in [SOURCE_TASK_METRICS, SINK_TASK_METRICS] {
+            for (name, attributes) in self.read(mbean).await? {
+                let Some(task) = task_of(&name) else {
+                    continue;
+                };
+                let entry = metrics.entry(task).or_default();
+                for (attribute, value) in &attributes {
+                    entry.set(attribute, value);
+                }
+            }
+        }
+        Ok(metrics)
+    }
+}
+
+/// Connector and task of a bean name such as
+/// `kafka.connect:connector=sink,task=0,type=sink-task-metrics`
+fn task_of(mbean: &str) -> Option<(String, u64)> {
+    let (_, properties) = mbean.split_once(':')?;
+    let properties: BTreeMap<&str, &str> = properties
+        .split(',')
+        .filter_map(|property| property.split_once('='))
+        .collect();
+    let connector = properties.get("connector")?;
+    let task = properties.get("task")?.parse().ok()?;
+    Some((connector.trim_matches('"').to_string(), task))
+}
+
+impl Connect {
+    /// Statuses of every connector, merged with the task metrics of the workers. Each worker
+    /// only reports the tasks it runs, so every worker should be given
+    pub async fn connector_metrics(&self, workers: &[Jolokia]) -> Result<Vec<ConnectorMetrics>> {
+        let mut metrics = HashMap::new();
+        for worker in workers {
+            metrics.extend(worker.task_metrics().await?);
+        }
+        let mut connectors: Vec<ConnectorMetrics> = self
+            .connectors(true, false)
+            .await?
+            .values()
+            .filter_map(|connector| connector.status.as_ref())
+            .map(|status| ConnectorMetrics::new(status, &metrics))
+            .collect();
+        connectors.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(connectors)
+    }
+}
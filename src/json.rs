@@ -0,0 +1,63 @@
+//! Parsing of response bodies. With the `simd-json` feature, the large bodies are parsed with simd-json
+use crate::error::ConnectError;
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+
+/// Parses a json body, which simd-json may modify in place
+#[cfg(feature = "simd-json")]
+pub(crate) fn from_slice<T: DeserializeOwned>(body: &mut [u8]) -> Result<T> {
+    Ok(simd_json::serde::from_slice(body)?)
+}
+
+/// Parses a json body, which simd-json may modify in place
+#[cfg(not(feature = "simd-json"))]
+pub(crate) fn from_slice<T: DeserializeOwned>(body: &mut [u8]) -> Result<T> {
+    Ok(serde_json::from_slice(body)?)
+}
+
+/// Reads and parses the body of a response
+pub(crate) async fn from_response<T: DeserializeOwned>(
+    response: reqwest::Response,
+    limit: Option<usize>,
+) -> Result<T> {
+    let mut body = read_body(response, limit).await?;
+    from_slice(&mut body)
+}
+
+/// Reads the body of a response, failing once it exceeds `limit` bytes
+pub(crate) async fn read_body(
+    mut response: reqwest::Response,
+    limit: Option<usize>,
+) -> Result<Vec<u8>> {
+    let Some(limit) = limit else {
+        return Ok(response.bytes().await?.to_vec());
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit as u64)
+    {
+        anyhow::bail!(ConnectError::ResponseTooLarge { limit });
+    }
+    // the length may be missing or wrong, so the limit is also enforced while reading
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            anyhow::bail!(ConnectError::ResponseTooLarge { limit });
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Reads up to `limit` bytes of the body, leaving out the rest, for messages that are only shown
+pub(crate) async fn read_capped(mut response: reqwest::Response, limit: usize) -> Vec<u8> {
+    let mut body = Vec::new();
+    while let Ok(Some(chunk)) = response.chunk().await {
+        let kept = chunk.len().min(limit - body.len());
+        body.extend_from_slice(&chunk[..kept]);
+        if body.len() == limit {
+            break;
+        }
+    }
+    body
+}
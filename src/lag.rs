@@ -0,0 +1,169 @@
+//! Lag of sink connectors, read from their consumer groups
+//! Only available with the `rdkafka` feature
+//!
+//! The REST API reports a sink as running even when it falls behind. Each sink commits to the
+//! `connect-<name>` consumer group, unless `consumer.override.group.id` is set, so its lag is the
+//! distance between the committed offsets of the group and the end of every partition it reads
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::Connect;
+use anyhow::Result;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+use regex::Regex;
+use serde::Serialize;
+use std::time::Duration;
+
+const GROUP_ID_OVERRIDE: &str = "consumer.override.group.id";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PartitionLag {
+    pub topic: String,
+    pub partition: i32,
+    /// None when the group never committed on the partition
+    pub committed: Option<i64>,
+    pub end_offset: i64,
+    /// Records left to consume. For partitions without commits, every record still retained
+    pub lag: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SinkLag {
+    pub connector: String,
+    pub group: String,
+    pub partitions: Vec<PartitionLag>,
+}
+
+impl SinkLag {
+    pub fn total(&self) -> i64 {
+        self.partitions.iter().map(|partition| partition.lag).sum()
+    }
+
+    pub fn max(&self) -> i64 {
+        self.partitions
+            .iter()
+            .map(|partition| partition.lag)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The lag of every partition in the Prometheus text format, for exporters to serve
+    pub fn prometheus_lines(&self) -> Vec<String> {
+        self.partitions
+            .iter()
+            .map(|partition| {
+                format!(
+                    "kafka_connect_sink_lag{{connector=\"{}\",group=\"{}\",topic=\"{}\",partition=\"{}\"}} {}",
+                    self.connector, self.group, partition.topic, partition.partition, partition.lag
+                )
+            })
+            .collect()
+    }
+}
+
+/// Consumer group of a sink connector
+pub fn consumer_group(name: &str, config: &ConnectorConfig) -> String {
+    config
+        .get_str(GROUP_ID_OVERRIDE)
+        .map_or_else(|| format!("connect-{}", name), String::from)
+}
+
+/// Reads the lag of sink connectors from Kafka
+#[derive(Clone)]
+pub struct LagMonitor {
+    config: ClientConfig,
+    timeout: Duration,
+}
+
+impl LagMonitor {
+    pub fn new(bootstrap_servers: &str) -> Self {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", bootstrap_servers);
+        Self::from_config(config)
+    }
+
+    /// Monitor configured with any librdkafka property, such as the security ones
+    pub fn from_config(config: ClientConfig) -> Self {
+        Self {
+            config,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Timeout of the requests to the brokers, 30 seconds by default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Lag of a sink connector on every partition of the topics it reads
+    pub async fn sink_lag(&self, client: &Connect, connector: &str) -> Result<SinkLag> {
+        let config = client.connector_config(connector).await?;
+        let group = consumer_group(connector, &config);
+        let mut consumer_config = self.config.clone();
+        consumer_config
+            .set("group.id", &group)
+            .set("enable.auto.commit", "false");
+        let timeout = self.timeout;
+        let topics = config.get_str(config_keys::TOPICS).map(String::from);
+        let pattern = config.get_str(config_keys::TOPICS_REGEX).map(String::from);
+        // librdkafka only reads offsets and watermarks synchronously
+        let partitions = tokio::task::spawn_blocking(move || {
+            let consumer: BaseConsumer = consumer_config.create()?;
+            partition_lags(&consumer, topics.as_deref(), pattern.as_deref(), timeout)
+        })
+        .await??;
+        Ok(SinkLag {
+            connector: connector.to_string(),
+            group,
+            partitions,
+        })
+    }
+}
+
+fn partition_lags(
+    consumer: &BaseConsumer,
+    topics: Option<&str>,
+    pattern: Option<&str>,
+    timeout: Duration,
+) -> Result<Vec<PartitionLag>> {
+    let listed: Vec<&str> = topics
+        .map(|topics| topics.split(',').map(str::trim).collect())
+        .unwrap_or_default();
+    let regex = pattern
+        .map(|pattern| Regex::new(&format!("^(?:{})$", pattern)))
+        .transpose()?;
+    let metadata = consumer.fetch_metadata(None, timeout)?;
+    let mut assignment = TopicPartitionList::new();
+    for topic in metadata.topics() {
+        let read = listed.contains(&topic.name())
+            || regex
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(topic.name()));
+        if !read {
+            continue;
+        }
+        for partition in topic.partitions() {
+            assignment.add_partition(topic.name(), partition.id());
+        }
+    }
+    let committed = consumer.committed_offsets(assignment, timeout)?;
+    let mut lags = Vec::new();
+    for element in committed.elements() {
+        let (low, high) =
+            consumer.fetch_watermarks(element.topic(), element.partition(), timeout)?;
+        let committed = match element.offset() {
+            Offset::Offset(offset) => Some(offset),
+            _ => None,
+        };
+        lags.push(PartitionLag {
+            topic: element.topic().to_string(),
+            partition: element.partition(),
+            committed,
+            end_offset: high,
+            lag: (high - committed.unwrap_or(low)).max(0),
+        });
+    }
+    lags.sort_by(|a, b| (&a.topic, a.partition).cmp(&(&b.topic, b.partition)));
+    Ok(lags)
+}
@@ -1,15 +1,108 @@
+pub mod activity;
+pub mod analyze;
+pub mod api;
+pub mod apply;
+#[cfg(feature = "backup")]
+pub mod backup;
+pub mod builder;
+pub mod bulk;
+pub mod cache;
+pub mod capabilities;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod cluster_set;
+pub mod coerce;
+pub mod config;
+pub mod config_keys;
+pub mod config_providers;
+#[cfg(feature = "confluent_cloud")]
+pub mod confluent_cloud;
+pub mod connectors;
+#[cfg(feature = "containers")]
+pub mod containers;
+pub mod converters;
+pub mod credentials;
+#[cfg(feature = "display")]
+pub mod display;
+pub mod dry_run;
+pub mod error;
+pub mod error_handling;
+#[cfg(feature = "rdkafka")]
+pub mod events;
+#[cfg(feature = "fake_server")]
+pub mod fake_server;
+pub mod filter;
+pub mod formats;
+pub mod headers;
+pub mod health;
+#[cfg(feature = "health_server")]
+pub mod health_server;
+mod hedging;
+pub mod history;
+#[cfg(feature = "jolokia")]
+pub mod jolokia;
+mod json;
+#[cfg(feature = "rdkafka")]
+pub mod lag;
+pub mod lint;
+pub mod listing;
+pub mod merge;
 pub mod models;
+#[cfg(feature = "msk_connect")]
+pub mod msk_connect;
+pub mod naming;
+pub mod normalize;
+pub mod offsets;
+pub mod ping;
+pub mod plugins;
+pub mod raw;
+pub mod read_only;
+pub mod rebalance;
+pub mod reconfigure;
+pub mod recreate;
+mod recycle;
+pub mod redact;
+pub mod redirect;
+pub mod restart_budget;
+pub mod retry;
+#[cfg(feature = "schemars")]
+pub mod schema;
+#[cfg(feature = "schema_registry")]
+pub mod schema_registry;
+pub mod snapshot;
+#[cfg(feature = "object_store")]
+pub mod snapshot_store;
+pub mod supervisor;
+#[cfg(feature = "test_support")]
+pub mod test_support;
+pub mod topic_usage;
+#[cfg(feature = "rdkafka")]
+pub mod topics;
+pub mod trace;
+pub mod transforms;
+#[cfg(feature = "vault")]
+pub mod vault;
+#[cfg(feature = "vcr")]
+pub mod vcr;
+pub mod wait;
+pub mod watch;
+pub mod wizard;
+pub mod workers;
+pub use api::ConnectApi;
+pub use builder::ConnectBuilder;
+use capabilities::{Capabilities, Feature};
+use config::ConnectorConfig;
+use error::ConnectError;
 use models::*;
+use offsets::{ConnectorOffsets, OffsetsMessage};
+use serde::{de::DeserializeOwned, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use anyhow::Result;
-use base64::{engine::general_purpose, Engine};
-use reqwest::{header, Client, StatusCode};
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::RetryTransientMiddleware;
-use retry_policies::policies::ExponentialBackoff;
-use retry_policies::Jitter;
-use std::time::Duration;
+use base64::engine::general_purpose;
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
 
 const ENGINE: general_purpose::GeneralPurpose = general_purpose::STANDARD;
 
@@ -17,43 +110,34 @@ const ENGINE: general_purpose::GeneralPurpose = general_purpose::STANDARD;
 #[derive(Debug, Clone)]
 pub struct Connect {
     client: ClientWithMiddleware,
+    /// Same client without retries, for probes that must fail fast
+    probe: ClientWithMiddleware,
     address: String,
+    max_response_size: Option<usize>,
+    capabilities: Arc<tokio::sync::OnceCell<Capabilities>>,
+    dry_run: Option<dry_run::DryRun>,
+    naming_policy: Option<Arc<dyn naming::NamingPolicy>>,
+    plugin_preflight: Option<plugins::PluginPreflight>,
+    activity: Option<activity::ActivityRecorder>,
 }
 
 impl Connect {
     pub fn new(address: &str, username: &str, password: Option<&str>) -> Self {
-        // set up the basic auth
-        let credentials = ENGINE.encode(format!("{}:{}", username, password.unwrap_or("")));
-        let basic_auth = format!("Basic {}", credentials);
-        let mut headers = header::HeaderMap::new();
-        let mut auth_value = header::HeaderValue::from_str(&basic_auth).unwrap();
-        auth_value.set_sensitive(true);
-        headers.insert(header::AUTHORIZATION, auth_value);
-        let client = Client::builder().default_headers(headers).build().unwrap();
-        let address = address.to_string();
-        // setup backoff
-        let policy = ExponentialBackoff::builder()
-            .retry_bounds(Duration::from_secs(1), Duration::from_secs(60))
-            .jitter(Jitter::Bounded)
-            .base(2)
-            .build_with_total_retry_duration(Duration::from_secs(600));
-        let retry_transient_middleware = RetryTransientMiddleware::new_with_policy(policy);
-        let client = ClientBuilder::new(client)
-            .with(retry_transient_middleware)
-            .build();
-        Self { client, address }
+        ConnectBuilder::new(address)
+            .basic_auth(username, password)
+            .build()
+            .unwrap()
+    }
+
+    /// Starts configuring a client, for settings not covered by `new` such as TLS
+    pub fn builder(address: &str) -> ConnectBuilder {
+        ConnectBuilder::new(address)
     }
 
     /// Returns info for a kafka-connect cluster
     pub async fn info(&self) -> Result<ClusterInfo> {
-        let response: ClusterInfo = self
-            .client
-            .get(format!("{}/", self.address))
-            .send()
-            .await?
-            .json()
-            .await?;
-        Ok(response)
+        let response = self.client.get(format!("{}/", self.address)).send().await?;
+        self.parse(response).await
     }
 
     /// Get a list of connector names
@@ -64,10 +148,8 @@ impl Connect {
             .client
             .get(format!("{}/connectors", self.address))
             .send()
-            .await?
-            .json()
             .await?;
-        Ok(response)
+        self.parse(response).await
     }
 
     pub async fn connectors(
@@ -75,6 +157,12 @@ impl Connect {
         expand_status: bool,
         expand_info: bool,
     ) -> Result<HashMap<String, Connector>> {
+        let endpoint = self.connectors_endpoint(expand_status, expand_info)?;
+        let response = self.client.get(endpoint).send().await?;
+        self.parse(response).await
+    }
+
+    fn connectors_endpoint(&self, expand_status: bool, expand_info: bool) -> Result<String> {
         let mut endpoint = format!("{}/connectors", self.address);
         // TODO: Perhaps replace this logic with the URL crate, if at all possible
         let expand = match (expand_status, expand_info) {
@@ -87,8 +175,210 @@ impl Connect {
             anyhow::bail!("You must expand either info, status, or both. If you'd rather use none of them, you may call the connector_names() method instead");
         }
         endpoint.push_str(expand);
-        let response = self.client.get(endpoint).send().await?.json().await?;
-        Ok(response)
+        Ok(endpoint)
+    }
+
+    /// Reads and parses the body of a response, within the size limit of the client
+    async fn parse<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<T> {
+        json::from_response(response, self.max_response_size).await
+    }
+
+    /// Returns the status of a connector and of its tasks
+    pub async fn connector_status(&self, connector: &str) -> Result<ConnectorStatus> {
+        self.connector_status_as(connector).await
+    }
+
+    async fn connector_status_as<T: DeserializeOwned>(&self, connector: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{}/connectors/{}/status", self.address, connector))
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::OK => self.parse(response).await,
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(connector.to_string()))
+            }
+            _ => anyhow::bail!("Unrecognizable error for status code {}", status_code),
+        }
+    }
+
+    /// Creates a new connector
+    pub async fn create_connector(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        self.create(name, config, None).await
+    }
+
+    /// Creates a new connector starting in the state, which is RUNNING, PAUSED or STOPPED
+    pub async fn create_connector_with_state(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+        initial_state: Status,
+    ) -> Result<ConnectorInfo> {
+        // older workers ignore the field and start the connector
+        self.gate(Feature::InitialState).await?;
+        self.create(name, config, Some(initial_state)).await
+    }
+
+    /// Whether the cluster lists the connector
+    async fn is_known(&self, name: &str) -> Result<bool> {
+        Ok(self
+            .connector_names()
+            .await?
+            .iter()
+            .any(|known| known == name))
+    }
+
+    async fn create(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+        initial_state: Option<Status>,
+    ) -> Result<ConnectorInfo> {
+        naming::check_new_name(name, self.naming_policy.as_deref())?;
+        self.preflight_plugin(config).await?;
+        if let Some(dry_run) = &self.dry_run {
+            return self
+                .preview_create(dry_run, name, config, initial_state)
+                .await;
+        }
+        let mut body = serde_json::json!({ "name": name, "config": config });
+        if let Some(initial_state) = initial_state {
+            body["initial_state"] = serde_json::to_value(initial_state)?;
+        }
+        let response = self
+            .client
+            .post(format!("{}/connectors", self.address))
+            .json(&body)
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::CREATED | StatusCode::OK => self.parse(response).await,
+            StatusCode::CONFLICT => anyhow::bail!(
+                "Connector already exists, or a rebalance is underway: {}",
+                error_message(response).await
+            ),
+            StatusCode::BAD_REQUEST => {
+                anyhow::bail!("Invalid configuration: {}", error_message(response).await)
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
+    }
+
+    /// Updates the configuration of a connector, creating it if it does not exist. With a naming
+    /// policy, a plugin preflight or a name that is not valid, the connectors are listed first to
+    /// check a new connector
+    pub async fn update_connector_config(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        // only new connectors are checked, existing ones can still be updated
+        if (self.naming_policy.is_some()
+            || self.plugin_preflight.is_some()
+            || naming::validate_name(name).is_err())
+            && !self.is_known(name).await?
+        {
+            naming::check_new_name(name, self.naming_policy.as_deref())?;
+            self.preflight_plugin(config).await?;
+        }
+        if let Some(dry_run) = &self.dry_run {
+            return self.preview_update(dry_run, name, config).await;
+        }
+        let response = self
+            .client
+            .put(format!("{}/connectors/{}/config", self.address, name))
+            .json(config)
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::CREATED | StatusCode::OK => self.parse(response).await,
+            StatusCode::CONFLICT => {
+                anyhow::bail!(ConnectError::Rebalancing)
+            }
+            StatusCode::BAD_REQUEST => {
+                anyhow::bail!("Invalid configuration: {}", error_message(response).await)
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
+    }
+
+    /// Updates the configuration of an existing connector only if it is still the one expected,
+    /// returning a [`ConnectError::ConfigConflict`] with the differences otherwise. Both are
+    /// compared normalized, with the name filled in as kafka-connect adds it. The check and the update
+    /// being separate requests, a change made in between is still overwritten
+    pub async fn update_config_if_unchanged(
+        &self,
+        name: &str,
+        expected_current: &ConnectorConfig,
+        new_config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        if !self.is_known(name).await? {
+            anyhow::bail!(ConnectError::ConnectorNotFound(name.to_string()));
+        }
+        let live = self.connector_config(name).await?;
+        let expected = apply::ConnectorSpec::new(name, expected_current.clone()).effective_config();
+        let diff = normalize::Normalizer::default().diff(&expected, &live);
+        if !diff.is_empty() {
+            anyhow::bail!(ConnectError::ConfigConflict {
+                connector: name.to_string(),
+                diff,
+            });
+        }
+        self.update_connector_config(name, new_config).await
+    }
+
+    /// Changes the given keys of the configuration of a connector, leaving the others as they are.
+    /// Keys without a value are removed
+    pub async fn patch_connector_config(
+        &self,
+        name: &str,
+        patch: &HashMap<String, Option<String>>,
+    ) -> Result<ConnectorInfo> {
+        if let Some(dry_run) = &self.dry_run {
+            return self.preview_patch(dry_run, name, patch).await;
+        }
+        let response = self
+            .client
+            .patch(format!("{}/connectors/{}/config", self.address, name))
+            .json(patch)
+            .send()
+            .await?;
+        let status_code = response.status();
+        self.check_endpoint(status_code, Feature::PatchConfig)
+            .await?;
+        match status_code {
+            StatusCode::OK => self.parse(response).await,
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(name.to_string()))
+            }
+            StatusCode::CONFLICT => {
+                anyhow::bail!(ConnectError::Rebalancing)
+            }
+            StatusCode::BAD_REQUEST => {
+                anyhow::bail!("Invalid configuration: {}", error_message(response).await)
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
     }
 
     pub async fn restart_connector(
@@ -97,6 +387,20 @@ impl Connect {
         include_tasks: bool,
         only_failed: bool,
     ) -> Result<Option<ConnectorStatus>> {
+        // older workers ignore the parameters and only restart the connector
+        if include_tasks || only_failed {
+            self.gate(Feature::RestartTasks).await?;
+        }
+        if let Some(dry_run) = &self.dry_run {
+            let mutation = dry_run::Mutation::Restart {
+                connector: name.to_string(),
+                include_tasks,
+                only_failed,
+            };
+            self.preview_on_connector(dry_run, name, None, mutation)
+                .await?;
+            return Ok(None);
+        }
         let response = self
             .client
             .post(format!(
@@ -108,10 +412,111 @@ impl Connect {
         let status_code = response.status();
         match status_code {
             StatusCode::NO_CONTENT | StatusCode::OK => Ok(None),
-            StatusCode::ACCEPTED => Ok(response.json().await?),
-            StatusCode::NOT_FOUND => anyhow::bail!("Connector does not exist"),
+            StatusCode::ACCEPTED => self.parse(response).await,
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(name.to_string()))
+            }
             StatusCode::CONFLICT => {
-                anyhow::bail!("A rebalance may be  needed, forthcoming, or underway")
+                anyhow::bail!(ConnectError::Rebalancing)
+            }
+            StatusCode::INTERNAL_SERVER_ERROR => {
+                anyhow::bail!("The request could not be processed.")
+            }
+            _ => anyhow::bail!("Unrecognizable error for status code {}", status_code),
+        }
+    }
+
+    /// Restarts a single task of a connector
+    pub async fn restart_task(&self, connector: &str, task: u64) -> Result<()> {
+        if let Some(dry_run) = &self.dry_run {
+            let mutation = dry_run::Mutation::RestartTask {
+                connector: connector.to_string(),
+                task,
+            };
+            self.preview_on_connector(dry_run, connector, Some(task), mutation)
+                .await?;
+            return Ok(());
+        }
+        let response = self
+            .client
+            .post(format!(
+                "{}/connectors/{}/tasks/{}/restart",
+                self.address, connector, task
+            ))
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::NO_CONTENT | StatusCode::OK => Ok(()),
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(connector.to_string()))
+            }
+            StatusCode::CONFLICT => {
+                anyhow::bail!(ConnectError::Rebalancing)
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
+    }
+
+    /// Pauses the connector and its tasks. The request is processed asynchronously
+    pub async fn pause_connector(&self, connector: &str) -> Result<()> {
+        self.change_state(connector, "pause").await
+    }
+
+    /// Resumes a paused or stopped connector. The request is processed asynchronously
+    pub async fn resume_connector(&self, connector: &str) -> Result<()> {
+        self.change_state(connector, "resume").await
+    }
+
+    /// Stops the connector and shuts down its tasks, without deleting its configuration
+    pub async fn stop_connector(&self, connector: &str) -> Result<()> {
+        self.change_state(connector, "stop").await
+    }
+
+    async fn change_state(&self, connector: &str, action: &str) -> Result<()> {
+        if let Some(dry_run) = &self.dry_run {
+            if action == "stop" {
+                self.gate(Feature::Stop).await?;
+            }
+            let connector_name = connector.to_string();
+            let mutation = match action {
+                "pause" => dry_run::Mutation::Pause {
+                    connector: connector_name,
+                },
+                "resume" => dry_run::Mutation::Resume {
+                    connector: connector_name,
+                },
+                _ => dry_run::Mutation::Stop {
+                    connector: connector_name,
+                },
+            };
+            self.preview_on_connector(dry_run, connector, None, mutation)
+                .await?;
+            return Ok(());
+        }
+        let response = self
+            .client
+            .put(format!(
+                "{}/connectors/{}/{}",
+                self.address, connector, action
+            ))
+            .send()
+            .await?;
+        let status_code = response.status();
+        if action == "stop" {
+            self.check_endpoint(status_code, Feature::Stop).await?;
+        }
+        match status_code {
+            StatusCode::ACCEPTED | StatusCode::NO_CONTENT | StatusCode::OK => Ok(()),
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(connector.to_string()))
+            }
+            StatusCode::CONFLICT => {
+                anyhow::bail!(ConnectError::Rebalancing)
             }
             StatusCode::INTERNAL_SERVER_ERROR => {
                 anyhow::bail!("The request could not be processed.")
@@ -121,6 +526,14 @@ impl Connect {
     }
 
     pub async fn delete_connector(&self, connector: &str) -> anyhow::Result<()> {
+        if let Some(dry_run) = &self.dry_run {
+            let mutation = dry_run::Mutation::Delete {
+                connector: connector.to_string(),
+            };
+            self.preview_on_connector(dry_run, connector, None, mutation)
+                .await?;
+            return Ok(());
+        }
         let response = self
             .client
             .delete(format!("{}/connectors/{}", self.address, connector))
@@ -129,24 +542,299 @@ impl Connect {
         let status_code = response.status();
         match status_code {
             StatusCode::NO_CONTENT | StatusCode::OK => Ok(()),
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(connector.to_string()))
+            }
             StatusCode::CONFLICT => {
-                anyhow::bail!("A rebalance may be  needed, forthcoming, or underway")
+                anyhow::bail!(ConnectError::Rebalancing)
             }
             _ => anyhow::bail!("Unrecognizable error"),
         }
     }
 
-    pub async fn connector_config(
+    pub async fn connector_config(&self, connector: &str) -> anyhow::Result<ConnectorConfig> {
+        self.connector_config_as(connector).await
+    }
+
+    /// Returns the configuration of a connector deserialized into the given struct. Every value
+    /// being a string, typed fields can use the deserializers of [`coerce`]
+    pub async fn connector_config_as<T: DeserializeOwned>(&self, connector: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{}/connectors/{}/config", self.address, connector))
+            .send()
+            .await?;
+        self.parse(response).await
+    }
+
+    /// Returns the offsets of a connector as plain json values
+    pub async fn connector_offsets(&self, connector: &str) -> Result<ConnectorOffsets> {
+        self.connector_offsets_as(connector).await
+    }
+
+    /// Returns the offsets of a connector, deserialized in the given partition and offset types
+    pub async fn connector_offsets_as<P, O>(
         &self,
         connector: &str,
-    ) -> anyhow::Result<HashMap<String, String>> {
-        let response: HashMap<String, String> = self
+    ) -> Result<ConnectorOffsets<P, O>>
+    where
+        P: DeserializeOwned,
+        O: DeserializeOwned,
+    {
+        let response = self.offsets_response(connector).await?;
+        self.parse(response).await
+    }
+
+    /// Successful response of the offsets endpoint
+    async fn offsets_response(&self, connector: &str) -> Result<reqwest::Response> {
+        let response = self
             .client
-            .get(format!("{}/connectors/{}/config", self.address, connector))
+            .get(format!("{}/connectors/{}/offsets", self.address, connector))
+            .send()
+            .await?;
+        let status_code = response.status();
+        self.check_endpoint(status_code, Feature::ReadOffsets)
+            .await?;
+        match status_code {
+            StatusCode::OK => Ok(response),
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(connector.to_string()))
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
+    }
+
+    /// Alters the offsets of a connector, which must be stopped
+    pub async fn alter_connector_offsets<P, O>(
+        &self,
+        connector: &str,
+        offsets: &ConnectorOffsets<P, O>,
+    ) -> Result<String>
+    where
+        P: Serialize,
+        O: Serialize,
+    {
+        if let Some(dry_run) = &self.dry_run {
+            self.gate(Feature::AlterOffsets).await?;
+            let mutation = dry_run::Mutation::AlterOffsets {
+                connector: connector.to_string(),
+            };
+            return self
+                .preview_on_connector(dry_run, connector, None, mutation)
+                .await;
+        }
+        let response = self
+            .client
+            .patch(format!("{}/connectors/{}/offsets", self.address, connector))
+            .json(offsets)
             .send()
-            .await?
-            .json()
             .await?;
-        Ok(response)
+        self.check_endpoint(response.status(), Feature::AlterOffsets)
+            .await?;
+        offsets_response(connector, response, self.max_response_size).await
+    }
+
+    /// Resets the offsets of a connector, which must be stopped
+    pub async fn reset_connector_offsets(&self, connector: &str) -> Result<String> {
+        if let Some(dry_run) = &self.dry_run {
+            self.gate(Feature::AlterOffsets).await?;
+            let mutation = dry_run::Mutation::ResetOffsets {
+                connector: connector.to_string(),
+            };
+            return self
+                .preview_on_connector(dry_run, connector, None, mutation)
+                .await;
+        }
+        let response = self
+            .client
+            .delete(format!("{}/connectors/{}/offsets", self.address, connector))
+            .send()
+            .await?;
+        self.check_endpoint(response.status(), Feature::AlterOffsets)
+            .await?;
+        offsets_response(connector, response, self.max_response_size).await
+    }
+
+    /// Validates a configuration against the plugin set in its `connector.class`
+    pub async fn validate_connector_config(&self, config: &ConnectorConfig) -> Result<ConfigInfos> {
+        let Some(class) = config.get_str(config_keys::CONNECTOR_CLASS) else {
+            anyhow::bail!(
+                "The configuration must set {}",
+                config_keys::CONNECTOR_CLASS
+            );
+        };
+        let response = self
+            .client
+            .put(format!(
+                "{}/connector-plugins/{}/config/validate",
+                self.address, class
+            ))
+            .json(config)
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::OK => self.parse(response).await,
+            StatusCode::NOT_FOUND => anyhow::bail!(ConnectError::PluginNotFound(class.to_string())),
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
+    }
+
+    /// Returns the level of every logger of the worker
+    pub async fn loggers(&self) -> Result<HashMap<String, LoggerLevel>> {
+        let response = self
+            .client
+            .get(format!("{}/admin/loggers", self.address))
+            .send()
+            .await?;
+        self.parse(response).await
+    }
+
+    pub async fn logger(&self, logger: &str) -> Result<LoggerLevel> {
+        self.logger_as(logger).await
+    }
+
+    async fn logger_as<T: DeserializeOwned>(&self, logger: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{}/admin/loggers/{}", self.address, logger))
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::OK => self.parse(response).await,
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::LoggerNotFound(logger.to_string()))
+            }
+            _ => anyhow::bail!("Unrecognizable error for status code {}", status_code),
+        }
+    }
+
+    /// Sets the level of a logger and of its children.
+    /// Returns the affected loggers, which are only known when the scope is a single worker
+    pub async fn set_logger_level(
+        &self,
+        logger: &str,
+        level: &str,
+        scope: LoggerScope,
+    ) -> Result<Vec<String>> {
+        // older workers ignore the scope and only change the worker receiving the request
+        if scope == LoggerScope::Cluster {
+            self.gate(Feature::ClusterLoggers).await?;
+        }
+        if let Some(dry_run) = &self.dry_run {
+            return self.preview_logger(dry_run, logger, level, scope).await;
+        }
+        let response = self
+            .client
+            .put(format!(
+                "{}/admin/loggers/{}?scope={}",
+                self.address,
+                logger,
+                scope.as_str()
+            ))
+            .json(&serde_json::json!({ "level": level }))
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::OK => self.parse(response).await,
+            StatusCode::NO_CONTENT => Ok(Vec::new()),
+            StatusCode::BAD_REQUEST => {
+                anyhow::bail!("Invalid logger level: {}", error_message(response).await)
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
+    }
+
+    /// Calls an endpoint the client does not wrap, such as the extensions of a vendor, through the
+    /// same authentication, retries and size limit. The path is relative to the address of the
+    /// cluster, and empty responses parse as `null`, so `()` or `Option<T>` can be requested
+    pub async fn request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&serde_json::Value>,
+    ) -> Result<T> {
+        if self.dry_run.is_some() && method != reqwest::Method::GET {
+            anyhow::bail!("Only GET requests can be made in a dry run, not {}", method);
+        }
+        let url = format!("{}/{}", self.address, path.trim_start_matches('/'));
+        let mut request = self.client.request(method, url).query(query);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request.send().await?;
+        let status_code = response.status();
+        match status_code {
+            _ if status_code.is_success() => {
+                let mut body = json::read_body(response, self.max_response_size).await?;
+                if body.is_empty() {
+                    body = b"null".to_vec();
+                }
+                json::from_slice(&mut body)
+            }
+            StatusCode::CONFLICT => {
+                anyhow::bail!(ConnectError::Rebalancing)
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                error_message(response).await
+            ),
+        }
+    }
+}
+
+/// Bytes of an error body read, the rest being left out
+pub(crate) const MAX_ERROR_BODY: usize = 8 * 1024;
+
+/// Extracts the message of an error returned by kafka-connect, falling back to the raw body
+async fn error_message(response: reqwest::Response) -> String {
+    let body = json::read_capped(response, MAX_ERROR_BODY).await;
+    match serde_json::from_slice::<ErrorResponse>(&body) {
+        Ok(error) => error.message,
+        Err(_) => String::from_utf8_lossy(&body).into_owned(),
+    }
+}
+
+async fn offsets_response(
+    connector: &str,
+    response: reqwest::Response,
+    limit: Option<usize>,
+) -> Result<String> {
+    let status_code = response.status();
+    match status_code {
+        StatusCode::OK => Ok(json::from_response::<OffsetsMessage>(response, limit)
+            .await?
+            .message),
+        StatusCode::BAD_REQUEST => anyhow::bail!(
+            "The offsets could not be modified, the connector may not be stopped: {}",
+            error_message(response).await
+        ),
+        StatusCode::NOT_FOUND => {
+            anyhow::bail!(ConnectError::ConnectorNotFound(connector.to_string()))
+        }
+        StatusCode::CONFLICT => {
+            anyhow::bail!(ConnectError::Rebalancing)
+        }
+        _ => anyhow::bail!(
+            "Unrecognizable error for status code {}: {}",
+            status_code,
+            error_message(response).await
+        ),
     }
 }
@@ -0,0 +1,184 @@
+//! Linting of configurations, catching the mistakes kafka-connect accepts
+//! [`lint`] runs the built-in rules on a configuration without contacting the cluster, returning
+//! [`Finding`]s that CI can fail on according to their [`Severity`]
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::error_handling::lint_error_handling;
+use serde::Serialize;
+use std::fmt;
+
+/// Keys deprecated by the plugins whose class starts with the prefix, with their replacement
+const DEPRECATED: &[(&str, &str, &str)] = &[
+    ("io.debezium.", "database.server.name", "topic.prefix"),
+    (
+        "io.debezium.",
+        "database.history.kafka.topic",
+        "schema.history.internal.kafka.topic",
+    ),
+    (
+        "io.debezium.",
+        "database.history.kafka.bootstrap.servers",
+        "schema.history.internal.kafka.bootstrap.servers",
+    ),
+    (
+        "io.debezium.",
+        "database.whitelist",
+        "database.include.list",
+    ),
+    (
+        "io.debezium.",
+        "database.blacklist",
+        "database.exclude.list",
+    ),
+    ("io.debezium.", "schema.whitelist", "schema.include.list"),
+    ("io.debezium.", "schema.blacklist", "schema.exclude.list"),
+    ("io.debezium.", "table.whitelist", "table.include.list"),
+    ("io.debezium.", "table.blacklist", "table.exclude.list"),
+    ("io.debezium.", "column.whitelist", "column.include.list"),
+    ("io.debezium.", "column.blacklist", "column.exclude.list"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    /// The connector will fail, or be rejected
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// Name of the rule, such as `tasks-max`
+    pub rule: &'static str,
+    pub severity: Severity,
+    /// Key the finding is about, when a single one
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(rule: &'static str, severity: Severity, key: Option<&str>, message: String) -> Self {
+        Self {
+            rule,
+            severity,
+            key: key.map(str::to_string),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}] {}", self.severity, self.rule, self.message)
+    }
+}
+
+fn converters(config: &ConnectorConfig, findings: &mut Vec<Finding>) {
+    for key in [config_keys::KEY_CONVERTER, config_keys::VALUE_CONVERTER] {
+        if config.get_str(key).is_none_or(str::is_empty) {
+            findings.push(Finding::new(
+                "missing-converter",
+                Severity::Warning,
+                Some(key),
+                format!("{} is unset, the default of the worker will be used", key),
+            ));
+        }
+    }
+}
+
+fn tasks_max(config: &ConnectorConfig, findings: &mut Vec<Finding>) {
+    let key = config_keys::TASKS_MAX;
+    let finding = match config.get_i64(key) {
+        Ok(None) => Finding::new(
+            "tasks-max",
+            Severity::Warning,
+            Some(key),
+            format!("{} is unset, a single task will run", key),
+        ),
+        Ok(Some(tasks)) if tasks < 1 => Finding::new(
+            "tasks-max",
+            Severity::Error,
+            Some(key),
+            format!("{} is {}, no task can run", key, tasks),
+        ),
+        Ok(Some(_)) => return,
+        Err(_) => Finding::new(
+            "tasks-max",
+            Severity::Error,
+            Some(key),
+            format!("{} is not an integer", key),
+        ),
+    };
+    findings.push(finding);
+}
+
+fn topics(config: &ConnectorConfig, findings: &mut Vec<Finding>) {
+    let set = |key| config.get_str(key).is_some_and(|value| !value.is_empty());
+    if config.is_sink() && set(config_keys::TOPICS) && set(config_keys::TOPICS_REGEX) {
+        findings.push(Finding::new(
+            "topics-and-regex",
+            Severity::Error,
+            None,
+            format!(
+                "{} and {} are both set, sinks only accept one",
+                config_keys::TOPICS,
+                config_keys::TOPICS_REGEX
+            ),
+        ));
+    }
+}
+
+fn error_handling(config: &ConnectorConfig, findings: &mut Vec<Finding>) {
+    if let Some(message) = lint_error_handling(config) {
+        findings.push(Finding::new(
+            "sink-error-handling",
+            Severity::Warning,
+            Some(config_keys::ERRORS_TOLERANCE),
+            message,
+        ));
+    }
+}
+
+fn deprecated(config: &ConnectorConfig, findings: &mut Vec<Finding>) {
+    let class = config
+        .get_str(config_keys::CONNECTOR_CLASS)
+        .unwrap_or_default();
+    for (prefix, key, replacement) in DEPRECATED {
+        if class.starts_with(prefix) && config.contains_key(*key) {
+            findings.push(Finding::new(
+                "deprecated-key",
+                Severity::Warning,
+                Some(key),
+                format!("{} is deprecated, use {}", key, replacement),
+            ));
+        }
+    }
+}
+
+/// Findings of the built-in rules, the most severe first
+pub fn lint(config: &ConnectorConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    converters(config, &mut findings);
+    tasks_max(config, &mut findings);
+    topics(config, &mut findings);
+    error_handling(config, &mut findings);
+    deprecated(config, &mut findings);
+    findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+    findings
+}
+
+/// Severity of the most severe finding, `None` when there are none
+pub fn max_severity(findings: &[Finding]) -> Option<Severity> {
+    findings.iter().map(|finding| finding.severity).max()
+}
@@ -0,0 +1,200 @@
+//! Streaming of expanded connector listings
+//! The response of `/connectors?expand=...` is a single json map, which on large clusters weighs many
+//! megabytes. It is split into its entries as the body arrives, so that only one entry is buffered at a time
+use crate::error::ConnectError;
+use crate::json;
+use crate::models::Connector;
+use crate::Connect;
+use anyhow::Result;
+use futures::{stream, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+impl Connect {
+    /// Same as [`Connect::connectors`], yielding every connector as soon as it is parsed.
+    /// The size limit of the client applies to each entry rather than to the whole listing
+    pub async fn connectors_stream(
+        &self,
+        expand_status: bool,
+        expand_info: bool,
+    ) -> Result<impl Stream<Item = Result<(String, Connector)>>> {
+        let limit = self.max_response_size;
+        let endpoint = self.connectors_endpoint(expand_status, expand_info)?;
+        let response = self.client.get(endpoint).send().await?;
+        let status_code = response.status();
+        if !status_code.is_success() {
+            anyhow::bail!("Unrecognizable error for status code {}", status_code);
+        }
+        let state = (
+            response.bytes_stream().boxed(),
+            MapSplitter::default(),
+            false,
+        );
+        Ok(stream::unfold(
+            state,
+            move |(mut body, mut splitter, mut done)| async move {
+                loop {
+                    if done {
+                        return None;
+                    }
+                    match splitter.next_entry() {
+                        Ok(Some(entry)) => return Some((Ok(entry), (body, splitter, done))),
+                        Ok(None) if splitter.is_finished() => return None,
+                        Ok(None) => {}
+                        Err(e) => return Some((Err(e), (body, splitter, true))),
+                    }
+                    match body.next().await {
+                        Some(Ok(chunk)) => {
+                            splitter.push(&chunk);
+                            if let Some(limit) =
+                                limit.filter(|limit| splitter.buffer.len() > *limit)
+                            {
+                                let error = ConnectError::ResponseTooLarge { limit };
+                                return Some((Err(error.into()), (body, splitter, true)));
+                            }
+                        }
+                        Some(Err(e)) => return Some((Err(e.into()), (body, splitter, true))),
+                        None => {
+                            done = true;
+                            if !splitter.is_finished() {
+                                let error = anyhow::anyhow!("The listing ended unexpectedly");
+                                return Some((Err(error), (body, splitter, done)));
+                            }
+                        }
+                    }
+                }
+            },
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Position {
+    /// Before the opening brace
+    #[default]
+    Start,
+    /// Before a key, or the closing brace
+    Entries,
+    Finished,
+}
+
+/// Splits a json object into its entries, without parsing the object as a whole
+#[derive(Debug, Default)]
+struct MapSplitter {
+    buffer: Vec<u8>,
+    position: Position,
+}
+
+impl MapSplitter {
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.position == Position::Finished
+    }
+
+    /// Returns the next complete entry, or None when more data is needed
+    fn next_entry<V: DeserializeOwned>(&mut self) -> Result<Option<(String, V)>> {
+        let mut index = skip_whitespace(&self.buffer, 0);
+        if self.position == Position::Start {
+            match self.buffer.get(index) {
+                None => return Ok(None),
+                Some(b'{') => {
+                    self.buffer.drain(..=index);
+                    self.position = Position::Entries;
+                    index = skip_whitespace(&self.buffer, 0);
+                }
+                Some(_) => anyhow::bail!("Expected a json object"),
+            }
+        }
+        if self.position != Position::Entries {
+            return Ok(None);
+        }
+        if self.buffer.get(index) == Some(&b',') {
+            index = skip_whitespace(&self.buffer, index + 1);
+        }
+        match self.buffer.get(index) {
+            None => return Ok(None),
+            Some(b'}') => {
+                self.buffer.clear();
+                self.position = Position::Finished;
+                return Ok(None);
+            }
+            Some(b'"') => {}
+            Some(_) => anyhow::bail!("Expected a key in the json object"),
+        }
+        let key_start = index;
+        let Some(key_end) = string_end(&self.buffer, key_start) else {
+            return Ok(None);
+        };
+        index = skip_whitespace(&self.buffer, key_end);
+        match self.buffer.get(index) {
+            None => return Ok(None),
+            Some(b':') => {}
+            Some(_) => anyhow::bail!("Expected a colon after a key of the json object"),
+        }
+        let value_start = skip_whitespace(&self.buffer, index + 1);
+        let Some(value_end) = value_end(&self.buffer, value_start) else {
+            return Ok(None);
+        };
+        let key: String = serde_json::from_slice(&self.buffer[key_start..key_end])?;
+        let value: V = json::from_slice(&mut self.buffer[value_start..value_end])?;
+        self.buffer.drain(..value_end);
+        Ok(Some((key, value)))
+    }
+}
+
+fn skip_whitespace(buffer: &[u8], mut index: usize) -> usize {
+    while buffer.get(index).is_some_and(u8::is_ascii_whitespace) {
+        index += 1;
+    }
+    index
+}
+
+/// Index right after the string starting at `start`, if it is complete
+fn string_end(buffer: &[u8], start: usize) -> Option<usize> {
+    let mut escaped = false;
+    for (offset, byte) in buffer[start + 1..].iter().enumerate() {
+        match byte {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Some(start + offset + 2),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Index right after the value starting at `start`, if it is complete
+fn value_end(buffer: &[u8], start: usize) -> Option<usize> {
+    match buffer.get(start)? {
+        b'"' => string_end(buffer, start),
+        b'{' | b'[' => {
+            let mut depth = 0usize;
+            let mut index = start;
+            while index < buffer.len() {
+                match buffer[index] {
+                    b'"' => {
+                        index = string_end(buffer, index)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(index + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                index += 1;
+            }
+            None
+        }
+        // scalars end at the next separator, which must have arrived for the scalar to be complete
+        _ => buffer[start..]
+            .iter()
+            .position(|byte| matches!(byte, b',' | b'}') || byte.is_ascii_whitespace())
+            .map(|offset| start + offset),
+    }
+}
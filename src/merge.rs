@@ -0,0 +1,108 @@
+//! Three-way merge of configurations, to preserve the changes operators make on a live connector
+//! Given the configuration last applied (the base), the live one and the desired one, each key
+//! takes the side that changed it. A key both sides changed differently is a conflict, which
+//! takes the desired value so that a reconcile converges; callers wanting to keep the live value
+//! can set it back from the conflict
+use crate::config::ConnectorConfig;
+use crate::redact::{is_secret_key, MASK};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A key changed on both sides since the base, values being `None` when unset. `Debug` masks the
+/// secrets
+#[derive(Clone, PartialEq, Eq, Serialize)]
+pub struct Conflict {
+    pub key: String,
+    pub base: Option<String>,
+    pub live: Option<String>,
+    pub desired: Option<String>,
+}
+
+impl fmt::Debug for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secret = is_secret_key(&self.key);
+        let shown = |value: &Option<String>| -> Option<String> {
+            value.as_ref().map(|value| {
+                if secret {
+                    MASK.to_string()
+                } else {
+                    value.clone()
+                }
+            })
+        };
+        f.debug_struct("Conflict")
+            .field("key", &self.key)
+            .field("base", &shown(&self.base))
+            .field("live", &shown(&self.live))
+            .field("desired", &shown(&self.desired))
+            .finish()
+    }
+}
+
+/// The values of secrets are masked
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shown = |value: &Option<String>| match value {
+            None => "(unset)".to_string(),
+            Some(_) if is_secret_key(&self.key) => MASK.to_string(),
+            Some(value) => value.clone(),
+        };
+        write!(
+            f,
+            "{}: {} was changed to {} live and to {} in the desired configuration",
+            self.key,
+            shown(&self.base),
+            shown(&self.live),
+            shown(&self.desired)
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Merge {
+    pub config: ConnectorConfig,
+    /// Conflicts sorted by key
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Merge {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merges the changes made live and the desired ones since the base
+pub fn three_way(
+    base: &ConnectorConfig,
+    live: &ConnectorConfig,
+    desired: &ConnectorConfig,
+) -> Merge {
+    let keys: BTreeSet<&String> = base
+        .keys()
+        .chain(live.keys())
+        .chain(desired.keys())
+        .collect();
+    let mut config = ConnectorConfig::new();
+    let mut conflicts = Vec::new();
+    for key in keys {
+        let (base, live, desired) = (base.get(key), live.get(key), desired.get(key));
+        let merged = if live == desired || live == base {
+            desired
+        } else if desired == base {
+            live
+        } else {
+            conflicts.push(Conflict {
+                key: key.clone(),
+                base: base.cloned(),
+                live: live.cloned(),
+                desired: desired.cloned(),
+            });
+            desired
+        };
+        if let Some(value) = merged {
+            config.set(key.clone(), value.clone());
+        }
+    }
+    Merge { config, conflicts }
+}
@@ -1,73 +1,434 @@
 //! Connect rest interface models
 //! Every struct defined here is used to interact with the kafka-connect API
 //! The structures follow as of now the specification for kafka-connect  version 7.5
+//! Fields that are not modeled yet are kept in the `extra` map of each structure, so that they survive a round trip
+//! Response structures are `non_exhaustive`, code outside the crate builds them with their constructors
+use crate::config::ConnectorConfig;
+use crate::trace::ExceptionInfo;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
 
 /// ClusterInfo
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct ClusterInfo {
     pub version: String,
     pub commit: String,
     pub kafka_cluster_id: String,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl ClusterInfo {
+    pub fn new(
+        version: impl Into<String>,
+        commit: impl Into<String>,
+        kafka_cluster_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            version: version.into(),
+            commit: commit.into(),
+            kafka_cluster_id: kafka_cluster_id.into(),
+            extra: HashMap::new(),
+        }
+    }
+}
+
+/// Body of the errors returned by the API
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct ErrorResponse {
+    pub error_code: u16,
+    pub message: String,
+}
+
+impl ErrorResponse {
+    pub fn new(error_code: u16, message: impl Into<String>) -> Self {
+        Self {
+            error_code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct Connector {
     pub info: Option<ConnectorInfo>,
     pub status: Option<ConnectorStatus>,
 }
 
 impl Connector {
+    pub fn new(info: Option<ConnectorInfo>, status: Option<ConnectorStatus>) -> Self {
+        Self { info, status }
+    }
+
     pub fn name(&self) -> &str {
-        if self.info.is_some() {
-            return &self.info.as_ref().unwrap().name;
+        if let Some(info) = &self.info {
+            return &info.name;
         }
         &self.status.as_ref().unwrap().name
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct ConnectorInfo {
     pub name: String,
-    pub config: HashMap<String, String>,
+    pub config: ConnectorConfig,
     pub tasks: Vec<TaskInfo>,
     #[serde(rename = "type")]
     pub kind: String,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl ConnectorInfo {
+    /// Info of a connector of the type, `source` or `sink`, running `tasks` tasks
+    pub fn new(
+        name: impl Into<String>,
+        kind: impl Into<String>,
+        config: ConnectorConfig,
+        tasks: u64,
+    ) -> Self {
+        let name = name.into();
+        Self {
+            tasks: (0..tasks).map(|task| TaskInfo::new(&name, task)).collect(),
+            name,
+            config,
+            kind: kind.into(),
+            extra: HashMap::new(),
+        }
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct TaskInfo {
     pub connector: String,
     pub task: u64,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl TaskInfo {
+    pub fn new(connector: impl Into<String>, task: u64) -> Self {
+        Self {
+            connector: connector.into(),
+            task,
+            extra: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct ConnectorStatus {
     pub connector: ConnectorState,
     pub name: String,
     pub tasks: Vec<TaskStatus>,
     #[serde(rename = "type")]
     pub kind: String,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl ConnectorStatus {
+    pub fn new(
+        name: impl Into<String>,
+        kind: impl Into<String>,
+        connector: ConnectorState,
+        tasks: Vec<TaskStatus>,
+    ) -> Self {
+        Self {
+            connector,
+            name: name.into(),
+            tasks,
+            kind: kind.into(),
+            extra: HashMap::new(),
+        }
+    }
+
+    /// A connector is healthy when both the connector and all of its tasks are running
+    pub fn is_healthy(&self) -> bool {
+        self.connector.state.is_healthy() && self.tasks.iter().all(|task| task.state.is_healthy())
+    }
+
+    pub fn failed_tasks(&self) -> Vec<&TaskStatus> {
+        self.tasks
+            .iter()
+            .filter(|task| task.state == Status::Failed)
+            .collect()
+    }
+
+    pub fn running_task_count(&self) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| task.state == Status::Running)
+            .count()
+    }
+
+    /// Workers the connector and its tasks are assigned to
+    pub fn workers(&self) -> BTreeSet<&str> {
+        std::iter::once(self.connector.worker_id.as_str())
+            .chain(self.tasks.iter().map(|task| task.worker_id.as_str()))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct ConnectorState {
     pub connector: Option<String>,
     pub state: Status,
     pub worker_id: String,
+    pub trace: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl ConnectorState {
+    pub fn new(state: Status, worker_id: impl Into<String>) -> Self {
+        Self {
+            connector: None,
+            state,
+            worker_id: worker_id.into(),
+            trace: None,
+            extra: HashMap::new(),
+        }
+    }
+
+    pub fn with_trace(mut self, trace: impl Into<String>) -> Self {
+        self.trace = Some(trace.into());
+        self
+    }
+
+    /// Parses the trace of a failed connector
+    pub fn exception(&self) -> Option<ExceptionInfo> {
+        self.trace.as_deref().and_then(ExceptionInfo::parse)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct TaskStatus {
     pub id: u64,
     pub state: Status,
     pub worker_id: String,
     pub trace: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl TaskStatus {
+    pub fn new(id: u64, state: Status, worker_id: impl Into<String>) -> Self {
+        Self {
+            id,
+            state,
+            worker_id: worker_id.into(),
+            trace: None,
+            extra: HashMap::new(),
+        }
+    }
+
+    pub fn with_trace(mut self, trace: impl Into<String>) -> Self {
+        self.trace = Some(trace.into());
+        self
+    }
+
+    /// Parses the trace of a failed task
+    pub fn exception(&self) -> Option<ExceptionInfo> {
+        self.trace.as_deref().and_then(ExceptionInfo::parse)
+    }
+}
+
+/// Result of validating a connector configuration
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct ConfigInfos {
+    /// Class of the connector plugin
+    pub name: String,
+    pub error_count: u32,
+    pub groups: Vec<String>,
+    pub configs: Vec<ConfigInfo>,
+}
+
+impl ConfigInfos {
+    /// Result for the plugin, counting the errors of the values
+    pub fn new(name: impl Into<String>, configs: Vec<ConfigInfo>) -> Self {
+        let mut groups: Vec<String> = Vec::new();
+        for config in &configs {
+            if let Some(group) = &config.definition.group {
+                if !groups.contains(group) {
+                    groups.push(group.clone());
+                }
+            }
+        }
+        Self {
+            name: name.into(),
+            error_count: configs
+                .iter()
+                .map(|config| config.value.errors.len() as u32)
+                .sum(),
+            groups,
+            configs,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.error_count == 0
+    }
+
+    /// Every key with at least one error, along with its errors
+    pub fn errors(&self) -> Vec<(&str, &[String])> {
+        self.configs
+            .iter()
+            .filter(|config| !config.value.errors.is_empty())
+            .map(|config| (config.value.name.as_str(), config.value.errors.as_slice()))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct ConfigInfo {
+    pub definition: ConfigKeyInfo,
+    pub value: ConfigValueInfo,
+}
+
+impl ConfigInfo {
+    pub fn new(definition: ConfigKeyInfo, value: ConfigValueInfo) -> Self {
+        Self { definition, value }
+    }
+}
+
+/// Definition of a configuration key, as declared by the plugin
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct ConfigKeyInfo {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub required: bool,
+    pub default_value: Option<String>,
+    pub importance: String,
+    pub documentation: Option<String>,
+    pub group: Option<String>,
+    pub order: i32,
+    pub width: String,
+    pub display_name: String,
+    pub dependents: Vec<String>,
+}
+
+impl ConfigKeyInfo {
+    /// Optional key of the type, such as `STRING`, of medium importance
+    pub fn new(name: impl Into<String>, kind: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            display_name: name.clone(),
+            name,
+            kind: kind.into(),
+            required: false,
+            default_value: None,
+            importance: "MEDIUM".to_string(),
+            documentation: None,
+            group: None,
+            order: -1,
+            width: "NONE".to_string(),
+            dependents: Vec::new(),
+        }
+    }
+}
+
+/// Value of a configuration key, along with its validation results
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct ConfigValueInfo {
+    pub name: String,
+    pub value: Option<String>,
+    pub recommended_values: Vec<String>,
+    pub errors: Vec<String>,
+    pub visible: bool,
+}
+
+impl ConfigValueInfo {
+    /// Visible value without errors
+    pub fn new(name: impl Into<String>, value: Option<String>) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            recommended_values: Vec::new(),
+            errors: Vec::new(),
+            visible: true,
+        }
+    }
+
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.errors.push(error.into());
+        self
+    }
+}
+
+/// Level of a logger of a worker
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct LoggerLevel {
+    pub level: String,
+    /// Unix timestamp in milliseconds of the last change, if the level was changed at runtime
+    pub last_modified: Option<u64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl LoggerLevel {
+    pub fn new(level: impl Into<String>) -> Self {
+        Self {
+            level: level.into(),
+            last_modified: None,
+            extra: HashMap::new(),
+        }
+    }
+}
+
+/// Which workers a logger level change applies to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LoggerScope {
+    /// Only the worker receiving the request
+    #[default]
+    Worker,
+    /// Every worker of the cluster
+    Cluster,
+}
+
+impl LoggerScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Worker => "worker",
+            Self::Cluster => "cluster",
+        }
+    }
 }
 
 /// Status that a task or connector may be in
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+/// Deserializing is case insensitive, as some proxies lowercase the states
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Status {
     Paused,
@@ -75,4 +436,88 @@ pub enum Status {
     Restarting,
     Failed,
     Unassigned,
+    Stopped,
+}
+
+impl Status {
+    /// Whether the state is settled and only changes on an action or a failure, as opposed to
+    /// RESTARTING and UNASSIGNED
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, Self::Restarting | Self::Unassigned)
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        *self == Self::Running
+    }
+}
+
+impl<'de> Deserialize<'de> for Status {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let status = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        status.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for Status {
+    type Err = anyhow::Error;
+
+    fn from_str(status: &str) -> anyhow::Result<Self> {
+        match status.to_ascii_uppercase().as_str() {
+            "PAUSED" => Ok(Self::Paused),
+            "RUNNING" => Ok(Self::Running),
+            "RESTARTING" => Ok(Self::Restarting),
+            "FAILED" => Ok(Self::Failed),
+            "UNASSIGNED" => Ok(Self::Unassigned),
+            "STOPPED" => Ok(Self::Stopped),
+            _ => anyhow::bail!("Unknown status {}", status),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Self::Paused => "PAUSED",
+            Self::Running => "RUNNING",
+            Self::Restarting => "RESTARTING",
+            Self::Failed => "FAILED",
+            Self::Unassigned => "UNASSIGNED",
+            Self::Stopped => "STOPPED",
+        };
+        f.write_str(status)
+    }
+}
+
+/// Plugin installed on the workers of a cluster
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct PluginInfo {
+    pub class: String,
+    /// `source` or `sink` for connectors
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Version reported by the plugin, unset when it does not declare one
+    pub version: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl PluginInfo {
+    pub fn new(class: impl Into<String>, kind: impl Into<String>) -> Self {
+        Self {
+            class: class.into(),
+            kind: kind.into(),
+            version: None,
+            extra: HashMap::new(),
+        }
+    }
+
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
 }
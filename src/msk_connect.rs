@@ -0,0 +1,477 @@
+//! Connectors managed by Amazon MSK Connect
+//! Only available with the `msk_connect` feature
+//!
+//! MSK Connect is operated through the AWS API rather than the kafka-connect REST API. Connectors are
+//! addressed by ARN there, so names are resolved by listing. It has no tasks, offsets, loggers or
+//! pausing, and creating a connector needs infrastructure settings beside its configuration, given
+//! with [`MskConnect::with_template`]
+use crate::api::ConnectApi;
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::error::ConnectError;
+use crate::models::*;
+use crate::offsets::ConnectorOffsets;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use aws_sdk_kafkaconnect::operation::create_connector::builders::CreateConnectorFluentBuilder;
+use aws_sdk_kafkaconnect::operation::describe_connector::DescribeConnectorOutput;
+use aws_sdk_kafkaconnect::types::{
+    self as aws, ApacheKafkaCluster, Capacity, ConnectorSummary, CustomPlugin, KafkaCluster,
+    KafkaClusterClientAuthentication, KafkaClusterClientAuthenticationType,
+    KafkaClusterEncryptionInTransit, KafkaClusterEncryptionInTransitType, Plugin,
+    ProvisionedCapacity, Vpc,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Settings MSK Connect requires to create a connector, shared by the connectors of a cluster
+#[derive(Clone, Debug)]
+pub struct MskConnectorTemplate {
+    /// Such as 3.7.x
+    pub kafka_connect_version: String,
+    /// IAM role the connector runs as
+    pub service_execution_role_arn: String,
+    pub bootstrap_servers: String,
+    pub subnets: Vec<String>,
+    pub security_groups: Vec<String>,
+    /// ARNs and revisions of the custom plugins the connector classes come from
+    pub plugins: Vec<(String, i64)>,
+    pub mcu_count: i32,
+    pub worker_count: i32,
+    /// Authenticates to the Kafka cluster with IAM instead of no authentication
+    pub iam_authentication: bool,
+    /// Connects to the Kafka cluster with TLS
+    pub tls: bool,
+}
+
+/// Client of the connectors of an AWS account and region
+#[derive(Clone, Debug)]
+pub struct MskConnect {
+    client: aws_sdk_kafkaconnect::Client,
+    template: Option<MskConnectorTemplate>,
+}
+
+impl MskConnect {
+    pub fn new(client: aws_sdk_kafkaconnect::Client) -> Self {
+        Self {
+            client,
+            template: None,
+        }
+    }
+
+    /// Client configured from the environment, as the AWS CLI would be
+    pub async fn from_env() -> Self {
+        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        Self::new(aws_sdk_kafkaconnect::Client::new(&config))
+    }
+
+    /// Settings used to create connectors. Creating fails without them
+    pub fn with_template(mut self, template: MskConnectorTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    async fn summaries(&self, prefix: Option<&str>) -> Result<Vec<ConnectorSummary>> {
+        let pages: Vec<_> = self
+            .client
+            .list_connectors()
+            .set_connector_name_prefix(prefix.map(String::from))
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await?;
+        Ok(pages
+            .into_iter()
+            .flat_map(|page| page.connectors.unwrap_or_default())
+            .collect())
+    }
+
+    async fn summary(&self, name: &str) -> Result<ConnectorSummary> {
+        self.summaries(Some(name))
+            .await?
+            .into_iter()
+            .find(|summary| summary.connector_name() == Some(name))
+            .ok_or_else(|| ConnectError::ConnectorNotFound(name.to_string()).into())
+    }
+
+    async fn arn_of(&self, name: &str) -> Result<String> {
+        let summary = self.summary(name).await?;
+        summary.connector_arn.context("MSK Connect returned no ARN")
+    }
+
+    async fn describe(&self, name: &str) -> Result<DescribeConnectorOutput> {
+        let arn = self.arn_of(name).await?;
+        Ok(self
+            .client
+            .describe_connector()
+            .connector_arn(arn)
+            .send()
+            .await?)
+    }
+
+    fn create_request(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<CreateConnectorFluentBuilder> {
+        let Some(template) = &self.template else {
+            anyhow::bail!(
+                "Creating MSK connectors requires a template, see MskConnect::with_template"
+            );
+        };
+        let vpc = Vpc::builder()
+            .set_subnets(Some(template.subnets.clone()))
+            .set_security_groups(Some(template.security_groups.clone()))
+            .build()?;
+        let cluster = ApacheKafkaCluster::builder()
+            .bootstrap_servers(&template.bootstrap_servers)
+            .vpc(vpc)
+            .build()?;
+        let authentication = if template.iam_authentication {
+            KafkaClusterClientAuthenticationType::Iam
+        } else {
+            KafkaClusterClientAuthenticationType::None
+        };
+        let encryption = if template.tls {
+            KafkaClusterEncryptionInTransitType::Tls
+        } else {
+            KafkaClusterEncryptionInTransitType::Plaintext
+        };
+        let mut request = self
+            .client
+            .create_connector()
+            .connector_name(name)
+            .set_connector_configuration(Some(
+                config.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            ))
+            .kafka_connect_version(&template.kafka_connect_version)
+            .service_execution_role_arn(&template.service_execution_role_arn)
+            .kafka_cluster(
+                KafkaCluster::builder()
+                    .apache_kafka_cluster(cluster)
+                    .build(),
+            )
+            .kafka_cluster_client_authentication(
+                KafkaClusterClientAuthentication::builder()
+                    .authentication_type(authentication)
+                    .build()?,
+            )
+            .kafka_cluster_encryption_in_transit(
+                KafkaClusterEncryptionInTransit::builder()
+                    .encryption_type(encryption)
+                    .build()?,
+            )
+            .capacity(
+                Capacity::builder()
+                    .provisioned_capacity(
+                        ProvisionedCapacity::builder()
+                            .mcu_count(template.mcu_count)
+                            .worker_count(template.worker_count)
+                            .build(),
+                    )
+                    .build(),
+            );
+        for (arn, revision) in &template.plugins {
+            let plugin = CustomPlugin::builder()
+                .custom_plugin_arn(arn)
+                .revision(*revision)
+                .build()?;
+            request = request.plugins(Plugin::builder().custom_plugin(plugin).build());
+        }
+        Ok(request)
+    }
+}
+
+fn state_of(state: Option<&aws::ConnectorState>) -> Status {
+    match state {
+        Some(aws::ConnectorState::Running) => Status::Running,
+        Some(aws::ConnectorState::Failed) => Status::Failed,
+        Some(aws::ConnectorState::Restarting) => Status::Restarting,
+        // creating, updating and deleting connectors are not running anywhere yet, or anymore
+        _ => Status::Unassigned,
+    }
+}
+
+/// MSK Connect does not tell sources from sinks, the class name usually does
+fn kind_of(config: &ConnectorConfig) -> String {
+    match config.get_str(config_keys::CONNECTOR_CLASS) {
+        Some(class) if class.contains("Sink") => "sink".to_string(),
+        Some(class) if class.contains("Source") => "source".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn arn_extra(arn: Option<&str>) -> HashMap<String, Value> {
+    arn.map(|arn| ("arn".to_string(), Value::from(arn)))
+        .into_iter()
+        .collect()
+}
+
+fn status_of(
+    name: &str,
+    arn: Option<&str>,
+    state: Option<&aws::ConnectorState>,
+    trace: Option<String>,
+    kind: String,
+) -> ConnectorStatus {
+    ConnectorStatus {
+        connector: ConnectorState {
+            connector: None,
+            state: state_of(state),
+            // the workers are managed by AWS and never exposed
+            worker_id: String::new(),
+            trace,
+            extra: HashMap::new(),
+        },
+        name: name.to_string(),
+        tasks: Vec::new(),
+        kind,
+        extra: arn_extra(arn),
+    }
+}
+
+fn info_of(name: &str, arn: Option<&str>, config: ConnectorConfig) -> ConnectorInfo {
+    ConnectorInfo {
+        name: name.to_string(),
+        kind: kind_of(&config),
+        config,
+        tasks: Vec::new(),
+        extra: arn_extra(arn),
+    }
+}
+
+fn unsupported<T>(operation: &str) -> Result<T> {
+    anyhow::bail!(ConnectError::UnsupportedByCluster(format!(
+        "{} is not available on MSK Connect",
+        operation
+    )))
+}
+
+#[async_trait]
+impl ConnectApi for MskConnect {
+    async fn info(&self) -> Result<ClusterInfo> {
+        unsupported("Cluster info")
+    }
+
+    async fn connector_names(&self) -> Result<Vec<String>> {
+        Ok(self
+            .summaries(None)
+            .await?
+            .into_iter()
+            .filter_map(|summary| summary.connector_name)
+            .collect())
+    }
+
+    /// Expanding the info describes every connector, one request each
+    async fn connectors(
+        &self,
+        expand_status: bool,
+        expand_info: bool,
+    ) -> Result<HashMap<String, Connector>> {
+        let mut connectors = HashMap::new();
+        for summary in self.summaries(None).await? {
+            let Some(name) = summary.connector_name() else {
+                continue;
+            };
+            let info = if expand_info {
+                let config = self.connector_config(name).await?;
+                Some(info_of(name, summary.connector_arn(), config))
+            } else {
+                None
+            };
+            let status = expand_status.then(|| {
+                let kind = info
+                    .as_ref()
+                    .map_or_else(|| "unknown".to_string(), |info| info.kind.clone());
+                status_of(
+                    name,
+                    summary.connector_arn(),
+                    summary.connector_state(),
+                    None,
+                    kind,
+                )
+            });
+            connectors.insert(name.to_string(), Connector { info, status });
+        }
+        Ok(connectors)
+    }
+
+    async fn connector_status(&self, connector: &str) -> Result<ConnectorStatus> {
+        let description = self.describe(connector).await?;
+        let config: ConnectorConfig = description
+            .connector_configuration()
+            .cloned()
+            .unwrap_or_default()
+            .into();
+        let trace = description
+            .state_description()
+            .and_then(|state| state.message())
+            .map(String::from);
+        Ok(status_of(
+            connector,
+            description.connector_arn(),
+            description.connector_state(),
+            trace,
+            kind_of(&config),
+        ))
+    }
+
+    async fn connector_active_topics(&self, _connector: &str) -> Result<Option<Vec<String>>> {
+        unsupported("Reading active topics")
+    }
+
+    /// Creates a connector in the infrastructure of the template. MSK Connect takes minutes to
+    /// start it, its status is unassigned meanwhile
+    async fn create_connector(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        let created = self.create_request(name, config)?.send().await?;
+        Ok(info_of(name, created.connector_arn(), config.clone()))
+    }
+
+    async fn create_connector_with_state(
+        &self,
+        _name: &str,
+        _config: &ConnectorConfig,
+        _initial_state: Status,
+    ) -> Result<ConnectorInfo> {
+        unsupported("Creating connectors in a state")
+    }
+
+    async fn update_connector_config(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorInfo> {
+        let description = self.describe(name).await?;
+        let arn = description
+            .connector_arn()
+            .context("MSK Connect returned no ARN")?;
+        self.client
+            .update_connector()
+            .connector_arn(arn)
+            .set_current_version(description.current_version().map(String::from))
+            .set_connector_configuration(Some(
+                config.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            ))
+            .send()
+            .await?;
+        Ok(info_of(name, Some(arn), config.clone()))
+    }
+
+    /// MSK Connect only replaces whole configurations, so the patch is applied to the current one.
+    /// The read and the update being separate requests, a change made in between is overwritten
+    async fn patch_connector_config(
+        &self,
+        name: &str,
+        patch: &HashMap<String, Option<String>>,
+    ) -> Result<ConnectorInfo> {
+        let mut config = self.connector_config(name).await?;
+        for (key, value) in patch {
+            match value {
+                Some(value) => {
+                    config.set(key.clone(), value.clone());
+                }
+                None => {
+                    config.remove(key);
+                }
+            }
+        }
+        self.update_connector_config(name, &config).await
+    }
+
+    /// Restarts the connector. MSK Connect always restarts its tasks along with it
+    async fn restart_connector(
+        &self,
+        name: &str,
+        _include_tasks: bool,
+        only_failed: bool,
+    ) -> Result<Option<ConnectorStatus>> {
+        let arn = self.arn_of(name).await?;
+        self.client
+            .restart_connector()
+            .connector_arn(arn)
+            .only_failed_tasks(only_failed)
+            .send()
+            .await?;
+        Ok(None)
+    }
+
+    async fn restart_task(&self, _connector: &str, _task: u64) -> Result<()> {
+        unsupported("Restarting a single task")
+    }
+
+    async fn pause_connector(&self, _connector: &str) -> Result<()> {
+        unsupported("Pausing")
+    }
+
+    async fn resume_connector(&self, _connector: &str) -> Result<()> {
+        unsupported("Resuming")
+    }
+
+    async fn stop_connector(&self, _connector: &str) -> Result<()> {
+        unsupported("Stopping")
+    }
+
+    async fn delete_connector(&self, connector: &str) -> Result<()> {
+        let summary = self.summary(connector).await?;
+        self.client
+            .delete_connector()
+            .set_connector_arn(summary.connector_arn)
+            .set_current_version(summary.current_version)
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn connector_config(&self, connector: &str) -> Result<ConnectorConfig> {
+        let description = self.describe(connector).await?;
+        Ok(description
+            .connector_configuration
+            .unwrap_or_default()
+            .into())
+    }
+
+    async fn connector_offsets(&self, _connector: &str) -> Result<ConnectorOffsets> {
+        unsupported("Reading offsets")
+    }
+
+    async fn alter_connector_offsets(
+        &self,
+        _connector: &str,
+        _offsets: &ConnectorOffsets,
+    ) -> Result<String> {
+        unsupported("Altering offsets")
+    }
+
+    async fn reset_connector_offsets(&self, _connector: &str) -> Result<String> {
+        unsupported("Resetting offsets")
+    }
+
+    async fn validate_connector_config(&self, _config: &ConnectorConfig) -> Result<ConfigInfos> {
+        unsupported("Validating configurations")
+    }
+
+    async fn connector_plugins(&self) -> Result<Vec<PluginInfo>> {
+        unsupported("Listing connector plugins")
+    }
+
+    async fn loggers(&self) -> Result<HashMap<String, LoggerLevel>> {
+        unsupported("Reading loggers")
+    }
+
+    async fn logger(&self, _logger: &str) -> Result<LoggerLevel> {
+        unsupported("Reading loggers")
+    }
+
+    async fn set_logger_level(
+        &self,
+        _logger: &str,
+        _level: &str,
+        _scope: LoggerScope,
+    ) -> Result<Vec<String>> {
+        unsupported("Setting logger levels")
+    }
+}
@@ -0,0 +1,114 @@
+//! Validation of connector names, before they reach the cluster
+//! Every name is checked for what breaks the REST paths and the tools deriving names from it, such
+//! as slashes or control characters. A [`NamingPolicy`] set on the client adds the conventions of
+//! a team, and is enforced when creating connectors, including through the PUT of a configuration
+use crate::error::ConnectError;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fmt;
+
+/// Longest name accepted, that of the longest topic, as some tools derive topics from the name
+pub const MAX_LENGTH: usize = 249;
+
+/// Characters that break the paths of the REST API, even when encoded by some proxies
+const FORBIDDEN: &[char] = &['/', '\\', '?', '#', '%'];
+
+fn invalid(name: &str, reason: impl Into<String>) -> anyhow::Error {
+    ConnectError::InvalidConnectorName {
+        name: name.to_string(),
+        reason: reason.into(),
+    }
+    .into()
+}
+
+/// Checks the name for what breaks URLs and consumer group names
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(invalid(name, "the name is empty"));
+    }
+    if name.len() > MAX_LENGTH {
+        return Err(invalid(
+            name,
+            format!("the name is longer than {} bytes", MAX_LENGTH),
+        ));
+    }
+    if name.trim() != name {
+        return Err(invalid(name, "the name starts or ends with whitespace"));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_control() || FORBIDDEN.contains(c))
+    {
+        return Err(invalid(name, format!("the name contains {:?}", c)));
+    }
+    Ok(())
+}
+
+/// Conventions the names of new connectors must follow
+pub trait NamingPolicy: fmt::Debug + Send + Sync {
+    /// Describes why the name does not follow the policy
+    fn check(&self, name: &str) -> std::result::Result<(), String>;
+}
+
+/// Names matching a regular expression, anchored at both ends
+#[derive(Clone, Debug)]
+pub struct PatternPolicy(Regex);
+
+impl PatternPolicy {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let anchored = format!("^(?:{})$", pattern);
+        Ok(Self(
+            Regex::new(&anchored).with_context(|| format!("Invalid pattern {}", pattern))?,
+        ))
+    }
+}
+
+impl NamingPolicy for PatternPolicy {
+    fn check(&self, name: &str) -> std::result::Result<(), String> {
+        if self.0.is_match(name) {
+            Ok(())
+        } else {
+            Err(format!("the name does not match {}", self.0.as_str()))
+        }
+    }
+}
+
+/// Names starting with one of the prefixes, such as the name of a team
+#[derive(Clone, Debug)]
+pub struct PrefixPolicy(Vec<String>);
+
+impl PrefixPolicy {
+    pub fn new<I, S>(prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(prefixes.into_iter().map(Into::into).collect())
+    }
+}
+
+impl NamingPolicy for PrefixPolicy {
+    fn check(&self, name: &str) -> std::result::Result<(), String> {
+        if self
+            .0
+            .iter()
+            .any(|prefix| name.starts_with(prefix.as_str()))
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "the name does not start with any of {}",
+                self.0.join(", ")
+            ))
+        }
+    }
+}
+
+/// Checks the name of a new connector, against the policy when given
+pub(crate) fn check_new_name(name: &str, policy: Option<&dyn NamingPolicy>) -> Result<()> {
+    validate_name(name)?;
+    if let Some(policy) = policy {
+        policy.check(name).map_err(|reason| invalid(name, reason))?;
+    }
+    Ok(())
+}
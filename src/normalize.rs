@@ -0,0 +1,133 @@
+//! Normalization of configurations before comparing them
+//! The values kafka-connect returns are the strings it was given, so cosmetic differences such as
+//! `tasks.max: 01` or `topics: b, a` would otherwise show as perpetual drift. [`Connect::apply`]
+//! compares with the default [`Normalizer`]; dropping the keys set to their default takes the
+//! defaults of the plugin, from [`Connect::normalizer`]. Normalized configurations are only meant
+//! to be compared, the original ones are the ones to write
+use crate::config::{ConfigDiff, ConnectorConfig};
+use crate::config_keys;
+use crate::Connect;
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::LazyLock;
+
+static NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[+-]?\d+(\.\d+)?([eE][+-]?\d+)?$").unwrap());
+
+/// Keys never dropped for being set to their default
+const KEPT: &[&str] = &[config_keys::NAME, config_keys::CONNECTOR_CLASS];
+
+#[derive(Clone, Debug)]
+pub struct Normalizer {
+    /// Trims the whitespace around values and around the items of lists
+    pub trim: bool,
+    /// Writes numbers in their shortest form, such as `7` for `007` and `1.5` for `1.50`
+    pub canonical_numbers: bool,
+    /// Comma separated lists whose order does not matter, `topics` by default
+    pub unordered_lists: BTreeSet<String>,
+    /// Default values of the plugin, dropped from the configurations
+    pub defaults: HashMap<String, String>,
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        Self {
+            trim: true,
+            canonical_numbers: true,
+            unordered_lists: BTreeSet::from([config_keys::TOPICS.to_string()]),
+            defaults: HashMap::new(),
+        }
+    }
+}
+
+impl Normalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn unordered_list(mut self, key: impl Into<String>) -> Self {
+        self.unordered_lists.insert(key.into());
+        self
+    }
+
+    pub fn defaults(mut self, defaults: HashMap<String, String>) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    fn value(&self, key: &str, value: &str) -> String {
+        let value = if self.trim { value.trim() } else { value };
+        if self.unordered_lists.contains(key) {
+            let mut items: Vec<&str> = value
+                .split(',')
+                .map(|item| if self.trim { item.trim() } else { item })
+                .filter(|item| !item.is_empty())
+                .collect();
+            items.sort_unstable();
+            return items.join(",");
+        }
+        if self.canonical_numbers && NUMBER.is_match(value) {
+            let integer = !value.contains(['.', 'e', 'E']);
+            if let Ok(number) = value.parse::<i64>() {
+                return number.to_string();
+            }
+            // integers beyond i64 would lose precision as floats
+            if let (false, Ok(number)) = (integer, value.parse::<f64>()) {
+                if number.is_finite() {
+                    return number.to_string();
+                }
+            }
+        }
+        value.to_string()
+    }
+
+    pub fn normalize(&self, config: &ConnectorConfig) -> ConnectorConfig {
+        config
+            .iter()
+            .map(|(key, value)| (key.clone(), self.value(key, value)))
+            .filter(|(key, value)| {
+                KEPT.contains(&key.as_str())
+                    || self
+                        .defaults
+                        .get(key)
+                        .is_none_or(|default| self.value(key, default) != *value)
+            })
+            .collect()
+    }
+
+    /// Whether the configurations only differ cosmetically
+    pub fn equivalent(&self, a: &ConnectorConfig, b: &ConnectorConfig) -> bool {
+        self.normalize(a) == self.normalize(b)
+    }
+
+    /// Differences left once both configurations are normalized
+    pub fn diff(&self, from: &ConnectorConfig, to: &ConnectorConfig) -> ConfigDiff {
+        self.normalize(from).diff(&self.normalize(to))
+    }
+}
+
+impl Connect {
+    /// Default values of the keys of the plugin of the configuration, connector level keys such as
+    /// `tasks.max` included, as reported by the validation endpoint
+    pub async fn plugin_defaults(
+        &self,
+        config: &ConnectorConfig,
+    ) -> Result<HashMap<String, String>> {
+        let validation = self.validate_connector_config(config).await?;
+        Ok(validation
+            .configs
+            .into_iter()
+            .filter_map(|config| {
+                let default = config.definition.default_value?;
+                Some((config.definition.name, default))
+            })
+            .collect())
+    }
+
+    /// The default normalizer, also dropping the keys set to the defaults of the plugin of the
+    /// configuration
+    pub async fn normalizer(&self, config: &ConnectorConfig) -> Result<Normalizer> {
+        Ok(Normalizer::default().defaults(self.plugin_defaults(config).await?))
+    }
+}
@@ -0,0 +1,149 @@
+//! Connector offsets, as exposed by kafka-connect 3.6 onwards
+//! Source connectors define their own partition and offset formats, so the structures are
+//! generic and default to plain json values
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Offsets of every partition of a connector. This is also the body used to alter them
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ConnectorOffsets<P = Value, O = Value> {
+    pub offsets: Vec<ConnectorOffset<P, O>>,
+}
+
+/// Offset of a single partition. A null offset resets the partition when altering offsets
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ConnectorOffset<P = Value, O = Value> {
+    pub partition: P,
+    pub offset: Option<O>,
+}
+
+pub type SourceConnectorOffset<P = Value, O = Value> = ConnectorOffset<P, O>;
+
+/// Offset of a partition in the dynamic form, for connectors without typed offsets
+pub type JsonOffsets = ConnectorOffset<Value, Value>;
+
+/// Offsets of a connector in the dynamic form
+pub type Offsets = ConnectorOffsets<Value, Value>;
+
+impl Offsets {
+    pub fn iter(&self) -> impl Iterator<Item = &JsonOffsets> {
+        self.offsets.iter()
+    }
+
+    /// Offsets of the partitions of a topic, as set by sink connectors in `kafka_topic`
+    pub fn by_topic<'a>(&'a self, topic: &'a str) -> impl Iterator<Item = &'a JsonOffsets> {
+        self.by_partition_key("kafka_topic", topic)
+    }
+
+    /// Offsets of the partitions whose key holds the value, such as `server` for Debezium
+    pub fn by_partition_key<'a>(
+        &'a self,
+        key: &'a str,
+        value: impl Into<Value>,
+    ) -> impl Iterator<Item = &'a JsonOffsets> {
+        let value = value.into();
+        self.offsets
+            .iter()
+            .filter(move |offset| offset.partition.get(key) == Some(&value))
+    }
+}
+
+/// Body of a request altering offsets, in the dynamic form. Reset entries carry a null offset,
+/// which clears the offset of the partition
+#[derive(Clone, Debug, Default)]
+pub struct OffsetsRequestBuilder {
+    offsets: Vec<JsonOffsets>,
+}
+
+impl OffsetsRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offset of a sink connector, the next record it consumes from the topic partition
+    pub fn sink(mut self, topic: impl Into<String>, partition: i32, offset: i64) -> Self {
+        self.offsets.push(ConnectorOffset {
+            partition: sink_partition(topic.into(), partition),
+            offset: Some(serde_json::json!({ "kafka_offset": offset })),
+        });
+        self
+    }
+
+    /// Clears the offset of a sink connector for the topic partition
+    pub fn sink_reset(mut self, topic: impl Into<String>, partition: i32) -> Self {
+        self.offsets.push(ConnectorOffset {
+            partition: sink_partition(topic.into(), partition),
+            offset: None,
+        });
+        self
+    }
+
+    /// Offset of a source connector, both in the format the connector defines
+    pub fn source(mut self, partition: impl Into<Value>, offset: impl Into<Value>) -> Self {
+        self.offsets.push(ConnectorOffset {
+            partition: partition.into(),
+            offset: Some(offset.into()),
+        });
+        self
+    }
+
+    /// Clears the offset of a source connector for the partition
+    pub fn source_reset(mut self, partition: impl Into<Value>) -> Self {
+        self.offsets.push(ConnectorOffset {
+            partition: partition.into(),
+            offset: None,
+        });
+        self
+    }
+
+    /// Fails when there is no entry, when a partition is not an object or appears twice
+    pub fn build(self) -> Result<Offsets> {
+        if self.offsets.is_empty() {
+            anyhow::bail!("The request must alter at least one offset");
+        }
+        for (index, entry) in self.offsets.iter().enumerate() {
+            if !entry.partition.is_object() {
+                anyhow::bail!("Partition {} is not an object", entry.partition);
+            }
+            if self.offsets[..index]
+                .iter()
+                .any(|other| other.partition == entry.partition)
+            {
+                anyhow::bail!("Partition {} appears more than once", entry.partition);
+            }
+        }
+        Ok(ConnectorOffsets {
+            offsets: self.offsets,
+        })
+    }
+}
+
+fn sink_partition(topic: String, partition: i32) -> Value {
+    serde_json::json!({ "kafka_topic": topic, "kafka_partition": partition })
+}
+
+/// Partition of a sink connector, which is a kafka topic partition
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SinkPartition {
+    pub kafka_topic: String,
+    pub kafka_partition: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SinkOffset {
+    pub kafka_offset: i64,
+}
+
+pub type SinkConnectorOffset = ConnectorOffset<SinkPartition, SinkOffset>;
+
+/// Response to the requests altering or resetting offsets
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OffsetsMessage {
+    pub message: String,
+}
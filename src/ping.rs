@@ -0,0 +1,46 @@
+//! Reachability probes of a worker, for health checks and for choosing between workers
+//! A ping is a single GET of `/`, never retried, which gives up after a short timeout
+use crate::Connect;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Timeout of [`Connect::ping`]
+pub const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Outcome of a ping
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Ping {
+    /// Whether the worker answered successfully within the timeout
+    pub reachable: bool,
+    /// Time until the answer, or until giving up
+    pub latency: Duration,
+    /// Why the worker is not reachable
+    pub error: Option<String>,
+}
+
+impl Connect {
+    /// Pings the worker with a timeout of 2 seconds
+    pub async fn ping(&self) -> Ping {
+        self.ping_timeout(DEFAULT_PING_TIMEOUT).await
+    }
+
+    pub async fn ping_timeout(&self, timeout: Duration) -> Ping {
+        let started = Instant::now();
+        let request = self
+            .probe
+            .get(format!("{}/", self.address))
+            .timeout(timeout)
+            .send();
+        let error = match tokio::time::timeout(timeout, request).await {
+            Ok(Ok(response)) if response.status().is_success() => None,
+            Ok(Ok(response)) => Some(format!("The worker answered {}", response.status())),
+            Ok(Err(err)) => Some(err.to_string()),
+            Err(_) => Some(format!("No answer within {:?}", timeout)),
+        };
+        Ping {
+            reachable: error.is_none(),
+            latency: started.elapsed(),
+            error,
+        }
+    }
+}
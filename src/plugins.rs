@@ -0,0 +1,131 @@
+//! Plugins installed on a cluster, and the preflight checking them before creating connectors
+//! A connector whose class is not installed is rejected with an internal error that does not
+//! name the class. With a [`PluginPreflight`] set on the client, connectors are only created once
+//! their class is found among the plugins, at the minimum version when one is required, failing
+//! with [`ConnectError::PluginMissing`] otherwise
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::error::ConnectError;
+use crate::models::PluginInfo;
+use crate::Connect;
+use anyhow::Result;
+use semver::Version;
+use std::collections::HashMap;
+
+/// Version of a plugin, lenient with the versions that are not semver, such as `2.5.0.Final`
+/// or `10.7`. Missing components are zeros
+pub fn plugin_version(version: &str) -> Option<Version> {
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .take_while(|part| !part.is_empty())
+        .map(str::parse::<u64>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().and_then(Result::ok).unwrap_or_default();
+    let patch = parts.next().and_then(Result::ok).unwrap_or_default();
+    Some(Version::new(major, minor, patch))
+}
+
+/// Minimum versions of the plugins, checked before creating connectors
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginPreflight {
+    min_versions: HashMap<String, String>,
+}
+
+impl PluginPreflight {
+    /// Only checks that the classes are installed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn min_version(mut self, class: impl Into<String>, version: impl Into<String>) -> Self {
+        self.min_versions.insert(class.into(), version.into());
+        self
+    }
+
+    /// Minimum version of the class, either of them possibly being an alias of the other
+    fn min_version_of(&self, class: &str) -> Option<&str> {
+        self.min_versions
+            .iter()
+            .find(|(key, _)| refers_to(key, class) || refers_to(class, key))
+            .map(|(_, version)| version.as_str())
+    }
+}
+
+/// Whether the name refers to the class, as kafka-connect accepts in `connector.class` the fully
+/// qualified name of the class, its simple name, and its simple name without `Connector`
+fn refers_to(class: &str, name: &str) -> bool {
+    let simple = class.rsplit('.').next().unwrap_or(class);
+    name == class || name == simple || simple.strip_suffix("Connector") == Some(name)
+}
+
+/// Whether the plugin is the class, at the minimum version when given. Plugins without a
+/// readable version do not meet any minimum
+fn satisfies(plugin: &PluginInfo, class: &str, min_version: Option<&str>) -> bool {
+    if !refers_to(&plugin.class, class) {
+        return false;
+    }
+    let Some(min_version) = min_version else {
+        return true;
+    };
+    match (
+        plugin.version.as_deref().and_then(plugin_version),
+        plugin_version(min_version),
+    ) {
+        (Some(version), Some(min_version)) => version >= min_version,
+        _ => false,
+    }
+}
+
+impl Connect {
+    /// Connector plugins installed on the cluster
+    pub async fn connector_plugins(&self) -> Result<Vec<PluginInfo>> {
+        let response = self
+            .client
+            .get(format!("{}/connector-plugins", self.address))
+            .send()
+            .await?;
+        self.parse(response).await
+    }
+
+    /// The installed plugin of the class or of one of its aliases, failing with [`ConnectError::PluginMissing`] when it is
+    /// not installed or older than the minimum version
+    pub async fn require_plugin(
+        &self,
+        class: &str,
+        min_version: Option<&str>,
+    ) -> Result<PluginInfo> {
+        let plugins = self.connector_plugins().await?;
+        if let Some(plugin) = plugins
+            .iter()
+            .find(|plugin| satisfies(plugin, class, min_version))
+        {
+            return Ok(plugin.clone());
+        }
+        let mut installed: Vec<String> = plugins
+            .into_iter()
+            .map(|plugin| match plugin.version {
+                Some(version) => format!("{} {}", plugin.class, version),
+                None => plugin.class,
+            })
+            .collect();
+        installed.sort();
+        anyhow::bail!(ConnectError::PluginMissing {
+            class: class.to_string(),
+            min_version: min_version.map(str::to_string),
+            installed,
+        })
+    }
+
+    /// Runs the preflight of the client on the configuration of a new connector
+    pub(crate) async fn preflight_plugin(&self, config: &ConnectorConfig) -> Result<()> {
+        let (Some(preflight), Some(class)) = (
+            &self.plugin_preflight,
+            config.get_str(config_keys::CONNECTOR_CLASS),
+        ) else {
+            return Ok(());
+        };
+        self.require_plugin(class, preflight.min_version_of(class))
+            .await?;
+        Ok(())
+    }
+}
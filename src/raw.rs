@@ -0,0 +1,194 @@
+//! Raw response bodies, deserialized with borrows instead of owned strings
+//! Meant for monitoring loops polling large clusters, where allocating every name, worker id and
+//! config value of each poll adds up. The borrowed structures only hold what such loops look at
+//!
+//! The `_raw` methods return untyped json instead, for fields of newer kafka-connect versions
+//! that are not modeled yet
+use crate::json;
+use crate::models::Status;
+use crate::Connect;
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+
+/// Body of a response, kept around so that deserialized values can borrow from it
+#[derive(Clone, Debug)]
+pub struct RawResponse {
+    body: Vec<u8>,
+}
+
+impl RawResponse {
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Deserializes the body into any type, which may borrow from it.
+    /// Always uses serde_json, as simd-json cannot borrow from an immutable buffer
+    pub fn parse<'a, T: Deserialize<'a>>(&'a self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+
+    /// Parses the body of [`Connect::connectors_borrowed`]
+    pub fn connectors(&self) -> Result<HashMap<StrRef<'_>, ConnectorRef<'_>>> {
+        self.parse()
+    }
+}
+
+/// String borrowed from the body, unless it contains escaped characters
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(transparent)]
+pub struct StrRef<'a>(#[serde(borrow)] pub Cow<'a, str>);
+
+impl Deref for StrRef<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for StrRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Borrowed counterpart of [`crate::models::Connector`]
+#[derive(Clone, Debug, Deserialize)]
+pub struct ConnectorRef<'a> {
+    #[serde(borrow)]
+    pub info: Option<ConnectorInfoRef<'a>>,
+    #[serde(borrow)]
+    pub status: Option<ConnectorStatusRef<'a>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ConnectorInfoRef<'a> {
+    #[serde(borrow)]
+    pub name: StrRef<'a>,
+    #[serde(borrow)]
+    pub config: HashMap<StrRef<'a>, StrRef<'a>>,
+    #[serde(rename = "type", borrow)]
+    pub kind: StrRef<'a>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ConnectorStatusRef<'a> {
+    #[serde(borrow)]
+    pub name: StrRef<'a>,
+    #[serde(borrow)]
+    pub connector: StateRef<'a>,
+    #[serde(borrow)]
+    pub tasks: Vec<TaskStateRef<'a>>,
+    #[serde(rename = "type", borrow)]
+    pub kind: StrRef<'a>,
+}
+
+impl ConnectorStatusRef<'_> {
+    /// A connector is healthy when both the connector and all of its tasks are running
+    pub fn is_healthy(&self) -> bool {
+        self.connector.state == Status::Running
+            && self.tasks.iter().all(|task| task.state == Status::Running)
+    }
+}
+
+/// State of a connector. Traces contain escaped characters, so they are usually owned
+#[derive(Clone, Debug, Deserialize)]
+pub struct StateRef<'a> {
+    pub state: Status,
+    #[serde(borrow)]
+    pub worker_id: StrRef<'a>,
+    #[serde(borrow)]
+    pub trace: Option<StrRef<'a>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TaskStateRef<'a> {
+    pub id: u64,
+    pub state: Status,
+    #[serde(borrow)]
+    pub worker_id: StrRef<'a>,
+    #[serde(borrow)]
+    pub trace: Option<StrRef<'a>>,
+}
+
+/// Borrowed counterpart of [`crate::offsets::SinkPartition`], for use with
+/// [`crate::offsets::ConnectorOffsets`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct SinkPartitionRef<'a> {
+    #[serde(borrow)]
+    pub kafka_topic: StrRef<'a>,
+    pub kafka_partition: i32,
+}
+
+impl Connect {
+    /// Same as [`Connect::connectors`], returning the body for [`RawResponse::connectors`]
+    pub async fn connectors_borrowed(
+        &self,
+        expand_status: bool,
+        expand_info: bool,
+    ) -> Result<RawResponse> {
+        let endpoint = self.connectors_endpoint(expand_status, expand_info)?;
+        let response = self.client.get(endpoint).send().await?;
+        let body = json::read_body(response, self.max_response_size).await?;
+        Ok(RawResponse { body })
+    }
+
+    /// Same as [`Connect::connector_offsets`], returning the body to parse into
+    /// [`crate::offsets::ConnectorOffsets`] of borrowed partitions and offsets
+    pub async fn connector_offsets_borrowed(&self, connector: &str) -> Result<RawResponse> {
+        let response = self.offsets_response(connector).await?;
+        let body = json::read_body(response, self.max_response_size).await?;
+        Ok(RawResponse { body })
+    }
+}
+
+impl Connect {
+    /// Same as [`Connect::info`], as untyped json
+    pub async fn info_raw(&self) -> Result<Value> {
+        let response = self.client.get(format!("{}/", self.address)).send().await?;
+        self.parse(response).await
+    }
+
+    /// Same as [`Connect::connectors`], as untyped json
+    pub async fn connectors_raw(&self, expand_status: bool, expand_info: bool) -> Result<Value> {
+        let endpoint = self.connectors_endpoint(expand_status, expand_info)?;
+        let response = self.client.get(endpoint).send().await?;
+        self.parse(response).await
+    }
+
+    /// Same as [`Connect::connector_status`], as untyped json
+    pub async fn connector_status_raw(&self, connector: &str) -> Result<Value> {
+        self.connector_status_as(connector).await
+    }
+
+    /// Same as [`Connect::connector_config`], as untyped json
+    pub async fn connector_config_raw(&self, connector: &str) -> Result<Value> {
+        self.connector_config_as(connector).await
+    }
+
+    /// Same as [`Connect::connector_offsets`], as untyped json
+    pub async fn connector_offsets_raw(&self, connector: &str) -> Result<Value> {
+        let response = self.offsets_response(connector).await?;
+        self.parse(response).await
+    }
+
+    /// Same as [`Connect::loggers`], as untyped json
+    pub async fn loggers_raw(&self) -> Result<Value> {
+        let response = self
+            .client
+            .get(format!("{}/admin/loggers", self.address))
+            .send()
+            .await?;
+        self.parse(response).await
+    }
+
+    /// Same as [`Connect::logger`], as untyped json
+    pub async fn logger_raw(&self, logger: &str) -> Result<Value> {
+        self.logger_as(logger).await
+    }
+}
@@ -0,0 +1,165 @@
+//! Client restricted to reading the state of a cluster
+//! Dashboards and exporters can hold a [`ReadOnlyConnect`], which has no method to create,
+//! change, restart or delete connectors, so that a handle given to them statically cannot mutate
+//! the cluster. The credentials should still be limited on the server side, this only protects
+//! against mistakes in the code holding the handle
+use crate::capabilities::Capabilities;
+use crate::config::ConnectorConfig;
+use crate::filter::ConnectorFilter;
+use crate::models::{
+    ClusterInfo, ConfigInfos, Connector, ConnectorStatus, LoggerLevel, PluginInfo,
+};
+use crate::offsets::ConnectorOffsets;
+use crate::ping::Ping;
+use crate::snapshot::ClusterSnapshot;
+use crate::watch::{AdaptiveInterval, Watcher};
+use crate::workers::WorkerReport;
+use crate::Connect;
+use anyhow::Result;
+use futures::Stream;
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Read-only handle on a cluster, see [`Connect::read_only`]
+#[derive(Clone, Debug)]
+pub struct ReadOnlyConnect {
+    inner: Connect,
+}
+
+impl From<Connect> for ReadOnlyConnect {
+    fn from(inner: Connect) -> Self {
+        Self { inner }
+    }
+}
+
+impl Connect {
+    /// Handle on the same cluster that can only read it, sharing the connections of the client
+    pub fn read_only(&self) -> ReadOnlyConnect {
+        ReadOnlyConnect::from(self.clone())
+    }
+}
+
+impl ReadOnlyConnect {
+    /// Same client sending the headers with every request, see [`Connect::with_headers`]
+    pub fn with_headers(&self, headers: HeaderMap) -> ReadOnlyConnect {
+        self.inner.with_headers(headers).into()
+    }
+
+    pub async fn info(&self) -> Result<ClusterInfo> {
+        self.inner.info().await
+    }
+
+    pub async fn capabilities(&self) -> Result<Capabilities> {
+        self.inner.capabilities().await
+    }
+
+    pub async fn ping(&self) -> Ping {
+        self.inner.ping().await
+    }
+
+    pub async fn ping_timeout(&self, timeout: Duration) -> Ping {
+        self.inner.ping_timeout(timeout).await
+    }
+
+    pub async fn connector_names(&self) -> Result<Vec<String>> {
+        self.inner.connector_names().await
+    }
+
+    pub async fn connectors(
+        &self,
+        expand_status: bool,
+        expand_info: bool,
+    ) -> Result<HashMap<String, Connector>> {
+        self.inner.connectors(expand_status, expand_info).await
+    }
+
+    pub async fn connectors_stream(
+        &self,
+        expand_status: bool,
+        expand_info: bool,
+    ) -> Result<impl Stream<Item = Result<(String, Connector)>>> {
+        self.inner
+            .connectors_stream(expand_status, expand_info)
+            .await
+    }
+
+    pub async fn connectors_filtered(
+        &self,
+        filter: &ConnectorFilter,
+    ) -> Result<HashMap<String, Connector>> {
+        self.inner.connectors_filtered(filter).await
+    }
+
+    pub async fn connector_status(&self, connector: &str) -> Result<ConnectorStatus> {
+        self.inner.connector_status(connector).await
+    }
+
+    pub async fn connector_config(&self, connector: &str) -> Result<ConnectorConfig> {
+        self.inner.connector_config(connector).await
+    }
+
+    pub async fn connector_config_as<T: DeserializeOwned>(&self, connector: &str) -> Result<T> {
+        self.inner.connector_config_as(connector).await
+    }
+
+    pub async fn connector_offsets(&self, connector: &str) -> Result<ConnectorOffsets> {
+        self.inner.connector_offsets(connector).await
+    }
+
+    pub async fn connector_offsets_as<P, O>(
+        &self,
+        connector: &str,
+    ) -> Result<ConnectorOffsets<P, O>>
+    where
+        P: DeserializeOwned,
+        O: DeserializeOwned,
+    {
+        self.inner.connector_offsets_as(connector).await
+    }
+
+    pub async fn connector_active_topics(&self, connector: &str) -> Result<Option<Vec<String>>> {
+        self.inner.connector_active_topics(connector).await
+    }
+
+    pub async fn connector_plugins(&self) -> Result<Vec<PluginInfo>> {
+        self.inner.connector_plugins().await
+    }
+
+    /// Validation does not change the cluster, even though it is a PUT request
+    pub async fn validate_connector_config(&self, config: &ConnectorConfig) -> Result<ConfigInfos> {
+        self.inner.validate_connector_config(config).await
+    }
+
+    pub async fn loggers(&self) -> Result<HashMap<String, LoggerLevel>> {
+        self.inner.loggers().await
+    }
+
+    pub async fn logger(&self, logger: &str) -> Result<LoggerLevel> {
+        self.inner.logger(logger).await
+    }
+
+    pub async fn worker_report(&self, max_skew: usize) -> Result<WorkerReport> {
+        self.inner.worker_report(max_skew).await
+    }
+
+    pub async fn snapshot(&self) -> Result<ClusterSnapshot> {
+        self.inner.snapshot().await
+    }
+
+    /// Same as [`Connect::request`], restricted to GET requests
+    pub async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
+        self.inner
+            .request(reqwest::Method::GET, path, query, None)
+            .await
+    }
+
+    pub fn watcher(&self, interval: Duration) -> Watcher {
+        Watcher::new(self.inner.clone(), interval)
+    }
+
+    pub fn adaptive_watcher(&self, intervals: AdaptiveInterval) -> Watcher {
+        Watcher::adaptive(self.inner.clone(), intervals)
+    }
+}
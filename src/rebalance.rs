@@ -0,0 +1,152 @@
+//! Detection of rebalances, from 409 responses and from tasks moving between workers
+//! A healthy cluster rebalances when workers join or leave. A cluster rebalancing again and again
+//! answers most writes with 409 and keeps moving tasks around, which the monitor counts.
+//! Register it with [`crate::ConnectBuilder::middleware`] to see the responses, and feed it the
+//! updates of a [`crate::watch::Watcher`] to see the assignments
+use crate::watch::WatchUpdate;
+use async_trait::async_trait;
+use http::Extensions;
+use reqwest::{Request, Response, StatusCode};
+use reqwest_middleware::{Middleware, Next};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Rebalances kept to count the recent ones
+const HISTORY: usize = 100;
+
+/// Counters of a [`RebalanceMonitor`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RebalanceStats {
+    /// Responses with status 409
+    pub conflicts: u64,
+    /// Tasks seen on another worker than at the previous update
+    pub reassigned_tasks: u64,
+    /// Rebalances detected, signals closer than the quiet period counting as one
+    pub rebalances: u64,
+    /// Unix timestamp of the last signal of a rebalance, in seconds
+    pub last_rebalance: Option<u64>,
+}
+
+#[derive(Default)]
+struct State {
+    stats: RebalanceStats,
+    /// Start of the recent rebalances, in seconds since the epoch
+    history: VecDeque<u64>,
+    assignments: Option<BTreeMap<(String, u64), String>>,
+}
+
+/// Shared counters of the rebalances of a cluster. Clones count into the same counters
+#[derive(Clone)]
+pub struct RebalanceMonitor {
+    state: Arc<Mutex<State>>,
+    quiet: Duration,
+}
+
+impl Default for RebalanceMonitor {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+impl RebalanceMonitor {
+    /// Signals less than `quiet` apart belong to the same rebalance
+    pub fn new(quiet: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State::default())),
+            quiet,
+        }
+    }
+
+    pub fn stats(&self) -> RebalanceStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+
+    /// Rebalances started within the window, to alert on rebalance loops
+    pub fn rebalances_within(&self, window: Duration) -> usize {
+        let since = now().saturating_sub(window.as_secs());
+        let state = self.state.lock().unwrap();
+        state
+            .history
+            .iter()
+            .filter(|start| **start >= since)
+            .count()
+    }
+
+    fn signal(&self, state: &mut State) {
+        let at = now();
+        let ongoing = state
+            .stats
+            .last_rebalance
+            .is_some_and(|last| at.saturating_sub(last) < self.quiet.as_secs());
+        if !ongoing {
+            state.stats.rebalances += 1;
+            if state.history.len() == HISTORY {
+                state.history.pop_front();
+            }
+            state.history.push_back(at);
+        }
+        state.stats.last_rebalance = Some(at);
+    }
+
+    /// Counts the 409 responses of clients built without the monitor as middleware
+    pub fn observe_status(&self, status_code: StatusCode) {
+        if status_code != StatusCode::CONFLICT {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.stats.conflicts += 1;
+        self.signal(&mut state);
+    }
+
+    /// Compares the assignments of the tasks with the previous update
+    pub fn observe(&self, update: &WatchUpdate) {
+        let assignments: BTreeMap<(String, u64), String> = update
+            .statuses
+            .values()
+            .flat_map(|status| {
+                status
+                    .tasks
+                    .iter()
+                    .filter(|task| !task.worker_id.is_empty())
+                    .map(|task| ((status.name.clone(), task.id), task.worker_id.clone()))
+            })
+            .collect();
+        let mut state = self.state.lock().unwrap();
+        let moved = state.assignments.as_ref().map_or(0, |previous| {
+            assignments
+                .iter()
+                .filter(|(task, worker)| {
+                    previous.get(*task).is_some_and(|before| before != *worker)
+                })
+                .count()
+        });
+        state.assignments = Some(assignments);
+        if moved > 0 {
+            state.stats.reassigned_tasks += moved as u64;
+            self.signal(&mut state);
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for RebalanceMonitor {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let response = next.run(req, extensions).await?;
+        self.observe_status(response.status());
+        Ok(response)
+    }
+}
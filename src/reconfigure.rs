@@ -0,0 +1,63 @@
+//! Forced reconfiguration of the tasks of a connector
+//! Workers only ask a connector for new task configurations when its configuration changes or
+//! when it requests it itself. Some connectors never do after their first start, and keep running
+//! fewer tasks than `tasks.max` allows even once there is more work. Changing a key they ignore,
+//! [`config_keys::RECONFIGURED_AT`], makes the workers regenerate the tasks
+use crate::config_keys;
+use crate::models::{ConnectorInfo, Status};
+use crate::Connect;
+use anyhow::Result;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Connector running fewer tasks than its `tasks.max`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TaskShortfall {
+    pub connector: String,
+    pub tasks_max: u64,
+    pub running_tasks: usize,
+}
+
+impl Connect {
+    /// Writes the configuration again with a new [`config_keys::RECONFIGURED_AT`], which makes
+    /// the workers regenerate the tasks of the connector
+    pub async fn reconfigure_tasks(&self, connector: &str) -> Result<ConnectorInfo> {
+        let mut config = self.connector_config(connector).await?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        config.set(config_keys::RECONFIGURED_AT, now.to_string());
+        self.update_connector_config(connector, &config).await
+    }
+
+    /// Connectors running fewer tasks than `tasks.max`, sorted by name. Connectors with less work
+    /// than tasks, such as a JDBC source reading a single table, legitimately show up too
+    pub async fn task_shortfalls(&self) -> Result<Vec<TaskShortfall>> {
+        let connectors = self.connectors(true, true).await?;
+        let mut shortfalls = Vec::new();
+        for (name, connector) in connectors {
+            let (Some(info), Some(status)) = (connector.info, connector.status) else {
+                continue;
+            };
+            // paused or stopped connectors are expected to run no task
+            if status.connector.state != Status::Running {
+                continue;
+            }
+            let Some(tasks_max) = info.config.get_i64(config_keys::TASKS_MAX)? else {
+                continue;
+            };
+            let running_tasks = status
+                .tasks
+                .iter()
+                .filter(|task| task.state == Status::Running)
+                .count();
+            if (running_tasks as i64) < tasks_max {
+                shortfalls.push(TaskShortfall {
+                    connector: name,
+                    tasks_max: tasks_max as u64,
+                    running_tasks,
+                });
+            }
+        }
+        shortfalls.sort_by(|a, b| a.connector.cmp(&b.connector));
+        Ok(shortfalls)
+    }
+}
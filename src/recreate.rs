@@ -0,0 +1,71 @@
+//! Clean re-registration of a connector, keeping its position if asked
+//! Kafka-connect keeps the offsets of a deleted connector, in the offsets topic for a source and
+//! in its consumer group for a sink, so a connector created again under the same name resumes
+//! from them. The connector is stopped and its offsets exported, then it is deleted and created
+//! again, stopped, so that the offsets can be restored, or reset, before any of its tasks starts
+use crate::capabilities::Feature;
+use crate::config::ConnectorConfig;
+use crate::models::{ConnectorInfo, Status};
+use crate::Connect;
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+/// Time given to the stop and to the delete
+const STEP_TIMEOUT: Duration = Duration::from_secs(60);
+
+impl Connect {
+    /// Deletes the connector and creates it again with the configuration, which needs kafka 3.7.
+    /// With `preserve_offsets`, its offsets are exported beforehand and restored before it
+    /// starts, otherwise they are reset and it starts over. The connector is brought back to the
+    /// state it was in, a paused or stopped one staying so
+    pub async fn recreate_connector(
+        &self,
+        name: &str,
+        new_config: &ConnectorConfig,
+        preserve_offsets: bool,
+    ) -> Result<ConnectorInfo> {
+        // checked before deleting anything
+        self.gate(Feature::InitialState).await?;
+        let previous = self.connector_status(name).await?.connector.state;
+        let offsets = if preserve_offsets {
+            self.stop_and_wait(name, STEP_TIMEOUT).await?;
+            Some(self.connector_offsets(name).await?)
+        } else {
+            None
+        };
+        self.delete_and_wait(name, STEP_TIMEOUT).await?;
+        let info = self
+            .create_connector_with_state(name, new_config, Status::Stopped)
+            .await?;
+        // the connector stays stopped meanwhile, so the exported offsets are not lost
+        match offsets {
+            Some(offsets) if !offsets.offsets.is_empty() => {
+                self.alter_connector_offsets(name, &offsets)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Connector {} was recreated stopped, its offsets could not be restored: {}",
+                            name,
+                            serde_json::to_string(&offsets).unwrap_or_default()
+                        )
+                    })?;
+            }
+            Some(_) => {}
+            None => {
+                self.reset_connector_offsets(name).await.with_context(|| {
+                    format!(
+                        "Connector {} was recreated stopped, its offsets could not be reset",
+                        name
+                    )
+                })?;
+            }
+        }
+        match previous {
+            Status::Stopped => {}
+            Status::Paused => self.pause_connector(name).await?,
+            // failed connectors are meant to run as well
+            _ => self.resume_connector(name).await?,
+        }
+        Ok(info)
+    }
+}
@@ -0,0 +1,108 @@
+//! Recycling of the connections of long lived clients
+//! The connection pool keeps talking to the address a name resolved to when the connections were
+//! opened, so a service moving behind its DNS name goes unnoticed until the client restarts.
+//! Requests are sent with an HTTP client replaced once it reached its maximum lifetime, whose new
+//! connections resolve the name again. A request failing on a connection the server closed is
+//! sent again at once on a new client, rather than waiting for the backoff of the retries
+use async_trait::async_trait;
+use http::Extensions;
+use reqwest::{Client, Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use reqwest_retry::{default_on_request_failure, Retryable};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub(crate) type BuildClient = Arc<dyn Fn() -> anyhow::Result<Client> + Send + Sync>;
+
+/// Sends the requests itself instead of passing them on, so it must be the last middleware
+pub(crate) struct Recycling {
+    build: BuildClient,
+    max_lifetime: Option<Duration>,
+    retry_stale: bool,
+    current: Mutex<(Instant, Client)>,
+}
+
+impl Recycling {
+    pub(crate) fn new(
+        build: BuildClient,
+        max_lifetime: Option<Duration>,
+        retry_stale: bool,
+    ) -> anyhow::Result<Self> {
+        let client = build()?;
+        Ok(Self {
+            build,
+            max_lifetime,
+            retry_stale,
+            current: Mutex::new((Instant::now(), client)),
+        })
+    }
+
+    fn middleware_error(error: anyhow::Error) -> reqwest_middleware::Error {
+        reqwest_middleware::Error::Middleware(error)
+    }
+
+    /// The current client, replaced when it outlived its lifetime. Connections of the previous one
+    /// close once its requests complete
+    fn client(&self) -> reqwest_middleware::Result<Client> {
+        let mut current = self.current.lock().unwrap();
+        if self
+            .max_lifetime
+            .is_some_and(|lifetime| current.0.elapsed() >= lifetime)
+        {
+            *current = (
+                Instant::now(),
+                (self.build)().map_err(Self::middleware_error)?,
+            );
+        }
+        Ok(current.1.clone())
+    }
+
+    fn renew(&self) -> reqwest_middleware::Result<Client> {
+        let client = (self.build)().map_err(Self::middleware_error)?;
+        *self.current.lock().unwrap() = (Instant::now(), client.clone());
+        Ok(client)
+    }
+}
+
+/// Whether the connection was closed or reset under the request, which happens to connections
+/// idle for longer than the server or a proxy keeps them
+fn is_stale(error: &reqwest_middleware::Error) -> bool {
+    match error {
+        reqwest_middleware::Error::Reqwest(error) if error.is_timeout() || error.is_connect() => {
+            false
+        }
+        _ => matches!(
+            default_on_request_failure(error),
+            Some(Retryable::Transient)
+        ),
+    }
+}
+
+#[async_trait]
+impl Middleware for Recycling {
+    async fn handle(
+        &self,
+        req: Request,
+        _extensions: &mut Extensions,
+        _next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let retry = if self.retry_stale {
+            req.try_clone()
+        } else {
+            None
+        };
+        let result = self
+            .client()?
+            .execute(req)
+            .await
+            .map_err(reqwest_middleware::Error::Reqwest);
+        match (result, retry) {
+            (Err(error), Some(retry)) if is_stale(&error) => self
+                .renew()?
+                .execute(retry)
+                .await
+                .map_err(reqwest_middleware::Error::Reqwest),
+            (result, _) => result,
+        }
+    }
+}
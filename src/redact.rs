@@ -0,0 +1,107 @@
+//! Redaction of the secrets of configurations
+//! Keys matching the patterns of the installed [`Redactor`] have their values masked in masked
+//! snapshots, in the `Debug` output of configurations, and in the display of diffs and merge
+//! conflicts. Patterns are case insensitive globs where `*` matches anything
+use crate::config::ConnectorConfig;
+use std::sync::{LazyLock, RwLock};
+
+/// Value replacing secrets
+pub const MASK: &str = "********";
+
+pub const DEFAULT_PATTERNS: &[&str] =
+    &["*password*", "*secret*", "*sasl.jaas.config*", "*api.key*"];
+
+static INSTALLED: LazyLock<RwLock<Redactor>> = LazyLock::new(|| RwLock::new(Redactor::default()));
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Redactor {
+    patterns: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new(DEFAULT_PATTERNS.iter().copied())
+    }
+}
+
+/// Whether the glob matches the whole text, `*` matching any sequence
+fn matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // no `*`, the pattern must be the whole text
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+impl Redactor {
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| pattern.into().to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into().to_ascii_lowercase());
+        self
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn is_secret(&self, key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+        self.patterns.iter().any(|pattern| matches(pattern, &key))
+    }
+
+    /// The value, or the mask when the key holds a secret
+    pub fn value<'a>(&self, key: &str, value: &'a str) -> &'a str {
+        if self.is_secret(key) {
+            MASK
+        } else {
+            value
+        }
+    }
+
+    /// Copy of the configuration with every secret masked
+    pub fn redact(&self, config: &ConnectorConfig) -> ConnectorConfig {
+        config
+            .iter()
+            .map(|(key, value)| (key.clone(), self.value(key, value).to_string()))
+            .collect()
+    }
+
+    /// Makes the redactor the one used across the crate, in place of the default patterns
+    pub fn install(self) {
+        *INSTALLED.write().unwrap() = self;
+    }
+}
+
+/// The redactor used across the crate
+pub fn installed() -> Redactor {
+    INSTALLED.read().unwrap().clone()
+}
+
+/// Whether the installed redactor considers the key a secret
+pub fn is_secret_key(key: &str) -> bool {
+    INSTALLED.read().unwrap().is_secret(key)
+}
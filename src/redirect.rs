@@ -0,0 +1,147 @@
+//! Redirects followed by the client
+//! Gateways may answer with a redirect to a canonical host. The client follows up to 10 redirects
+//! by default, sending the `Authorization` header again only to the origin of the first request,
+//! as browsers do. A [`RedirectPolicy`] set with `ConnectBuilder::redirect_policy` changes how
+//! many are followed and whether the credentials are sent again to the same origin. Redirects to
+//! another origin never receive the credentials
+use async_trait::async_trait;
+use http::Extensions;
+use reqwest::{header, Method, Request, Response, StatusCode, Url};
+use reqwest_middleware::{Middleware, Next};
+
+/// Headers that are never sent to another origin
+const SENSITIVE: [header::HeaderName; 3] = [
+    header::AUTHORIZATION,
+    header::PROXY_AUTHORIZATION,
+    header::COOKIE,
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedirectPolicy {
+    max_redirects: usize,
+    same_origin_authorization: bool,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self {
+            max_redirects: 10,
+            same_origin_authorization: true,
+        }
+    }
+}
+
+impl RedirectPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the redirects as they are, without following them
+    pub fn none() -> Self {
+        Self::default().max_redirects(0)
+    }
+
+    /// Redirects followed for a request, before it fails
+    pub fn max_redirects(mut self, max: usize) -> Self {
+        self.max_redirects = max;
+        self
+    }
+
+    /// Whether redirects to the origin of the request receive its `Authorization` header
+    pub fn same_origin_authorization(mut self, propagate: bool) -> Self {
+        self.same_origin_authorization = propagate;
+        self
+    }
+}
+
+fn same_origin(a: &Url, b: &Url) -> bool {
+    a.scheme() == b.scheme()
+        && a.host_str() == b.host_str()
+        && a.port_or_known_default() == b.port_or_known_default()
+}
+
+/// Follows the redirects itself, so it must run after the middleware setting the credentials
+pub(crate) struct Redirects(pub(crate) RedirectPolicy);
+
+impl Redirects {
+    /// The request to send for the redirect, `None` when the response is not one to follow
+    fn follow_up(
+        &self,
+        previous: &Request,
+        origin: &Url,
+        response: &Response,
+    ) -> reqwest_middleware::Result<Option<Request>> {
+        let status = response.status();
+        if !status.is_redirection() || self.0.max_redirects == 0 {
+            return Ok(None);
+        }
+        let Some(location) = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+        else {
+            return Ok(None);
+        };
+        let url = response
+            .url()
+            .join(location)
+            .map_err(|e| anyhow::anyhow!("Invalid redirect to {}: {}", location, e))?;
+        // as reqwest does, 303 and the 301 and 302 of POST requests become GET requests
+        let keeps_method = match status {
+            StatusCode::SEE_OTHER => false,
+            StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND => previous.method() != Method::POST,
+            _ => true,
+        };
+        let mut next = if keeps_method {
+            previous.try_clone().ok_or_else(|| {
+                anyhow::anyhow!("The request to {} cannot be sent again", previous.url())
+            })?
+        } else {
+            let mut next = Request::new(Method::GET, url.clone());
+            *next.headers_mut() = previous.headers().clone();
+            next.headers_mut().remove(header::CONTENT_TYPE);
+            next.headers_mut().remove(header::CONTENT_LENGTH);
+            next
+        };
+        *next.url_mut() = url;
+        if !same_origin(origin, next.url()) {
+            for name in &SENSITIVE {
+                next.headers_mut().remove(name);
+            }
+        } else if !self.0.same_origin_authorization {
+            next.headers_mut().remove(header::AUTHORIZATION);
+        }
+        Ok(Some(next))
+    }
+}
+
+#[async_trait]
+impl Middleware for Redirects {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let origin = req.url().clone();
+        let mut previous = req.try_clone();
+        let mut response = next.clone().run(req, extensions).await?;
+        let mut redirects = 0;
+        while let Some(request) = &previous {
+            let Some(follow_up) = self.follow_up(request, &origin, &response)? else {
+                break;
+            };
+            if redirects == self.0.max_redirects {
+                return Err(anyhow::anyhow!(
+                    "Too many redirects, the last one to {}",
+                    follow_up.url()
+                )
+                .into());
+            }
+            redirects += 1;
+            previous = follow_up.try_clone();
+            response = next.clone().run(follow_up, extensions).await?;
+        }
+        Ok(response)
+    }
+}
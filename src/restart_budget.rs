@@ -0,0 +1,205 @@
+//! Limits on automatic restarts, so that healing never turns into a restart storm
+//! A connector failing again right after each restart is broken, and restarting it further only
+//! adds load on the cluster. Once it used up its budget, it is given up on for a cooldown. A global
+//! budget bounds the restarts of the whole cluster within the window. Pass the budget to
+//! [`crate::supervisor::SupervisorPolicy`], or to [`crate::bulk::RestartFailedOptions`] and keep
+//! it across sweeps
+//!
+//! With a backoff, consecutive restarts of a connector are spaced out, so a persistent failure
+//! consumes the budget slowly. The backoff resets once the connector stayed healthy for a while
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Backoff of [`RestartBudget::backoff`] suited to most remediations
+pub const DEFAULT_BACKOFF: [Duration; 4] = [
+    Duration::from_secs(60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(15 * 60),
+    Duration::from_secs(60 * 60),
+];
+
+/// Whether a restart fits in the budget
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Admission {
+    Allowed,
+    /// The connector used up its budget with this failure, and cools down from now on
+    GivenUp,
+    /// The connector was given up on, for this much longer
+    CoolingDown(Duration),
+    /// The connector was restarted recently, the next restart is allowed in this much time
+    BackingOff(Duration),
+    /// The cluster used up the global budget
+    GlobalExhausted,
+}
+
+#[derive(Default)]
+struct State {
+    restarts: HashMap<String, VecDeque<Instant>>,
+    global: VecDeque<Instant>,
+    cooldowns: HashMap<String, Instant>,
+    /// Consecutive restarts of each connector, and the time of the last one
+    attempts: HashMap<String, (usize, Instant)>,
+    healthy_since: HashMap<String, Instant>,
+}
+
+/// Restarts allowed per connector and for the whole cluster within a sliding window. Clones share
+/// the same counters
+#[derive(Clone)]
+pub struct RestartBudget {
+    per_connector: usize,
+    global: Option<usize>,
+    window: Duration,
+    cooldown: Duration,
+    backoff: Vec<Duration>,
+    healthy_after: Duration,
+    state: Arc<Mutex<State>>,
+}
+
+impl std::fmt::Debug for RestartBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RestartBudget")
+            .field("per_connector", &self.per_connector)
+            .field("global", &self.global)
+            .field("window", &self.window)
+            .field("cooldown", &self.cooldown)
+            .field("backoff", &self.backoff)
+            .field("healthy_after", &self.healthy_after)
+            .finish()
+    }
+}
+
+fn prune(restarts: &mut VecDeque<Instant>, window: Duration) {
+    while restarts
+        .front()
+        .is_some_and(|restart| restart.elapsed() >= window)
+    {
+        restarts.pop_front();
+    }
+}
+
+impl RestartBudget {
+    /// At most `per_connector` restarts of a connector within the window. A connector given up on
+    /// cools down for the length of the window
+    pub fn new(per_connector: usize, window: Duration) -> Self {
+        Self {
+            per_connector,
+            global: None,
+            window,
+            cooldown: window,
+            backoff: Vec::new(),
+            healthy_after: window,
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// At most `max` restarts across the cluster within the window
+    pub fn global(mut self, max: usize) -> Self {
+        self.global = Some(max);
+        self
+    }
+
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Delays between consecutive restarts of a connector, the last one repeating, such as
+    /// [`DEFAULT_BACKOFF`]
+    pub fn backoff(mut self, steps: impl IntoIterator<Item = Duration>) -> Self {
+        self.backoff = steps.into_iter().collect();
+        self
+    }
+
+    /// How long a connector must stay healthy for its backoff to reset, the window by default
+    pub fn healthy_after(mut self, healthy_after: Duration) -> Self {
+        self.healthy_after = healthy_after;
+        self
+    }
+
+    /// Checks whether the connector may be restarted, counting the restart when it may
+    pub fn admit(&self, connector: &str) -> Admission {
+        let mut state = self.state.lock().unwrap();
+        state.healthy_since.remove(connector);
+        if let Some(since) = state.cooldowns.get(connector) {
+            if since.elapsed() < self.cooldown {
+                return Admission::CoolingDown(self.cooldown - since.elapsed());
+            }
+            state.cooldowns.remove(connector);
+        }
+        if let Some((attempts, last)) = state.attempts.get(connector) {
+            let step = (*attempts).min(self.backoff.len()).checked_sub(1);
+            if let Some(delay) = step.map(|step| self.backoff[step]) {
+                if last.elapsed() < delay {
+                    return Admission::BackingOff(delay - last.elapsed());
+                }
+            }
+        }
+        let restarts = state.restarts.entry(connector.to_string()).or_default();
+        prune(restarts, self.window);
+        if restarts.len() >= self.per_connector {
+            state.restarts.remove(connector);
+            state
+                .cooldowns
+                .insert(connector.to_string(), Instant::now());
+            return Admission::GivenUp;
+        }
+        prune(&mut state.global, self.window);
+        if self.global.is_some_and(|max| state.global.len() >= max) {
+            return Admission::GlobalExhausted;
+        }
+        let now = Instant::now();
+        state.global.push_back(now);
+        state
+            .restarts
+            .entry(connector.to_string())
+            .or_default()
+            .push_back(now);
+        let attempts = state
+            .attempts
+            .get(connector)
+            .map_or(0, |(attempts, _)| *attempts);
+        state
+            .attempts
+            .insert(connector.to_string(), (attempts + 1, now));
+        Admission::Allowed
+    }
+
+    /// Records that the connector is healthy, which resets its backoff once it lasted
+    pub fn observe_healthy(&self, connector: &str) {
+        let mut state = self.state.lock().unwrap();
+        if !state.attempts.contains_key(connector) {
+            return;
+        }
+        let since = *state
+            .healthy_since
+            .entry(connector.to_string())
+            .or_insert_with(Instant::now);
+        if since.elapsed() >= self.healthy_after {
+            state.attempts.remove(connector);
+            state.healthy_since.remove(connector);
+        }
+    }
+
+    /// Connectors given up on and still cooling down, sorted
+    pub fn cooling_down(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let mut connectors: Vec<String> = state
+            .cooldowns
+            .iter()
+            .filter(|(_, since)| since.elapsed() < self.cooldown)
+            .map(|(connector, _)| connector.clone())
+            .collect();
+        connectors.sort();
+        connectors
+    }
+
+    /// Gives the connector its budget back, once it was fixed
+    pub fn forgive(&self, connector: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.restarts.remove(connector);
+        state.cooldowns.remove(connector);
+        state.attempts.remove(connector);
+        state.healthy_since.remove(connector);
+    }
+}
@@ -0,0 +1,118 @@
+//! Which failures the client retries
+//! Requests failing with a transient error are retried with an exponential backoff. By default
+//! these are the statuses 408, 429 and 5xx, timeouts, connection failures and connections cut
+//! while reading the response, as in `reqwest-retry`. A [`RetryClassification`] set with
+//! `ConnectBuilder::retry_classification` changes them, e.g. to retry the 502 of a proxy while
+//! surfacing the 500 of kafka-connect immediately
+use reqwest::StatusCode;
+use reqwest_retry::{default_on_request_failure, Retryable, RetryableStrategy};
+use std::collections::BTreeSet;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryClassification {
+    statuses: BTreeSet<u16>,
+    timeouts: bool,
+    connect_errors: bool,
+    interrupted: bool,
+}
+
+impl Default for RetryClassification {
+    fn default() -> Self {
+        let mut statuses: BTreeSet<u16> = (500..600).collect();
+        statuses.insert(StatusCode::REQUEST_TIMEOUT.as_u16());
+        statuses.insert(StatusCode::TOO_MANY_REQUESTS.as_u16());
+        Self {
+            statuses,
+            timeouts: true,
+            connect_errors: true,
+            interrupted: true,
+        }
+    }
+}
+
+impl RetryClassification {
+    /// The classification of `reqwest-retry`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retries nothing, transient statuses and errors being added from there
+    pub fn none() -> Self {
+        Self {
+            statuses: BTreeSet::new(),
+            timeouts: false,
+            connect_errors: false,
+            interrupted: false,
+        }
+    }
+
+    pub fn retry_status(mut self, status: StatusCode) -> Self {
+        self.statuses.insert(status.as_u16());
+        self
+    }
+
+    pub fn no_retry_status(mut self, status: StatusCode) -> Self {
+        self.statuses.remove(&status.as_u16());
+        self
+    }
+
+    /// Retries the 502, 503 and 504 that proxies answer when the workers are unreachable
+    pub fn retry_gateway_errors(self) -> Self {
+        self.retry_status(StatusCode::BAD_GATEWAY)
+            .retry_status(StatusCode::SERVICE_UNAVAILABLE)
+            .retry_status(StatusCode::GATEWAY_TIMEOUT)
+    }
+
+    pub fn retry_timeouts(mut self, retry: bool) -> Self {
+        self.timeouts = retry;
+        self
+    }
+
+    pub fn retry_connect_errors(mut self, retry: bool) -> Self {
+        self.connect_errors = retry;
+        self
+    }
+
+    /// Connections reset or closed before the whole response was read
+    pub fn retry_interrupted(mut self, retry: bool) -> Self {
+        self.interrupted = retry;
+        self
+    }
+
+    pub fn is_transient_status(&self, status: StatusCode) -> bool {
+        self.statuses.contains(&status.as_u16())
+    }
+
+    pub fn is_transient_error(&self, error: &reqwest_middleware::Error) -> bool {
+        if let reqwest_middleware::Error::Reqwest(error) = error {
+            if error.is_timeout() {
+                return self.timeouts;
+            }
+            if error.is_connect() {
+                return self.connect_errors;
+            }
+        }
+        self.interrupted
+            && matches!(
+                default_on_request_failure(error),
+                Some(Retryable::Transient)
+            )
+    }
+}
+
+impl RetryableStrategy for RetryClassification {
+    fn handle(
+        &self,
+        result: &Result<reqwest::Response, reqwest_middleware::Error>,
+    ) -> Option<Retryable> {
+        match result {
+            Ok(response) if self.is_transient_status(response.status()) => {
+                Some(Retryable::Transient)
+            }
+            Ok(response) if response.status().is_success() => None,
+            Ok(_) => Some(Retryable::Fatal),
+            Err(error) if self.is_transient_error(error) => Some(Retryable::Transient),
+            Err(_) => Some(Retryable::Fatal),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+//! JSON schemas of the manifests and snapshots, for validating them before they reach a cluster
+//! Only available with the `schemars` feature, which also derives `JsonSchema` for every model.
+//! `connectctl schema manifest` prints the manifest schema, to point yaml-language-server at
+use crate::apply::ConnectorSpec;
+use crate::snapshot::ClusterSnapshot;
+use schemars::Schema;
+
+/// Schema of a connector manifest, as read by `connectctl apply`
+pub fn manifest_schema() -> Schema {
+    schemars::schema_for!(ConnectorSpec)
+}
+
+pub fn snapshot_schema() -> Schema {
+    schemars::schema_for!(ClusterSnapshot)
+}
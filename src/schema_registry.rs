@@ -0,0 +1,244 @@
+//! Checks of the Schema Registry subjects a connector depends on, before deploying it
+//! Only available with the `schema_registry` feature
+//!
+//! Connectors using the avro or protobuf converters fail at runtime when the subjects of their
+//! topics are missing or when they produce schemas incompatible with the registered ones. The
+//! registry is read from the converter properties of the configuration, and subjects are named
+//! after topics with the default `TopicNameStrategy`
+use crate::apply::{ApplyOutcome, ConnectorSpec};
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::converters::{SchemaRegistry, AVRO_CONVERTER_CLASS, PROTOBUF_CONVERTER_CLASS};
+use crate::{json, Connect, MAX_ERROR_BODY};
+use anyhow::{Context, Result};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const TOPIC_NAME_STRATEGY: &str = "io.confluent.kafka.serializers.subject.TopicNameStrategy";
+
+/// Schema the connector is expected to produce for a subject
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExpectedSchema {
+    /// Subjects ending with `-key` are checked against the registry of the key converter,
+    /// others against the registry of the value converter
+    pub subject: String,
+    pub schema: String,
+}
+
+/// A subject the connector would fail on
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SchemaIssue {
+    pub subject: String,
+    pub problem: String,
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.subject, self.problem)
+    }
+}
+
+/// Client of the subjects and compatibility endpoints of a Schema Registry
+#[derive(Clone, Debug)]
+pub struct SchemaRegistryClient {
+    client: Client,
+    registry: SchemaRegistry,
+    max_response_size: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct Compatibility {
+    is_compatible: bool,
+    #[serde(default)]
+    messages: Vec<String>,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(registry: SchemaRegistry) -> Self {
+        Self {
+            client: Client::new(),
+            registry,
+            max_response_size: None,
+        }
+    }
+
+    /// Fails responses whose body exceeds the given number of bytes
+    pub fn max_response_size(mut self, bytes: usize) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.registry.basic_auth {
+            Some((username, password)) => builder.basic_auth(username, Some(password)),
+            None => builder,
+        }
+    }
+
+    pub async fn subjects(&self) -> Result<Vec<String>> {
+        let url = format!("{}/subjects", self.registry.url.trim_end_matches('/'));
+        let response = self.request(self.client.get(url)).send().await?;
+        let status_code = response.status();
+        if !status_code.is_success() {
+            anyhow::bail!("Schema Registry answered {} listing subjects", status_code);
+        }
+        json::from_response(response, self.max_response_size).await
+    }
+
+    /// Incompatibilities of the schema with the latest version of the subject, or None when the
+    /// subject does not exist yet
+    pub async fn incompatibilities(
+        &self,
+        subject: &str,
+        schema: &str,
+        schema_type: &str,
+    ) -> Result<Option<Vec<String>>> {
+        let url = format!(
+            "{}/compatibility/subjects/{}/versions/latest?verbose=true",
+            self.registry.url.trim_end_matches('/'),
+            subject
+        );
+        let body = serde_json::json!({ "schema": schema, "schemaType": schema_type });
+        let response = self
+            .request(self.client.post(url))
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/vnd.schemaregistry.v1+json",
+            )
+            .json(&body)
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::OK => {
+                let compatibility: Compatibility =
+                    json::from_response(response, self.max_response_size).await?;
+                Ok(Some(if compatibility.is_compatible {
+                    Vec::new()
+                } else if compatibility.messages.is_empty() {
+                    vec!["The schema is incompatible with the latest version".to_string()]
+                } else {
+                    compatibility.messages
+                }))
+            }
+            StatusCode::NOT_FOUND => Ok(None),
+            _ => anyhow::bail!(
+                "Schema Registry answered {} checking the compatibility of {}: {}",
+                status_code,
+                subject,
+                String::from_utf8_lossy(&json::read_capped(response, MAX_ERROR_BODY).await)
+            ),
+        }
+    }
+}
+
+/// A converter of the configuration backed by a Schema Registry
+struct RegistryConverter {
+    /// `key` or `value`
+    part: &'static str,
+    schema_type: &'static str,
+    registry: SchemaRegistry,
+    topic_subjects: bool,
+}
+
+fn registry_converters(config: &ConnectorConfig) -> Result<Vec<RegistryConverter>> {
+    let mut converters = Vec::new();
+    for (part, key) in [
+        ("key", config_keys::KEY_CONVERTER),
+        ("value", config_keys::VALUE_CONVERTER),
+    ] {
+        let schema_type = match config.get_str(key) {
+            Some(AVRO_CONVERTER_CLASS) => "AVRO",
+            Some(PROTOBUF_CONVERTER_CLASS) => "PROTOBUF",
+            _ => continue,
+        };
+        let url = config
+            .get_str(&format!("{}.schema.registry.url", key))
+            .with_context(|| format!("{} uses a Schema Registry but sets no url", key))?;
+        let mut registry = SchemaRegistry::new(url);
+        if let Some((username, password)) = config
+            .get_str(&format!("{}.basic.auth.user.info", key))
+            .and_then(|info| info.split_once(':'))
+        {
+            registry = registry.basic_auth(username, password);
+        }
+        let strategy = config.get_str(&format!("{}.{}.subject.name.strategy", key, part));
+        converters.push(RegistryConverter {
+            part,
+            schema_type,
+            registry,
+            topic_subjects: strategy.is_none_or(|strategy| strategy == TOPIC_NAME_STRATEGY),
+        });
+    }
+    Ok(converters)
+}
+
+/// Checks that the subjects of the topics a sink reads exist, and that the expected schemas are
+/// compatible with the registered ones. Subjects that do not exist yet are accepted for expected
+/// schemas, as the connector registers them
+pub async fn check_schemas(
+    config: &ConnectorConfig,
+    expected: &[ExpectedSchema],
+) -> Result<Vec<SchemaIssue>> {
+    let topics: Vec<&str> = config
+        .get_str(config_keys::TOPICS)
+        .map(|topics| topics.split(',').map(str::trim).collect())
+        .unwrap_or_default();
+    let mut issues = Vec::new();
+    for converter in registry_converters(config)? {
+        let client = SchemaRegistryClient::new(converter.registry);
+        if converter.topic_subjects && !topics.is_empty() {
+            let subjects = client.subjects().await?;
+            for topic in &topics {
+                let subject = format!("{}-{}", topic, converter.part);
+                if !subjects.contains(&subject) {
+                    issues.push(SchemaIssue {
+                        problem: format!(
+                            "The subject does not exist, the {} of {} cannot be deserialized",
+                            converter.part, topic
+                        ),
+                        subject,
+                    });
+                }
+            }
+        }
+        for schema in expected {
+            let is_key = schema.subject.ends_with("-key");
+            if is_key != (converter.part == "key") {
+                continue;
+            }
+            let incompatibilities = client
+                .incompatibilities(&schema.subject, &schema.schema, converter.schema_type)
+                .await?;
+            for problem in incompatibilities.into_iter().flatten() {
+                issues.push(SchemaIssue {
+                    subject: schema.subject.clone(),
+                    problem,
+                });
+            }
+        }
+    }
+    Ok(issues)
+}
+
+impl Connect {
+    /// Same as [`Connect::apply`], failing without touching the connector when [`check_schemas`]
+    /// reports issues
+    pub async fn apply_with_schema_check(
+        &self,
+        spec: &ConnectorSpec,
+        expected: &[ExpectedSchema],
+    ) -> Result<ApplyOutcome> {
+        let issues = check_schemas(&spec.config, expected).await?;
+        if !issues.is_empty() {
+            let issues: Vec<String> = issues.iter().map(SchemaIssue::to_string).collect();
+            anyhow::bail!(
+                "{} would fail on its schemas:\n{}",
+                spec.name,
+                issues.join("\n")
+            );
+        }
+        self.apply(spec).await
+    }
+}
@@ -0,0 +1,98 @@
+//! Snapshots of the connectors of a cluster, used for backups and migrations
+use crate::apply::{ApplyOutcome, ConnectorSpec};
+use crate::Connect;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub use crate::redact::{is_secret_key, MASK};
+
+/// Name of the file of a snapshot taken at the time, when kept among others
+pub fn file_name(taken_at: u64) -> String {
+    format!("snapshot-{}.json", taken_at)
+}
+
+/// Time of the snapshot named by [`file_name`]
+pub fn taken_at_of(file_name: &str) -> Option<u64> {
+    file_name
+        .strip_prefix("snapshot-")?
+        .strip_suffix(".json")?
+        .parse()
+        .ok()
+}
+
+/// Configurations of every connector of a cluster at a point in time
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ClusterSnapshot {
+    /// Version of the cluster the snapshot was taken from
+    pub version: String,
+    /// Unix timestamp, in seconds
+    pub taken_at: u64,
+    pub connectors: Vec<ConnectorSpec>,
+}
+
+impl ClusterSnapshot {
+    /// Returns a copy of the snapshot with every secret replaced by a mask, according to the
+    /// installed [`crate::redact::Redactor`]
+    pub fn masked(&self) -> Self {
+        let redactor = crate::redact::installed();
+        let connectors = self
+            .connectors
+            .iter()
+            .map(|spec| ConnectorSpec {
+                name: spec.name.clone(),
+                config: redactor.redact(&spec.config),
+            })
+            .collect();
+        Self {
+            connectors,
+            ..self.clone()
+        }
+    }
+
+    pub fn is_masked(&self) -> bool {
+        self.connectors
+            .iter()
+            .any(|spec| spec.config.values().any(|value| value == MASK))
+    }
+}
+
+impl Connect {
+    /// Takes a snapshot of the configuration of every connector
+    pub async fn snapshot(&self) -> Result<ClusterSnapshot> {
+        let info = self.info().await?;
+        let mut connectors: Vec<ConnectorSpec> = self
+            .connectors(false, true)
+            .await?
+            .into_iter()
+            .filter_map(|(name, connector)| {
+                connector
+                    .info
+                    .map(|info| ConnectorSpec::new(name, info.config))
+            })
+            .collect();
+        connectors.sort_by(|a, b| a.name.cmp(&b.name));
+        let taken_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Ok(ClusterSnapshot {
+            version: info.version,
+            taken_at,
+            connectors,
+        })
+    }
+
+    /// Applies every connector of the snapshot. Connectors missing from the snapshot are left untouched
+    pub async fn restore(&self, snapshot: &ClusterSnapshot) -> Result<Vec<(String, ApplyOutcome)>> {
+        if snapshot.is_masked() {
+            anyhow::bail!("The snapshot contains masked secrets and cannot be restored");
+        }
+        let mut outcomes = Vec::with_capacity(snapshot.connectors.len());
+        for spec in &snapshot.connectors {
+            outcomes.push((spec.name.clone(), self.apply(spec).await?));
+        }
+        Ok(outcomes)
+    }
+}
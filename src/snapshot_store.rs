@@ -0,0 +1,93 @@
+//! Snapshots kept in object stores, such as `s3://bucket/prefix`, `gs://bucket/prefix` or
+//! `az://container/prefix`
+//! Only available with the `object_store` feature
+//!
+//! A url ending with `.json` names the snapshot itself. Any other url is a prefix: snapshots are
+//! written under it as `snapshot-<taken_at>.json`, and the most recent one is read back.
+//! Credentials are read from the usual environment variables of each store, such as
+//! `AWS_ACCESS_KEY_ID` or `GOOGLE_SERVICE_ACCOUNT`
+use crate::apply::ApplyOutcome;
+use crate::snapshot::ClusterSnapshot;
+use crate::Connect;
+use anyhow::{Context, Result};
+use futures::TryStreamExt;
+use object_store::path::Path;
+use object_store::{ObjectStore, ObjectStoreExt, PutPayload};
+use reqwest::Url;
+
+fn open(url: &str) -> Result<(Box<dyn ObjectStore>, Path)> {
+    let url = Url::parse(url).with_context(|| format!("Invalid object store url {}", url))?;
+    let options = std::env::vars().map(|(key, value)| (key.to_ascii_lowercase(), value));
+    Ok(object_store::parse_url_opts(&url, options)?)
+}
+
+fn is_object(path: &Path) -> bool {
+    path.extension() == Some("json")
+}
+
+/// Writes the snapshot, returning the path of the object within the store
+pub async fn write_snapshot(url: &str, snapshot: &ClusterSnapshot) -> Result<String> {
+    let (store, path) = open(url)?;
+    let path = if is_object(&path) {
+        path
+    } else {
+        path.join(crate::snapshot::file_name(snapshot.taken_at))
+    };
+    let body = serde_json::to_vec_pretty(snapshot)?;
+    store.put(&path, PutPayload::from(body)).await?;
+    Ok(path.to_string())
+}
+
+/// Reads the snapshot of the url, or the most recent one under the prefix
+pub async fn read_snapshot(url: &str) -> Result<ClusterSnapshot> {
+    let (store, path) = open(url)?;
+    let path = if is_object(&path) {
+        path
+    } else {
+        let objects: Vec<_> = store.list(Some(&path)).try_collect().await?;
+        objects
+            .into_iter()
+            .filter(|object| is_object(&object.location))
+            .max_by_key(|object| object.last_modified)
+            .map(|object| object.location)
+            .with_context(|| format!("No snapshot under {}", url))?
+    };
+    let body = store.get(&path).await?.bytes().await?;
+    serde_json::from_slice(&body).with_context(|| format!("Invalid snapshot at {}", path))
+}
+
+/// Times of the snapshots written under the prefix, as named by [`write_snapshot`], most recent
+/// first
+pub async fn list_snapshots(url: &str) -> Result<Vec<u64>> {
+    let (store, path) = open(url)?;
+    let objects: Vec<_> = store.list(Some(&path)).try_collect().await?;
+    let mut taken_at: Vec<u64> = objects
+        .iter()
+        .filter_map(|object| object.location.filename())
+        .filter_map(crate::snapshot::taken_at_of)
+        .collect();
+    taken_at.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(taken_at)
+}
+
+/// Deletes the snapshot taken at the time under the prefix
+pub async fn delete_snapshot(url: &str, taken_at: u64) -> Result<()> {
+    let (store, path) = open(url)?;
+    store
+        .delete(&path.join(crate::snapshot::file_name(taken_at)))
+        .await?;
+    Ok(())
+}
+
+impl Connect {
+    /// Takes a snapshot and writes it to the object store, returning the path of the object. The
+    /// snapshot holds the secrets of the connectors, [`write_snapshot`] can write a masked one
+    pub async fn snapshot_to(&self, url: &str) -> Result<String> {
+        write_snapshot(url, &self.snapshot().await?).await
+    }
+
+    /// Restores the snapshot read from the object store, see [`Connect::restore`]
+    pub async fn restore_from(&self, url: &str) -> Result<Vec<(String, ApplyOutcome)>> {
+        self.restore(&read_snapshot(url).await?).await
+    }
+}
@@ -0,0 +1,297 @@
+//! Supervision of the connectors of a cluster, remediating what its built-in rules find
+//! Every tick reads the statuses once and applies the rules:
+//! - failed connectors and tasks are restarted
+//! - tasks UNASSIGNED for longer than a threshold, typically left behind by a dead worker, are
+//!   restarted, then their whole connector is if they are still stuck after another threshold
+//!
+//! Remediations go through the [`RestartBudget`] of the policy. In observe-only mode the findings
+//! are reported without acting on them
+use crate::filter::ConnectorFilter;
+use crate::models::{ConnectorStatus, Status};
+use crate::restart_budget::{Admission, RestartBudget};
+use crate::Connect;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// What the supervisor does
+#[derive(Clone, Debug)]
+pub struct SupervisorPolicy {
+    /// Reports the findings without remediating them
+    pub observe_only: bool,
+    /// Only connectors selected by the filter are supervised
+    pub filter: ConnectorFilter,
+    /// Restarts allowed, unlimited when unset
+    pub budget: Option<RestartBudget>,
+    pub restart_failed: bool,
+    /// Time after which an UNASSIGNED connector or task is stuck, `None` disabling the rule
+    pub unassigned_after: Option<Duration>,
+}
+
+impl Default for SupervisorPolicy {
+    fn default() -> Self {
+        Self {
+            observe_only: false,
+            filter: ConnectorFilter::new(),
+            budget: None,
+            restart_failed: true,
+            unassigned_after: Some(Duration::from_secs(5 * 60)),
+        }
+    }
+}
+
+/// Something wrong with a connector
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Finding {
+    Failed {
+        connector: String,
+        connector_failed: bool,
+        tasks: Vec<u64>,
+    },
+    /// A task, or the connector itself when `task` is unset, has been UNASSIGNED for that long
+    StuckUnassigned {
+        connector: String,
+        task: Option<u64>,
+        #[serde(rename = "for_secs", serialize_with = "serialize_secs")]
+        duration: Duration,
+    },
+}
+
+fn serialize_secs<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
+impl Finding {
+    pub fn connector(&self) -> &str {
+        match self {
+            Self::Failed { connector, .. } | Self::StuckUnassigned { connector, .. } => connector,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    RestartTask {
+        task: u64,
+    },
+    /// Restart of the connector along with its tasks, only the failed ones when `only_failed`
+    RestartConnector {
+        only_failed: bool,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", content = "error", rename_all = "snake_case")]
+pub enum Outcome {
+    /// Observe-only mode, nothing was done
+    Observed,
+    Done,
+    /// The budget does not allow the restart for now
+    Deferred,
+    /// The connector used up its budget and is left alone for the cooldown
+    GivenUp,
+    Failed(String),
+}
+
+/// A finding and what was done about it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Remediation {
+    pub finding: Finding,
+    pub action: Action,
+    pub outcome: Outcome,
+}
+
+struct Stuck {
+    since: Instant,
+    /// When the task was restarted, before restarting the connector
+    task_restarted_at: Option<Instant>,
+}
+
+pub struct Supervisor {
+    client: Connect,
+    policy: SupervisorPolicy,
+    unassigned: HashMap<(String, Option<u64>), Stuck>,
+}
+
+impl Supervisor {
+    pub fn new(client: Connect, policy: SupervisorPolicy) -> Self {
+        Self {
+            client,
+            policy,
+            unassigned: HashMap::new(),
+        }
+    }
+
+    /// Ticks forever, `interval` apart, handing the outcome of each tick to the callback
+    pub async fn run(
+        mut self,
+        interval: Duration,
+        mut on_tick: impl FnMut(Result<Vec<Remediation>>),
+    ) {
+        loop {
+            on_tick(self.tick().await);
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Reads the statuses, applies the rules and returns what they found, sorted by connector
+    pub async fn tick(&mut self) -> Result<Vec<Remediation>> {
+        let (_, expand_info) = self.policy.filter.expand();
+        let mut statuses: Vec<ConnectorStatus> = self
+            .client
+            .connectors(true, expand_info)
+            .await?
+            .into_iter()
+            .filter(|(name, connector)| self.policy.filter.matches(name, connector))
+            .filter_map(|(_, connector)| connector.status)
+            .collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut planned = Vec::new();
+        for status in &statuses {
+            if status.is_healthy() {
+                if let Some(budget) = &self.policy.budget {
+                    budget.observe_healthy(&status.name);
+                }
+            }
+            if self.policy.restart_failed {
+                planned.extend(failed(status));
+            }
+        }
+        if let Some(threshold) = self.policy.unassigned_after {
+            planned.extend(self.stuck_unassigned(&statuses, threshold));
+        }
+        let mut remediations = Vec::with_capacity(planned.len());
+        for (finding, action) in planned {
+            let outcome = self.remediate(finding.connector(), action).await;
+            if outcome == Outcome::Done {
+                self.restarted(&finding, action);
+            }
+            remediations.push(Remediation {
+                finding,
+                action,
+                outcome,
+            });
+        }
+        remediations.sort_by(|a, b| a.finding.connector().cmp(b.finding.connector()));
+        Ok(remediations)
+    }
+
+    fn stuck_unassigned(
+        &mut self,
+        statuses: &[ConnectorStatus],
+        threshold: Duration,
+    ) -> Vec<(Finding, Action)> {
+        let unassigned: Vec<(String, Option<u64>)> = statuses
+            .iter()
+            .flat_map(|status| {
+                let connector = (status.connector.state == Status::Unassigned)
+                    .then(|| (status.name.clone(), None));
+                let tasks = status
+                    .tasks
+                    .iter()
+                    .filter(|task| task.state == Status::Unassigned)
+                    .map(|task| (status.name.clone(), Some(task.id)));
+                connector.into_iter().chain(tasks)
+            })
+            .collect();
+        self.unassigned.retain(|key, _| unassigned.contains(key));
+        let mut planned = Vec::new();
+        for key in unassigned {
+            let stuck = self.unassigned.entry(key.clone()).or_insert(Stuck {
+                since: Instant::now(),
+                task_restarted_at: None,
+            });
+            let duration = stuck.since.elapsed();
+            if duration < threshold {
+                continue;
+            }
+            let (connector, task) = key;
+            let action = match (task, stuck.task_restarted_at) {
+                (Some(task), None) => Action::RestartTask { task },
+                (Some(_), Some(at)) if at.elapsed() < threshold => continue,
+                _ => Action::RestartConnector { only_failed: false },
+            };
+            planned.push((
+                Finding::StuckUnassigned {
+                    connector,
+                    task,
+                    duration,
+                },
+                action,
+            ));
+        }
+        planned
+    }
+
+    /// Records a restart carried out for a connector or task stuck unassigned, so that a task
+    /// restart is only followed by a connector restart once it was actually made
+    fn restarted(&mut self, finding: &Finding, action: Action) {
+        let Finding::StuckUnassigned {
+            connector, task, ..
+        } = finding
+        else {
+            return;
+        };
+        let Some(stuck) = self.unassigned.get_mut(&(connector.clone(), *task)) else {
+            return;
+        };
+        match action {
+            Action::RestartTask { .. } => stuck.task_restarted_at = Some(Instant::now()),
+            Action::RestartConnector { .. } => {
+                // start over, a task restart may be enough next time
+                stuck.since = Instant::now();
+                stuck.task_restarted_at = None;
+            }
+        }
+    }
+
+    async fn remediate(&self, connector: &str, action: Action) -> Outcome {
+        if self.policy.observe_only {
+            return Outcome::Observed;
+        }
+        match self
+            .policy
+            .budget
+            .as_ref()
+            .map(|budget| budget.admit(connector))
+        {
+            None | Some(Admission::Allowed) => {}
+            Some(Admission::GivenUp) => return Outcome::GivenUp,
+            Some(_) => return Outcome::Deferred,
+        }
+        let result = match action {
+            Action::RestartTask { task } => self.client.restart_task(connector, task).await,
+            Action::RestartConnector { only_failed } => self
+                .client
+                .restart_connector(connector, true, only_failed)
+                .await
+                .map(|_| ()),
+        };
+        match result {
+            Ok(()) => Outcome::Done,
+            Err(err) => Outcome::Failed(err.to_string()),
+        }
+    }
+}
+
+fn failed(status: &ConnectorStatus) -> Option<(Finding, Action)> {
+    let connector_failed = status.connector.state == Status::Failed;
+    let tasks: Vec<u64> = status.failed_tasks().iter().map(|task| task.id).collect();
+    if !connector_failed && tasks.is_empty() {
+        return None;
+    }
+    Some((
+        Finding::Failed {
+            connector: status.name.clone(),
+            connector_failed,
+            tasks,
+        },
+        Action::RestartConnector { only_failed: true },
+    ))
+}
@@ -0,0 +1,274 @@
+//! Fixtures and mock servers for the tests of crates depending on this one
+//! Only available with the `test_support` feature
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::models::*;
+use crate::retry::RetryClassification;
+use crate::Connect;
+use mockito::{Matcher, Mock, ServerGuard};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Trace of the failed tasks built by [`StatusFixture::failed_task`]
+pub const FAILED_TASK_TRACE: &str = "org.apache.kafka.connect.errors.ConnectException: Tolerance exceeded in error handler
+\tat org.apache.kafka.connect.runtime.errors.RetryWithToleranceOperator.execAndHandleError(RetryWithToleranceOperator.java:230)
+\tat org.apache.kafka.connect.runtime.WorkerSinkTask.convertAndTransformRecord(WorkerSinkTask.java:518)
+Caused by: org.apache.kafka.connect.errors.DataException: Converting byte[] to Kafka Connect data failed due to serialization error
+\tat org.apache.kafka.connect.json.JsonConverter.toConnectData(JsonConverter.java:333)
+";
+
+const WORKER: &str = "connect-1:8083";
+
+/// Builds the status of a connector, running on a single worker by default
+#[derive(Clone, Debug)]
+pub struct StatusFixture {
+    status: ConnectorStatus,
+}
+
+impl StatusFixture {
+    pub fn new(name: &str) -> Self {
+        Self {
+            status: ConnectorStatus {
+                connector: ConnectorState {
+                    connector: None,
+                    state: Status::Running,
+                    worker_id: WORKER.to_string(),
+                    trace: None,
+                    extra: HashMap::new(),
+                },
+                name: name.to_string(),
+                tasks: Vec::new(),
+                kind: "source".to_string(),
+                extra: HashMap::new(),
+            },
+        }
+    }
+
+    pub fn sink(mut self) -> Self {
+        self.status.kind = "sink".to_string();
+        self
+    }
+
+    pub fn state(mut self, state: Status) -> Self {
+        self.status.connector.state = state;
+        self
+    }
+
+    pub fn worker(mut self, worker_id: &str) -> Self {
+        self.status.connector.worker_id = worker_id.to_string();
+        self
+    }
+
+    /// Adds a task in the given state, on the worker of the connector
+    pub fn task(mut self, state: Status) -> Self {
+        self.status.tasks.push(TaskStatus {
+            id: self.status.tasks.len() as u64,
+            state,
+            worker_id: self.status.connector.worker_id.clone(),
+            trace: None,
+            extra: HashMap::new(),
+        });
+        self
+    }
+
+    /// Adds running tasks
+    pub fn tasks(self, count: usize) -> Self {
+        (0..count).fold(self, |fixture, _| fixture.task(Status::Running))
+    }
+
+    /// Adds a failed task, with a realistic trace
+    pub fn failed_task(mut self) -> Self {
+        self = self.task(Status::Failed);
+        if let Some(task) = self.status.tasks.last_mut() {
+            task.trace = Some(FAILED_TASK_TRACE.to_string());
+        }
+        self
+    }
+
+    pub fn build(self) -> ConnectorStatus {
+        self.status
+    }
+}
+
+/// Builds the info of a connector, with a file source configuration by default
+#[derive(Clone, Debug)]
+pub struct InfoFixture {
+    info: ConnectorInfo,
+}
+
+impl InfoFixture {
+    pub fn new(name: &str) -> Self {
+        let config: ConnectorConfig = [
+            (config_keys::NAME, name),
+            (
+                config_keys::CONNECTOR_CLASS,
+                "org.apache.kafka.connect.file.FileStreamSourceConnector",
+            ),
+            (config_keys::TASKS_MAX, "1"),
+            ("file", "/tmp/input.txt"),
+            ("topic", "input"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        Self {
+            info: ConnectorInfo {
+                name: name.to_string(),
+                config,
+                tasks: Vec::new(),
+                kind: "source".to_string(),
+                extra: HashMap::new(),
+            },
+        }
+    }
+
+    pub fn sink(mut self) -> Self {
+        self.info.kind = "sink".to_string();
+        self
+    }
+
+    /// Replaces the configuration. The name is kept in sync with the connector
+    pub fn config(mut self, mut config: ConnectorConfig) -> Self {
+        config.set(config_keys::NAME, &self.info.name);
+        self.info.config = config;
+        self
+    }
+
+    pub fn tasks(mut self, count: u64) -> Self {
+        self.info.tasks = (0..count)
+            .map(|task| TaskInfo {
+                connector: self.info.name.clone(),
+                task,
+                extra: HashMap::new(),
+            })
+            .collect();
+        self
+    }
+
+    pub fn build(self) -> ConnectorInfo {
+        self.info
+    }
+}
+
+/// A mockito server answering like a kafka-connect cluster
+pub struct MockCluster {
+    pub server: ServerGuard,
+}
+
+impl MockCluster {
+    pub async fn start() -> Self {
+        Self {
+            server: mockito::Server::new_async().await,
+        }
+    }
+
+    /// A client pointing at the server, which does not retry so failures surface at once
+    pub fn client(&self) -> Connect {
+        Connect::builder(&self.server.url())
+            .retry_classification(RetryClassification::none())
+            .build()
+            .unwrap()
+    }
+
+    /// Mocks a request answered with a json body
+    pub async fn mock_json<T: Serialize + ?Sized>(
+        &mut self,
+        method: &str,
+        path: &str,
+        status: usize,
+        body: &T,
+    ) -> Mock {
+        self.server
+            .mock(method, path)
+            .match_query(Matcher::Any)
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::to_vec(body).expect("fixtures serialize to json"))
+            .create_async()
+            .await
+    }
+
+    /// Mocks a request answered with the error body of the API
+    pub async fn mock_error(
+        &mut self,
+        method: &str,
+        path: &str,
+        status: u16,
+        message: &str,
+    ) -> Mock {
+        let body = ErrorResponse {
+            error_code: status,
+            message: message.to_string(),
+        };
+        self.mock_json(method, path, status.into(), &body).await
+    }
+
+    /// Mocks the root endpoint
+    pub async fn mock_cluster_info(&mut self, version: &str) -> Mock {
+        let info = ClusterInfo {
+            version: version.to_string(),
+            commit: "0123456789abcdef".to_string(),
+            kafka_cluster_id: "mock-cluster".to_string(),
+            extra: HashMap::new(),
+        };
+        self.mock_json("GET", "/", 200, &info).await
+    }
+
+    /// Mocks the listings of connectors, both plain and expanded, along with the endpoints of each connector.
+    /// Connectors only have info, status, or both depending on the fixtures given
+    pub async fn mock_connectors(
+        &mut self,
+        infos: &[ConnectorInfo],
+        statuses: &[ConnectorStatus],
+    ) -> Vec<Mock> {
+        let mut connectors: HashMap<String, Connector> = HashMap::new();
+        for info in infos {
+            connectors
+                .entry(info.name.clone())
+                .or_insert(Connector {
+                    info: None,
+                    status: None,
+                })
+                .info = Some(info.clone());
+        }
+        for status in statuses {
+            connectors
+                .entry(status.name.clone())
+                .or_insert(Connector {
+                    info: None,
+                    status: None,
+                })
+                .status = Some(status.clone());
+        }
+        let mut names: Vec<&String> = connectors.keys().collect();
+        names.sort();
+        let names = serde_json::to_vec(&names).expect("names serialize to json");
+        let mut mocks = vec![
+            self.server
+                .mock("GET", "/connectors")
+                .match_query(Matcher::Missing)
+                .with_header("content-type", "application/json")
+                .with_body(names)
+                .create_async()
+                .await,
+            self.server
+                .mock("GET", "/connectors")
+                .match_query(Matcher::Regex("expand=".to_string()))
+                .with_header("content-type", "application/json")
+                .with_body(serde_json::to_vec(&connectors).expect("fixtures serialize to json"))
+                .create_async()
+                .await,
+        ];
+        for info in infos {
+            let path = format!("/connectors/{}", info.name);
+            mocks.push(self.mock_json("GET", &path, 200, info).await);
+            let path = format!("/connectors/{}/config", info.name);
+            mocks.push(self.mock_json("GET", &path, 200, &info.config).await);
+        }
+        for status in statuses {
+            let path = format!("/connectors/{}/status", status.name);
+            mocks.push(self.mock_json("GET", &path, 200, status).await);
+        }
+        mocks
+    }
+}
@@ -0,0 +1,123 @@
+//! Which connectors touch a topic, before migrating or deleting it
+//! Workers track the topics each connector used since its creation or its last reset, the
+//! configuration tells which topics a sink is going to read. A topic is reported when either says
+//! so. Topic tracking can be disabled on the workers, in which case only the configurations count
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::error::ConnectError;
+use crate::Connect;
+use anyhow::Result;
+use futures::{stream, StreamExt};
+use regex::Regex;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Connectors whose active topics are read at the same time
+const CONCURRENCY: usize = 8;
+
+#[derive(Deserialize)]
+struct ActiveTopics {
+    topics: Vec<String>,
+}
+
+/// How a connector touches a topic
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TopicUsage {
+    pub connector: String,
+    /// `source` or `sink`
+    pub kind: String,
+    /// Whether the worker tracked the connector using the topic
+    pub active: bool,
+    /// Whether `topics` or `topics.regex` selects the topic
+    pub configured: bool,
+}
+
+impl ConnectorConfig {
+    /// Whether a sink configured this way reads the topic. `topics.regex` must match the whole
+    /// name, as Java does
+    pub fn selects_topic(&self, topic: &str) -> Result<bool> {
+        if let Some(topics) = self.get_list(config_keys::TOPICS) {
+            if topics.iter().any(|listed| listed == topic) {
+                return Ok(true);
+            }
+        }
+        match self.get_str(config_keys::TOPICS_REGEX) {
+            Some(pattern) => Ok(Regex::new(&format!("^(?:{})$", pattern))?.is_match(topic)),
+            None => Ok(false),
+        }
+    }
+}
+
+impl Connect {
+    /// Topics the connector used, `None` when the workers do not track topics
+    pub async fn connector_active_topics(&self, connector: &str) -> Result<Option<Vec<String>>> {
+        let response = self
+            .client
+            .get(format!("{}/connectors/{}/topics", self.address, connector))
+            .send()
+            .await?;
+        let status_code = response.status();
+        match status_code {
+            StatusCode::OK => {
+                let mut active: HashMap<String, ActiveTopics> = self.parse(response).await?;
+                Ok(Some(
+                    active
+                        .remove(connector)
+                        .map(|active| active.topics)
+                        .unwrap_or_default(),
+                ))
+            }
+            // topic.tracking.enable=false
+            StatusCode::FORBIDDEN => Ok(None),
+            StatusCode::NOT_FOUND => {
+                anyhow::bail!(ConnectError::ConnectorNotFound(connector.to_string()))
+            }
+            _ => anyhow::bail!(
+                "Unrecognizable error for status code {}: {}",
+                status_code,
+                crate::error_message(response).await
+            ),
+        }
+    }
+
+    /// Connectors using the topic or configured to read it, sorted by name
+    pub async fn connectors_for_topic(&self, topic: &str) -> Result<Vec<TopicUsage>> {
+        let connectors = self.connectors(false, true).await?;
+        let lookups = connectors.into_iter().filter_map(|(name, connector)| {
+            let info = connector.info?;
+            Some(async move {
+                let active = match self.connector_active_topics(&name).await {
+                    Ok(active) => active.is_some_and(|topics| topics.iter().any(|t| t == topic)),
+                    // deleted since the listing
+                    Err(err)
+                        if matches!(
+                            err.downcast_ref(),
+                            Some(ConnectError::ConnectorNotFound(_))
+                        ) =>
+                    {
+                        return Ok(None)
+                    }
+                    Err(err) => return Err(err),
+                };
+                let configured = info.config.selects_topic(topic)?;
+                Ok((active || configured).then_some(TopicUsage {
+                    connector: name,
+                    kind: info.kind,
+                    active,
+                    configured,
+                }))
+            })
+        });
+        let results: Vec<Result<Option<TopicUsage>>> = stream::iter(lookups)
+            .buffer_unordered(CONCURRENCY)
+            .collect()
+            .await;
+        let mut usages = Vec::new();
+        for result in results {
+            usages.extend(result?);
+        }
+        usages.sort_by(|a, b| a.connector.cmp(&b.connector));
+        Ok(usages)
+    }
+}
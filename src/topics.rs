@@ -0,0 +1,172 @@
+//! Checks of the Kafka topics a sink connector reads, before creating it
+//! Only available with the `rdkafka` feature
+//!
+//! A sink created before its topics exist sits idle without reporting any error. The topics of
+//! `topics` must exist and `topics.regex` must match at least one topic. The dead letter queue
+//! topic can be created on the way, with the partitions and replication factor requested
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::models::ConnectorInfo;
+use crate::Connect;
+use anyhow::Result;
+use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::ClientConfig;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Dead letter queue topic to create when it is missing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeadLetterQueueTopic {
+    pub partitions: i32,
+    /// Replication factor used unless the configuration sets
+    /// `errors.deadletterqueue.topic.replication.factor`
+    pub replication_factor: i32,
+}
+
+/// Outcome of the checks
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TopicReport {
+    /// Topics of `topics` that do not exist
+    pub missing_topics: Vec<String>,
+    /// `topics.regex`, when no topic matches it
+    pub unmatched_regex: Option<String>,
+    /// Topics created by the checks
+    pub created_topics: Vec<String>,
+}
+
+impl TopicReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing_topics.is_empty() && self.unmatched_regex.is_none()
+    }
+}
+
+/// Kafka admin client checking the topics of connectors
+pub struct TopicPreflight {
+    admin: Arc<AdminClient<DefaultClientContext>>,
+    timeout: Duration,
+}
+
+impl TopicPreflight {
+    pub fn new(bootstrap_servers: &str) -> Result<Self> {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", bootstrap_servers);
+        Self::from_config(&config)
+    }
+
+    /// Admin client configured with any librdkafka property, such as the security ones
+    pub fn from_config(config: &ClientConfig) -> Result<Self> {
+        Ok(Self {
+            admin: Arc::new(config.create()?),
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    /// Timeout of the requests to the brokers, 30 seconds by default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub async fn topic_names(&self) -> Result<Vec<String>> {
+        let admin = self.admin.clone();
+        let timeout = self.timeout;
+        // librdkafka only fetches metadata synchronously
+        let metadata =
+            tokio::task::spawn_blocking(move || admin.inner().fetch_metadata(None, timeout))
+                .await??;
+        Ok(metadata
+            .topics()
+            .iter()
+            .map(|topic| topic.name().to_string())
+            .collect())
+    }
+
+    /// Checks the topics of the configuration, creating the dead letter queue topic if requested
+    pub async fn check(
+        &self,
+        config: &ConnectorConfig,
+        dead_letter_queue: Option<DeadLetterQueueTopic>,
+    ) -> Result<TopicReport> {
+        let existing = self.topic_names().await?;
+        let mut report = TopicReport::default();
+        if let Some(topics) = config.get_str(config_keys::TOPICS) {
+            report.missing_topics = topics
+                .split(',')
+                .map(str::trim)
+                .filter(|topic| !topic.is_empty() && !existing.iter().any(|name| name == topic))
+                .map(String::from)
+                .collect();
+        }
+        if let Some(pattern) = config.get_str(config_keys::TOPICS_REGEX) {
+            // kafka matches the whole topic name
+            let regex = Regex::new(&format!("^(?:{})$", pattern))?;
+            if !existing.iter().any(|name| regex.is_match(name)) {
+                report.unmatched_regex = Some(pattern.to_string());
+            }
+        }
+        let dead_letter_topic = config.get_str(config_keys::ERRORS_DEADLETTERQUEUE_TOPIC_NAME);
+        if let (Some(topic), Some(requested)) = (dead_letter_topic, dead_letter_queue) {
+            if !existing.iter().any(|name| name == topic) {
+                let replication_factor = config
+                    .get_i64(config_keys::ERRORS_DEADLETTERQUEUE_TOPIC_REPLICATION_FACTOR)?
+                    .map_or(requested.replication_factor, |factor| factor as i32);
+                self.create_topic(topic, requested.partitions, replication_factor)
+                    .await?;
+                report.created_topics.push(topic.to_string());
+            }
+        }
+        Ok(report)
+    }
+
+    async fn create_topic(
+        &self,
+        name: &str,
+        partitions: i32,
+        replication_factor: i32,
+    ) -> Result<()> {
+        let topic = NewTopic::new(
+            name,
+            partitions,
+            TopicReplication::Fixed(replication_factor),
+        );
+        let options = AdminOptions::new().operation_timeout(Some(self.timeout));
+        for result in self.admin.create_topics([&topic], &options).await? {
+            if let Err((topic, code)) = result {
+                anyhow::bail!("Could not create topic {}: {}", topic, code);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Connect {
+    /// Creates a sink connector once [`TopicPreflight::check`] passes, failing without creating it
+    /// otherwise
+    pub async fn create_sink_connector_checked(
+        &self,
+        name: &str,
+        config: &ConnectorConfig,
+        preflight: &TopicPreflight,
+        dead_letter_queue: Option<DeadLetterQueueTopic>,
+    ) -> Result<ConnectorInfo> {
+        let report = preflight.check(config, dead_letter_queue).await?;
+        if !report.missing_topics.is_empty() {
+            anyhow::bail!(
+                "{} reads topics that do not exist: {}",
+                name,
+                report.missing_topics.join(", ")
+            );
+        }
+        if let Some(pattern) = report.unmatched_regex {
+            anyhow::bail!(
+                "{} reads topics matching {}, but none exists",
+                name,
+                pattern
+            );
+        }
+        self.create_connector(name, config).await
+    }
+}
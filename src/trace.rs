@@ -0,0 +1,86 @@
+//! Best-effort parsing of the java stack traces reported by failed tasks and connectors
+use serde::Serialize;
+
+const CAUSED_BY: &str = "Caused by:";
+
+/// A java exception, as reconstructed from its stack trace
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ExceptionInfo {
+    /// Fully qualified name of the exception class
+    pub class: String,
+    pub message: Option<String>,
+    /// The exception that caused this one, if any
+    pub caused_by: Option<Box<ExceptionInfo>>,
+    /// Stack frames, without the leading `at`
+    pub frames: Vec<String>,
+}
+
+impl ExceptionInfo {
+    /// Parses a stack trace. Returns None if the trace does not contain an exception header
+    pub fn parse(trace: &str) -> Option<Self> {
+        let mut exceptions: Vec<ExceptionInfo> = Vec::new();
+        for line in trace.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some(header) = trimmed.strip_prefix(CAUSED_BY) {
+                exceptions.push(Self::from_header(header.trim()));
+            } else if let Some(frame) = trimmed.strip_prefix("at ") {
+                if let Some(current) = exceptions.last_mut() {
+                    current.frames.push(frame.to_string());
+                }
+            } else if trimmed.starts_with("... ") || trimmed.starts_with("Suppressed:") {
+                // elided frames and suppressed exceptions carry no information we care about
+                continue;
+            } else {
+                match exceptions.last_mut() {
+                    None => exceptions.push(Self::from_header(trimmed)),
+                    // multi-line messages only continue before the first frame
+                    Some(current) if current.frames.is_empty() => {
+                        let message = current.message.get_or_insert_with(String::new);
+                        if !message.is_empty() {
+                            message.push('\n');
+                        }
+                        message.push_str(trimmed);
+                    }
+                    Some(_) => continue,
+                }
+            }
+        }
+        // chain every exception to the one that follows it
+        let mut chain = exceptions.into_iter().rev();
+        let mut root = chain.next()?;
+        for mut exception in chain {
+            exception.caused_by = Some(Box::new(root));
+            root = exception;
+        }
+        Some(root)
+    }
+
+    fn from_header(header: &str) -> Self {
+        let (class, message) = match header.split_once(": ") {
+            Some((class, message)) => (class, Some(message.to_string())),
+            None => (header.trim_end_matches(':'), None),
+        };
+        Self {
+            class: class.to_string(),
+            message,
+            ..Default::default()
+        }
+    }
+
+    /// The innermost exception of the chain, which is usually the most meaningful one
+    pub fn root_cause(&self) -> &ExceptionInfo {
+        let mut current = self;
+        while let Some(cause) = &current.caused_by {
+            current = cause;
+        }
+        current
+    }
+
+    /// Iterates over the exception and all of its causes, outermost first
+    pub fn chain(&self) -> impl Iterator<Item = &ExceptionInfo> {
+        std::iter::successors(Some(self), |exception| exception.caused_by.as_deref())
+    }
+}
@@ -0,0 +1,275 @@
+//! Single message transforms
+//! A chain manages the `transforms` list along with the `transforms.<alias>.*` keys of every transform,
+//! and the same goes for predicates
+use crate::config::ConnectorConfig;
+use crate::config_keys::{self, predicate_key, transform_key};
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Which part of the record a transform applies to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordPart {
+    Key,
+    Value,
+}
+
+impl RecordPart {
+    fn suffix(&self) -> &'static str {
+        match self {
+            Self::Key => "$Key",
+            Self::Value => "$Value",
+        }
+    }
+}
+
+/// Target type of the TimestampConverter transform
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimestampType {
+    String,
+    Unix,
+    Date,
+    Time,
+    Timestamp,
+}
+
+impl TimestampType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Unix => "unix",
+            Self::Date => "Date",
+            Self::Time => "Time",
+            Self::Timestamp => "Timestamp",
+        }
+    }
+}
+
+/// A single transform, made of its class and properties
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transform {
+    pub class: String,
+    pub properties: BTreeMap<String, String>,
+    /// Alias of the predicate deciding whether the transform applies
+    pub predicate: Option<String>,
+    /// Applies the transform when the predicate does not match
+    pub negate: bool,
+}
+
+impl Transform {
+    pub fn new(class: impl Into<String>) -> Self {
+        Self {
+            class: class.into(),
+            properties: BTreeMap::new(),
+            predicate: None,
+            negate: false,
+        }
+    }
+
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Only applies the transform to records matching the predicate
+    pub fn when(mut self, predicate: impl Into<String>) -> Self {
+        self.predicate = Some(predicate.into());
+        self.negate = false;
+        self
+    }
+
+    /// Only applies the transform to records not matching the predicate
+    pub fn unless(mut self, predicate: impl Into<String>) -> Self {
+        self.predicate = Some(predicate.into());
+        self.negate = true;
+        self
+    }
+
+    /// Replaces the key or value with one of its fields
+    pub fn extract_field(part: RecordPart, field: impl Into<String>) -> Self {
+        Self::new(format!(
+            "org.apache.kafka.connect.transforms.ExtractField{}",
+            part.suffix()
+        ))
+        .property("field", field)
+    }
+
+    /// Renames topics matching the regex, `replacement` may reference the regex groups
+    pub fn regex_router(regex: impl Into<String>, replacement: impl Into<String>) -> Self {
+        Self::new("org.apache.kafka.connect.transforms.RegexRouter")
+            .property("regex", regex)
+            .property("replacement", replacement)
+    }
+
+    /// Converts a timestamp field, or the whole key or value when no field is given.
+    /// The format follows the java `SimpleDateFormat` syntax and is only used for string timestamps
+    pub fn timestamp_converter(
+        part: RecordPart,
+        target: TimestampType,
+        field: Option<&str>,
+        format: Option<&str>,
+    ) -> Self {
+        let mut transform = Self::new(format!(
+            "org.apache.kafka.connect.transforms.TimestampConverter{}",
+            part.suffix()
+        ))
+        .property("target.type", target.as_str());
+        if let Some(field) = field {
+            transform = transform.property("field", field);
+        }
+        if let Some(format) = format {
+            transform = transform.property("format", format);
+        }
+        transform
+    }
+}
+
+/// A predicate, used to apply transforms conditionally
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Predicate {
+    pub class: String,
+    pub properties: BTreeMap<String, String>,
+}
+
+impl Predicate {
+    pub fn new(class: impl Into<String>) -> Self {
+        Self {
+            class: class.into(),
+            properties: BTreeMap::new(),
+        }
+    }
+
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Matches records whose topic matches the regex
+    pub fn topic_name_matches(pattern: impl Into<String>) -> Self {
+        Self::new("org.apache.kafka.connect.transforms.predicates.TopicNameMatches")
+            .property("pattern", pattern)
+    }
+
+    /// Matches records with at least one header with the given name
+    pub fn has_header_key(name: impl Into<String>) -> Self {
+        Self::new("org.apache.kafka.connect.transforms.predicates.HasHeaderKey")
+            .property("name", name)
+    }
+
+    /// Matches records with a null value
+    pub fn record_is_tombstone() -> Self {
+        Self::new("org.apache.kafka.connect.transforms.predicates.RecordIsTombstone")
+    }
+}
+
+/// Ordered chain of transforms, along with the predicates they refer to
+#[derive(Clone, Debug, Default)]
+pub struct TransformChain {
+    transforms: Vec<(String, Transform)>,
+    predicates: Vec<(String, Predicate)>,
+}
+
+fn validate_alias(kind: &str, alias: &str) -> Result<()> {
+    if alias.is_empty() || alias.contains(',') || alias.contains(char::is_whitespace) {
+        anyhow::bail!("Invalid {} alias {:?}", kind, alias);
+    }
+    Ok(())
+}
+
+impl TransformChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a transform. Adding an alias twice replaces the previous transform
+    pub fn add(mut self, alias: impl Into<String>, transform: Transform) -> Self {
+        let alias = alias.into();
+        match self.transforms.iter_mut().find(|(name, _)| *name == alias) {
+            Some((_, existing)) => *existing = transform,
+            None => self.transforms.push((alias, transform)),
+        }
+        self
+    }
+
+    /// Defines a predicate. Defining an alias twice replaces the previous predicate
+    pub fn predicate(mut self, alias: impl Into<String>, predicate: Predicate) -> Self {
+        let alias = alias.into();
+        match self.predicates.iter_mut().find(|(name, _)| *name == alias) {
+            Some((_, existing)) => *existing = predicate,
+            None => self.predicates.push((alias, predicate)),
+        }
+        self
+    }
+
+    /// Appends the transforms and predicates of another chain, its aliases replacing the ones
+    /// already defined
+    pub fn extend(mut self, other: TransformChain) -> Self {
+        for (alias, predicate) in other.predicates {
+            self = self.predicate(alias, predicate);
+        }
+        for (alias, transform) in other.transforms {
+            self = self.add(alias, transform);
+        }
+        self
+    }
+
+    pub fn aliases(&self) -> impl Iterator<Item = &str> {
+        self.transforms.iter().map(|(alias, _)| alias.as_str())
+    }
+
+    /// Merges the chain into a configuration.
+    /// Transforms already configured are kept, and come before the ones of the chain.
+    /// When an alias is already present its previous keys are dropped
+    pub fn apply_to(&self, config: &mut ConnectorConfig) -> Result<()> {
+        let mut predicates = config.get_list(config_keys::PREDICATES).unwrap_or_default();
+        for (alias, _) in &self.predicates {
+            validate_alias("predicate", alias)?;
+            if !predicates.contains(alias) {
+                predicates.push(alias.clone());
+            }
+        }
+        for (alias, transform) in &self.transforms {
+            validate_alias("transform", alias)?;
+            if let Some(predicate) = &transform.predicate {
+                if !predicates.contains(predicate) {
+                    anyhow::bail!(
+                        "Transform {} refers to the undefined predicate {}",
+                        alias,
+                        predicate
+                    );
+                }
+            }
+        }
+        for (alias, predicate) in &self.predicates {
+            let prefix = format!("{}.{}.", config_keys::PREDICATES, alias);
+            config.retain(|key, _| !key.starts_with(&prefix));
+            config.set(predicate_key(alias, "type"), predicate.class.as_str());
+            for (key, value) in &predicate.properties {
+                config.set(predicate_key(alias, key), value.as_str());
+            }
+        }
+        if !predicates.is_empty() {
+            config.set_list(config_keys::PREDICATES, predicates);
+        }
+        let mut aliases = config.get_list(config_keys::TRANSFORMS).unwrap_or_default();
+        for (alias, transform) in &self.transforms {
+            let prefix = format!("{}.{}.", config_keys::TRANSFORMS, alias);
+            config.retain(|key, _| !key.starts_with(&prefix));
+            config.set(transform_key(alias, "type"), transform.class.as_str());
+            for (key, value) in &transform.properties {
+                config.set(transform_key(alias, key), value.as_str());
+            }
+            if let Some(predicate) = &transform.predicate {
+                config
+                    .set(transform_key(alias, "predicate"), predicate.as_str())
+                    .set_bool(transform_key(alias, "negate"), transform.negate);
+            }
+            if !aliases.contains(alias) {
+                aliases.push(alias.clone());
+            }
+        }
+        if !aliases.is_empty() {
+            config.set_list(config_keys::TRANSFORMS, aliases);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+//! Secrets read from the KV version 2 engine of HashiCorp Vault
+//! Only available with the `vault` feature
+//!
+//! Credentials of the cluster can be provided from a secret, and configuration values such as
+//! `vault:database/orders#password` are replaced with the key of the secret at that path before
+//! applying, so that manifests only hold references to secrets
+use crate::apply::{ApplyOutcome, ConnectorSpec};
+use crate::config::ConnectorConfig;
+use crate::config_providers::{ConfigResolver, ProviderReference};
+use crate::credentials::{Credentials, CredentialsProvider};
+use crate::{json, Connect};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const PLACEHOLDER_PREFIX: &str = "vault:";
+
+/// How the client logs into Vault
+#[derive(Clone)]
+pub enum VaultAuth {
+    Token(String),
+    AppRole {
+        role_id: String,
+        secret_id: String,
+    },
+    /// Login with the service account token of a pod
+    Kubernetes {
+        role: String,
+        jwt: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct Login {
+    auth: LoginAuth,
+}
+
+#[derive(Deserialize)]
+struct LoginAuth {
+    client_token: String,
+}
+
+#[derive(Deserialize)]
+struct Secret {
+    data: SecretData,
+}
+
+#[derive(Deserialize)]
+struct SecretData {
+    data: HashMap<String, Value>,
+}
+
+/// Client of the KV version 2 engine of Vault
+pub struct Vault {
+    client: Client,
+    address: String,
+    mount: String,
+    namespace: Option<String>,
+    auth: VaultAuth,
+    token: Mutex<Option<String>>,
+    max_response_size: Option<usize>,
+}
+
+impl Vault {
+    /// Reads secrets from the engine mounted at `secret`
+    pub fn new(address: &str, auth: VaultAuth) -> Self {
+        let token = match &auth {
+            VaultAuth::Token(token) => Some(token.clone()),
+            _ => None,
+        };
+        Self {
+            client: Client::new(),
+            address: address.trim_end_matches('/').to_string(),
+            mount: "secret".to_string(),
+            namespace: None,
+            auth,
+            token: Mutex::new(token),
+            max_response_size: None,
+        }
+    }
+
+    /// Reads `VAULT_ADDR` and `VAULT_TOKEN`
+    pub fn from_env() -> Result<Self> {
+        let address = std::env::var("VAULT_ADDR").context("VAULT_ADDR is not set")?;
+        let token = std::env::var("VAULT_TOKEN").context("VAULT_TOKEN is not set")?;
+        let mut vault = Self::new(&address, VaultAuth::Token(token));
+        if let Ok(namespace) = std::env::var("VAULT_NAMESPACE") {
+            vault = vault.namespace(&namespace);
+        }
+        Ok(vault)
+    }
+
+    /// Path the KV engine is mounted at
+    pub fn mount(mut self, mount: &str) -> Self {
+        self.mount = mount.trim_matches('/').to_string();
+        self
+    }
+
+    /// Namespace of Vault Enterprise
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// Fails logins and reads whose body exceeds the given number of bytes
+    pub fn max_response_size(mut self, bytes: usize) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    fn request(&self, builder: RequestBuilder) -> RequestBuilder {
+        match &self.namespace {
+            Some(namespace) => builder.header("X-Vault-Namespace", namespace),
+            None => builder,
+        }
+    }
+
+    async fn login(&self) -> Result<String> {
+        let (method, body) = match &self.auth {
+            VaultAuth::Token(token) => return Ok(token.clone()),
+            VaultAuth::AppRole { role_id, secret_id } => (
+                "approle",
+                serde_json::json!({ "role_id": role_id, "secret_id": secret_id }),
+            ),
+            VaultAuth::Kubernetes { role, jwt } => (
+                "kubernetes",
+                serde_json::json!({ "role": role, "jwt": jwt }),
+            ),
+        };
+        let url = format!("{}/v1/auth/{}/login", self.address, method);
+        let response = self
+            .request(self.client.post(url))
+            .json(&body)
+            .send()
+            .await?;
+        let status_code = response.status();
+        if !status_code.is_success() {
+            anyhow::bail!("Vault answered {} to the {} login", status_code, method);
+        }
+        let login: Login = json::from_response(response, self.max_response_size).await?;
+        Ok(login.auth.client_token)
+    }
+
+    async fn token(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some(token) = token.as_ref() {
+            return Ok(token.clone());
+        }
+        let fresh = self.login().await?;
+        *token = Some(fresh.clone());
+        Ok(fresh)
+    }
+
+    /// Keys of the latest version of the secret at the path
+    pub async fn read_secret(&self, path: &str) -> Result<HashMap<String, String>> {
+        let url = format!(
+            "{}/v1/{}/data/{}",
+            self.address,
+            self.mount,
+            path.trim_matches('/')
+        );
+        let mut response = self
+            .request(self.client.get(&url))
+            .header("X-Vault-Token", self.token().await?)
+            .send()
+            .await?;
+        // the token of a login may have expired, log in once more
+        if response.status() == StatusCode::FORBIDDEN && !matches!(self.auth, VaultAuth::Token(_)) {
+            self.token.lock().await.take();
+            response = self
+                .request(self.client.get(&url))
+                .header("X-Vault-Token", self.token().await?)
+                .send()
+                .await?;
+        }
+        let status_code = response.status();
+        match status_code {
+            StatusCode::OK => {}
+            StatusCode::NOT_FOUND => anyhow::bail!("Vault has no secret at {}", path),
+            _ => anyhow::bail!("Vault answered {} reading {}", status_code, path),
+        }
+        let secret: Secret = json::from_response(response, self.max_response_size).await?;
+        Ok(secret
+            .data
+            .data
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect())
+    }
+
+    /// Replaces every `vault:<path>#<key>` value of the configuration with the key of the secret
+    pub async fn resolve(&self, config: &ConnectorConfig) -> Result<ConnectorConfig> {
+        let mut secrets: HashMap<&str, HashMap<String, String>> = HashMap::new();
+        let mut resolved = config.clone();
+        for (name, value) in config {
+            let Some(reference) = value.strip_prefix(PLACEHOLDER_PREFIX) else {
+                continue;
+            };
+            let (path, key) = reference.split_once('#').with_context(|| {
+                format!("{} references a secret without a key: {}", name, value)
+            })?;
+            if !secrets.contains_key(path) {
+                secrets.insert(path, self.read_secret(path).await?);
+            }
+            let secret = secrets[path]
+                .get(key)
+                .with_context(|| format!("The secret at {} has no key {}", path, key))?;
+            resolved.set(name.as_str(), secret.as_str());
+        }
+        Ok(resolved)
+    }
+}
+
+/// Basic credentials of the cluster kept in a Vault secret, read again once they are older than
+/// the refresh interval
+pub struct VaultCredentials {
+    vault: Vault,
+    path: String,
+    username_key: String,
+    password_key: String,
+    refresh: Duration,
+    cached: Mutex<Option<(Instant, Credentials)>>,
+}
+
+impl VaultCredentials {
+    /// Reads the `username` and `password` keys of the secret, every 5 minutes
+    pub fn new(vault: Vault, path: &str) -> Self {
+        Self {
+            vault,
+            path: path.to_string(),
+            username_key: "username".to_string(),
+            password_key: "password".to_string(),
+            refresh: Duration::from_secs(300),
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn keys(mut self, username_key: &str, password_key: &str) -> Self {
+        self.username_key = username_key.to_string();
+        self.password_key = password_key.to_string();
+        self
+    }
+
+    pub fn refresh(mut self, refresh: Duration) -> Self {
+        self.refresh = refresh;
+        self
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for VaultCredentials {
+    async fn credentials(&self) -> Result<Option<Credentials>> {
+        let mut cached = self.cached.lock().await;
+        if let Some((read_at, credentials)) = cached.as_ref() {
+            if read_at.elapsed() < self.refresh {
+                return Ok(Some(credentials.clone()));
+            }
+        }
+        let mut secret = self.vault.read_secret(&self.path).await?;
+        let username = secret.remove(&self.username_key).with_context(|| {
+            format!(
+                "The secret at {} has no key {}",
+                self.path, self.username_key
+            )
+        })?;
+        let credentials = Credentials::Basic {
+            username,
+            password: secret.remove(&self.password_key),
+        };
+        *cached = Some((Instant::now(), credentials.clone()));
+        Ok(Some(credentials))
+    }
+}
+
+impl Connect {
+    /// Same as [`Connect::apply`], with the Vault references of the spec resolved first
+    pub async fn apply_with_vault(
+        &self,
+        spec: &ConnectorSpec,
+        vault: &Vault,
+    ) -> Result<ApplyOutcome> {
+        let resolved = ConnectorSpec::new(spec.name.clone(), vault.resolve(&spec.config).await?);
+        self.apply(&resolved).await
+    }
+}
+
+/// Resolves `${vault:path:key}`, the references of the Vault config providers for workers. The
+/// path is relative to the mount of the client
+#[async_trait]
+impl ConfigResolver for Vault {
+    fn provider(&self) -> &str {
+        "vault"
+    }
+
+    async fn resolve(&self, reference: &ProviderReference) -> Result<Option<String>> {
+        let Some(path) = &reference.path else {
+            return Ok(None);
+        };
+        Ok(self.read_secret(path).await?.remove(&reference.key))
+    }
+}
@@ -0,0 +1,217 @@
+//! Recording of HTTP interactions, to replay them in tests without a cluster
+//! Only available with the `vcr` feature
+//!
+//! A [`Vcr`] is added to the client through [`ConnectBuilder::middleware`](crate::ConnectBuilder::middleware).
+//! When recording, every interaction is appended to a json cassette as it happens. When replaying,
+//! requests are answered from the cassette in the recorded order, without reaching the network
+use crate::json;
+use anyhow::Context;
+use async_trait::async_trait;
+use reqwest_middleware::reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// A recorded request and the response it received
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    /// Path and query of the request. The host is left out so that cassettes work against any address
+    pub path: String,
+    pub request_body: Option<String>,
+    pub status: u16,
+    pub response_body: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug)]
+enum Mode {
+    Record,
+    /// Index of the next interaction to replay
+    Replay(usize),
+}
+
+#[derive(Debug)]
+struct State {
+    mode: Mode,
+    cassette: Cassette,
+}
+
+/// Middleware recording or replaying interactions. Clones share the same cassette
+#[derive(Clone, Debug)]
+pub struct Vcr {
+    path: PathBuf,
+    state: Arc<Mutex<State>>,
+    max_response_size: Option<usize>,
+}
+
+impl Vcr {
+    /// Records every interaction to the file, replacing any previous recording
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self::new(path.as_ref(), Mode::Record, Cassette::default())
+    }
+
+    /// Replays the interactions recorded in the file
+    pub fn replay(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read cassette {}", path.display()))?;
+        let cassette = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid cassette {}", path.display()))?;
+        Ok(Self::new(path, Mode::Replay(0), cassette))
+    }
+
+    /// Replays the file when it exists, records it otherwise
+    pub fn auto(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            Self::replay(path)
+        } else {
+            Ok(Self::record(path))
+        }
+    }
+
+    fn new(path: &Path, mode: Mode, cassette: Cassette) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            state: Arc::new(Mutex::new(State { mode, cassette })),
+            max_response_size: None,
+        }
+    }
+
+    /// Fails recordings of responses whose body exceeds the given number of bytes
+    pub fn max_response_size(mut self, bytes: usize) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state.lock().unwrap().mode, Mode::Record)
+    }
+
+    /// Number of recorded interactions that were not replayed yet
+    pub fn remaining(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        match state.mode {
+            Mode::Record => 0,
+            Mode::Replay(next) => state.cassette.interactions.len().saturating_sub(next),
+        }
+    }
+
+    fn save(&self, cassette: &Cassette) -> anyhow::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(cassette)?)
+            .with_context(|| format!("Could not write cassette {}", self.path.display()))
+    }
+
+    fn replay_next(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+    ) -> anyhow::Result<Response> {
+        let mut state = self.state.lock().unwrap();
+        let Mode::Replay(next) = state.mode else {
+            unreachable!("only called when replaying");
+        };
+        let Some(interaction) = state.cassette.interactions.get(next).cloned() else {
+            anyhow::bail!("No recorded interaction left for {} {}", method, path);
+        };
+        if interaction.method != method
+            || interaction.path != path
+            || !same_body(interaction.request_body.as_deref(), body)
+        {
+            anyhow::bail!(
+                "Expected {} {} as interaction {}, got {} {}",
+                interaction.method,
+                interaction.path,
+                next,
+                method,
+                path
+            );
+        }
+        state.mode = Mode::Replay(next + 1);
+        let response = http::Response::builder()
+            .status(interaction.status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(interaction.response_body)?;
+        Ok(Response::from(response))
+    }
+}
+
+/// Json bodies are compared as values, since the order of their keys may change between runs
+fn same_body(recorded: Option<&str>, body: Option<&str>) -> bool {
+    match (recorded, body) {
+        (Some(recorded), Some(body)) => {
+            match (
+                serde_json::from_str::<serde_json::Value>(recorded),
+                serde_json::from_str::<serde_json::Value>(body),
+            ) {
+                (Ok(recorded), Ok(body)) => recorded == body,
+                _ => recorded == body,
+            }
+        }
+        (recorded, body) => recorded == body,
+    }
+}
+
+/// Path and query of the url, which is what interactions are matched on
+fn path_of(request: &Request) -> String {
+    let url = request.url();
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+#[async_trait]
+impl Middleware for Vcr {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().to_string();
+        let path = path_of(&req);
+        let request_body = req
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned());
+        if !self.is_recording() {
+            return Ok(self.replay_next(&method, &path, request_body.as_deref())?);
+        }
+        let response = next.run(req, extensions).await?;
+        let status = response.status();
+        let response_body =
+            String::from_utf8_lossy(&json::read_body(response, self.max_response_size).await?)
+                .into_owned();
+        let interaction = Interaction {
+            method,
+            path,
+            request_body,
+            status: status.as_u16(),
+            response_body: response_body.clone(),
+        };
+        let cassette = {
+            let mut state = self.state.lock().unwrap();
+            state.cassette.interactions.push(interaction);
+            state.cassette.clone()
+        };
+        self.save(&cassette)?;
+        let response = http::Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(response_body)
+            .map_err(anyhow::Error::from)?;
+        Ok(Response::from(response))
+    }
+}
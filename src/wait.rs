@@ -0,0 +1,168 @@
+//! State changes that wait for the cluster to carry them out
+//! Workers accept a pause, a resume or a stop as soon as it is recorded and carry it out on the
+//! tasks afterwards, while the tasks may still be writing. The offsets endpoints refuse connectors
+//! whose tasks are still around, so callers poll the status until the change took effect.
+//! Deletes are processed the same way, and a deleted connector may keep answering its status
+//! for a while during a rebalance. In a dry run the change is only recorded, so the helpers
+//! return at once as if it took effect
+use crate::error::ConnectError;
+use crate::models::{ConnectorStatus, Status};
+use crate::Connect;
+use anyhow::Result;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Time between two reads of the status
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Outcome of waiting for a connector and its tasks to reach a state
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Transition {
+    pub target: Status,
+    /// Whether the connector itself reached the target
+    pub connector_reached: bool,
+    /// Tasks that had not reached the target when the wait ended
+    pub lagging_tasks: Vec<u64>,
+}
+
+impl Transition {
+    fn new(target: Status, status: &ConnectorStatus) -> Self {
+        Self {
+            target,
+            connector_reached: status.connector.state == target,
+            lagging_tasks: status
+                .tasks
+                .iter()
+                .filter(|task| task.state != target)
+                .map(|task| task.id)
+                .collect(),
+        }
+    }
+
+    /// The change took effect everywhere, as assumed in a dry run
+    fn completed(target: Status) -> Self {
+        Self {
+            target,
+            connector_reached: true,
+            lagging_tasks: Vec::new(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.connector_reached && self.lagging_tasks.is_empty()
+    }
+}
+
+impl Connect {
+    /// Pauses the connector and waits until it and all of its tasks are PAUSED, or the timeout
+    pub async fn pause_and_wait(&self, connector: &str, timeout: Duration) -> Result<Transition> {
+        self.pause_connector(connector).await?;
+        if self.dry_run.is_some() {
+            return Ok(Transition::completed(Status::Paused));
+        }
+        self.wait_for_state(connector, Status::Paused, timeout)
+            .await
+    }
+
+    /// Resumes the connector and waits until it and all of its tasks are RUNNING, or the timeout
+    pub async fn resume_and_wait(&self, connector: &str, timeout: Duration) -> Result<Transition> {
+        self.resume_connector(connector).await?;
+        if self.dry_run.is_some() {
+            return Ok(Transition::completed(Status::Running));
+        }
+        self.wait_for_state(connector, Status::Running, timeout)
+            .await
+    }
+
+    /// Waits until the connector and all of its tasks are in the state, or the timeout. A timeout
+    /// is not an error, the transition tells what lagged
+    pub async fn wait_for_state(
+        &self,
+        connector: &str,
+        target: Status,
+        timeout: Duration,
+    ) -> Result<Transition> {
+        let (status, _) = self
+            .poll_status(connector, timeout, |status| {
+                Transition::new(target, status).is_complete()
+            })
+            .await?;
+        Ok(Transition::new(target, &status))
+    }
+
+    /// Stops the connector and waits until it is STOPPED and all of its tasks are torn down
+    pub async fn stop_and_wait(&self, connector: &str, timeout: Duration) -> Result<()> {
+        self.stop_connector(connector).await?;
+        if self.dry_run.is_some() {
+            return Ok(());
+        }
+        let stopped = |status: &ConnectorStatus| {
+            status.connector.state == Status::Stopped && status.tasks.is_empty()
+        };
+        let (_, done) = self.poll_status(connector, timeout, stopped).await?;
+        if !done {
+            anyhow::bail!("Connector {} did not stop within {:?}", connector, timeout);
+        }
+        Ok(())
+    }
+
+    /// Deletes the connector and waits until neither the listing nor the status know it anymore
+    pub async fn delete_and_wait(&self, connector: &str, timeout: Duration) -> Result<()> {
+        self.delete_connector(connector).await?;
+        if self.dry_run.is_some() {
+            return Ok(());
+        }
+        let started = Instant::now();
+        loop {
+            if !self
+                .connector_names()
+                .await?
+                .iter()
+                .any(|name| name == connector)
+            {
+                match self.connector_status(connector).await {
+                    Err(err)
+                        if matches!(
+                            err.downcast_ref(),
+                            Some(ConnectError::ConnectorNotFound(_))
+                        ) =>
+                    {
+                        return Ok(())
+                    }
+                    Err(err) => return Err(err),
+                    // still running somewhere, not yet deleted
+                    Ok(_) => {}
+                }
+            }
+            if started.elapsed() + POLL_INTERVAL > timeout {
+                anyhow::bail!(
+                    "Connector {} was not deleted within {:?}",
+                    connector,
+                    timeout
+                );
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Reads the status until `done` holds or the timeout elapsed, returning the last status and
+    /// whether `done` held
+    async fn poll_status(
+        &self,
+        connector: &str,
+        timeout: Duration,
+        done: impl Fn(&ConnectorStatus) -> bool,
+    ) -> Result<(ConnectorStatus, bool)> {
+        let started = Instant::now();
+        loop {
+            let status = self.connector_status(connector).await?;
+            if done(&status) {
+                return Ok((status, true));
+            }
+            if started.elapsed() + POLL_INTERVAL > timeout {
+                return Ok((status, false));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Watching a cluster for changes
+//! The watcher polls the expanded statuses of every connector and reports what changed between polls
+use crate::filter::ConnectorFilter;
+use crate::models::{Connector, ConnectorStatus, Status};
+use crate::Connect;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// A change observed between two polls
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WatchEvent {
+    Added {
+        name: String,
+        state: Status,
+    },
+    Removed {
+        name: String,
+    },
+    StateChanged {
+        name: String,
+        from: Status,
+        to: Status,
+    },
+    /// A task changed state. `from` is None for tasks that did not exist before
+    TaskStateChanged {
+        name: String,
+        task: u64,
+        from: Option<Status>,
+        to: Status,
+    },
+}
+
+impl WatchEvent {
+    /// Name of the connector the event refers to
+    pub fn connector(&self) -> &str {
+        match self {
+            Self::Added { name, .. }
+            | Self::Removed { name }
+            | Self::StateChanged { name, .. }
+            | Self::TaskStateChanged { name, .. } => name,
+        }
+    }
+}
+
+/// Result of a single poll
+#[derive(Clone, Debug)]
+pub struct WatchUpdate {
+    pub statuses: BTreeMap<String, ConnectorStatus>,
+    pub events: Vec<WatchEvent>,
+}
+
+/// Poll intervals of a watcher adapting to the activity of the cluster
+#[derive(Clone, Copy, Debug)]
+pub struct AdaptiveInterval {
+    /// Interval while a connector or task is restarting or unassigned, or shortly after a change
+    pub fast: Duration,
+    /// Interval once the cluster is stable
+    pub slow: Duration,
+    /// How long to keep polling fast after the last change or mutation
+    pub settle: Duration,
+}
+
+impl Default for AdaptiveInterval {
+    fn default() -> Self {
+        Self {
+            fast: Duration::from_secs(1),
+            slow: Duration::from_secs(30),
+            settle: Duration::from_secs(30),
+        }
+    }
+}
+
+pub struct Watcher {
+    client: Connect,
+    interval: Duration,
+    adaptive: Option<AdaptiveInterval>,
+    /// Polling stays fast until then
+    active_until: Option<Instant>,
+    transitioning: bool,
+    previous: Option<BTreeMap<String, ConnectorStatus>>,
+    filter: ConnectorFilter,
+}
+
+impl Watcher {
+    pub fn new(client: Connect, interval: Duration) -> Self {
+        Self {
+            client,
+            interval,
+            adaptive: None,
+            active_until: None,
+            transitioning: false,
+            previous: None,
+            filter: ConnectorFilter::new(),
+        }
+    }
+
+    /// Watcher polling fast while the cluster is changing, and slowly once it is stable
+    pub fn adaptive(client: Connect, intervals: AdaptiveInterval) -> Self {
+        Self {
+            adaptive: Some(intervals),
+            ..Self::new(client, intervals.slow)
+        }
+    }
+
+    /// Only watches the connectors selected by the filter
+    pub fn filter(mut self, filter: ConnectorFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Polls fast for a while, so that the effect of a change made to the cluster is seen quickly
+    pub fn notify_mutation(&mut self) {
+        if let Some(adaptive) = self.adaptive {
+            self.active_until = Some(Instant::now() + adaptive.settle);
+        }
+    }
+
+    /// Time the next call to [`Watcher::next`] waits before polling
+    pub fn current_interval(&self) -> Duration {
+        let Some(adaptive) = self.adaptive else {
+            return self.interval;
+        };
+        let settling = self
+            .active_until
+            .is_some_and(|until| Instant::now() < until);
+        if self.transitioning || settling {
+            adaptive.fast
+        } else {
+            adaptive.slow
+        }
+    }
+
+    /// Waits for the next poll and returns the current statuses along with the changes.
+    /// The first call returns immediately, reporting every connector as added
+    pub async fn next(&mut self) -> Result<WatchUpdate> {
+        if self.previous.is_some() {
+            tokio::time::sleep(self.current_interval()).await;
+        }
+        let (_, expand_info) = self.filter.expand();
+        let mut connectors = self.client.connectors(true, expand_info).await?;
+        connectors.retain(|name, connector| self.filter.matches(name, connector));
+        let statuses = statuses_of(connectors);
+        let events = match &self.previous {
+            Some(previous) => diff(previous, &statuses),
+            None => diff(&BTreeMap::new(), &statuses),
+        };
+        // the first poll reports every connector as added, which is not a change of the cluster
+        if self.previous.is_some() && !events.is_empty() {
+            self.notify_mutation();
+        }
+        self.transitioning = statuses.values().any(is_transitioning);
+        self.previous = Some(statuses.clone());
+        Ok(WatchUpdate { statuses, events })
+    }
+}
+
+fn statuses_of(connectors: HashMap<String, Connector>) -> BTreeMap<String, ConnectorStatus> {
+    connectors
+        .into_iter()
+        .filter_map(|(name, connector)| connector.status.map(|status| (name, status)))
+        .collect()
+}
+
+fn is_transitioning(status: &ConnectorStatus) -> bool {
+    std::iter::once(status.connector.state)
+        .chain(status.tasks.iter().map(|task| task.state))
+        .any(|state| !state.is_terminal())
+}
+
+/// Computes the events leading from one set of statuses to the other
+pub fn diff(
+    previous: &BTreeMap<String, ConnectorStatus>,
+    current: &BTreeMap<String, ConnectorStatus>,
+) -> Vec<WatchEvent> {
+    let mut events = Vec::new();
+    for (name, status) in current {
+        let Some(before) = previous.get(name) else {
+            events.push(WatchEvent::Added {
+                name: name.clone(),
+                state: status.connector.state,
+            });
+            continue;
+        };
+        if before.connector.state != status.connector.state {
+            events.push(WatchEvent::StateChanged {
+                name: name.clone(),
+                from: before.connector.state,
+                to: status.connector.state,
+            });
+        }
+        for task in &status.tasks {
+            let from = before
+                .tasks
+                .iter()
+                .find(|previous| previous.id == task.id)
+                .map(|previous| previous.state);
+            if from != Some(task.state) {
+                events.push(WatchEvent::TaskStateChanged {
+                    name: name.clone(),
+                    task: task.id,
+                    from,
+                    to: task.state,
+                });
+            }
+        }
+    }
+    for name in previous.keys() {
+        if !current.contains_key(name) {
+            events.push(WatchEvent::Removed { name: name.clone() });
+        }
+    }
+    events
+}
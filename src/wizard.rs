@@ -0,0 +1,97 @@
+//! Step by step construction of a connector configuration, driven by the validation endpoint
+//! Every step validates the configuration so far and asks for the next key that is missing or invalid
+use crate::config::ConnectorConfig;
+use crate::config_keys;
+use crate::models::ConfigInfo;
+use crate::Connect;
+use anyhow::Result;
+use serde::Serialize;
+
+/// A key the configuration needs a value for
+#[derive(Clone, Debug, Serialize)]
+pub struct Prompt {
+    pub key: String,
+    pub display_name: String,
+    pub documentation: Option<String>,
+    /// Type of the value, such as STRING, INT or BOOLEAN
+    pub kind: String,
+    pub required: bool,
+    pub default_value: Option<String>,
+    /// Values suggested by the plugin, if it knows the valid choices
+    pub recommended_values: Vec<String>,
+    /// Errors reported for the current value
+    pub errors: Vec<String>,
+    /// Value currently set, if any
+    pub value: Option<String>,
+}
+
+impl Prompt {
+    fn from_info(info: &ConfigInfo) -> Self {
+        Self {
+            key: info.definition.name.clone(),
+            display_name: info.definition.display_name.clone(),
+            documentation: info.definition.documentation.clone(),
+            kind: info.definition.kind.clone(),
+            required: info.definition.required,
+            default_value: info.definition.default_value.clone(),
+            recommended_values: info.value.recommended_values.clone(),
+            errors: info.value.errors.clone(),
+            value: info.value.value.clone(),
+        }
+    }
+}
+
+pub struct ConfigWizard<'a> {
+    client: &'a Connect,
+    config: ConnectorConfig,
+}
+
+impl<'a> ConfigWizard<'a> {
+    pub fn new(client: &'a Connect, name: &str, class: &str) -> Self {
+        let mut config = ConnectorConfig::default();
+        config.set(config_keys::NAME, name);
+        config.set(config_keys::CONNECTOR_CLASS, class);
+        Self::with_config(client, config)
+    }
+
+    /// Starts from an existing configuration, which must set the connector class
+    pub fn with_config(client: &'a Connect, config: ConnectorConfig) -> Self {
+        Self { client, config }
+    }
+
+    /// Validates the configuration, and returns the next key to fill in, or None once it is valid
+    pub async fn next(&self) -> Result<Option<Prompt>> {
+        let validation = self.client.validate_connector_config(&self.config).await?;
+        let prompt = validation
+            .configs
+            .iter()
+            .filter(|info| info.value.visible)
+            .find(|info| {
+                let missing = info.definition.required
+                    && info.value.value.is_none()
+                    && info.definition.default_value.is_none();
+                missing || !info.value.errors.is_empty()
+            })
+            .map(Prompt::from_info);
+        if prompt.is_none() && !validation.is_valid() {
+            // errors on keys hidden from the user cannot be fixed by prompting
+            anyhow::bail!(
+                "The configuration has {} errors on keys that cannot be prompted for",
+                validation.error_count
+            );
+        }
+        Ok(prompt)
+    }
+
+    pub fn answer(&mut self, key: &str, value: &str) {
+        self.config.set(key, value);
+    }
+
+    pub fn config(&self) -> &ConnectorConfig {
+        &self.config
+    }
+
+    pub fn into_config(self) -> ConnectorConfig {
+        self.config
+    }
+}
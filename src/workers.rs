@@ -0,0 +1,83 @@
+//! Distribution of connectors and tasks across the workers of a cluster
+//! Workers are only known through the statuses, so a worker running nothing does not appear.
+//! After a rebalance, the difference in tasks between the busiest and the idlest worker should
+//! not exceed one
+use crate::models::{ConnectorStatus, Status};
+use crate::Connect;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// What a worker runs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct WorkerLoad {
+    pub connectors: usize,
+    pub tasks: usize,
+    pub failed_tasks: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct WorkerReport {
+    pub workers: BTreeMap<String, WorkerLoad>,
+    /// Tasks waiting for a worker
+    pub unassigned_tasks: usize,
+    /// Difference in tasks between the busiest and the idlest worker
+    pub skew: usize,
+    /// Whether the skew exceeds the threshold
+    pub skewed: bool,
+}
+
+impl WorkerReport {
+    /// Report of the statuses, flagging a skew beyond `max_skew` tasks
+    pub fn new<'a>(
+        statuses: impl IntoIterator<Item = &'a ConnectorStatus>,
+        max_skew: usize,
+    ) -> Self {
+        let mut report = Self::default();
+        for status in statuses {
+            if status.connector.state != Status::Unassigned {
+                report
+                    .workers
+                    .entry(status.connector.worker_id.clone())
+                    .or_default()
+                    .connectors += 1;
+            }
+            for task in &status.tasks {
+                if task.state == Status::Unassigned {
+                    report.unassigned_tasks += 1;
+                    continue;
+                }
+                let load = report.workers.entry(task.worker_id.clone()).or_default();
+                load.tasks += 1;
+                if task.state == Status::Failed {
+                    load.failed_tasks += 1;
+                }
+            }
+        }
+        let tasks = report.workers.values().map(|load| load.tasks);
+        report.skew = tasks.clone().max().unwrap_or_default() - tasks.min().unwrap_or_default();
+        report.skewed = report.skew > max_skew;
+        report
+    }
+
+    /// Workers running the fewest tasks
+    pub fn idlest_workers(&self) -> Vec<&str> {
+        let fewest = self.workers.values().map(|load| load.tasks).min();
+        self.workers
+            .iter()
+            .filter(|(_, load)| Some(load.tasks) == fewest)
+            .map(|(worker, _)| worker.as_str())
+            .collect()
+    }
+}
+
+impl Connect {
+    /// Reports how connectors and tasks are spread across workers, see [`WorkerReport::new`]
+    pub async fn worker_report(&self, max_skew: usize) -> Result<WorkerReport> {
+        let connectors = self.connectors(true, false).await?;
+        let statuses = connectors
+            .values()
+            .filter_map(|connector| connector.status.as_ref());
+        Ok(WorkerReport::new(statuses, max_skew))
+    }
+}